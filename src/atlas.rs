@@ -0,0 +1,276 @@
+//! Packing of glyph bitmaps into texture atlas pages.
+
+use crate::{font::GlyphIdx, layout::advance, CharSet, Font, Glyph};
+use std::cmp::Reverse;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum AtlasError {
+	#[error("Glyph {0} does not fit into the atlas")]
+	GlyphTooLarge(u32),
+
+	#[error("The glyphs do not fit into an atlas of {0}x{1} pixels")]
+	AtlasFull(u32, u32)
+}
+
+/// Options for packing glyphs into an [`Atlas`].
+#[derive(Clone, Copy, Debug)]
+pub struct AtlasOptions {
+	/// The width of the atlas in pixels.
+	pub width: u32,
+
+	/// The height of the atlas in pixels.
+	pub height: u32,
+
+	/// The number of empty pixels between glyphs and around the border of the atlas,
+	/// which avoids bleeding when the texture is sampled with filtering.
+	pub padding: u32,
+
+	/// Whether the atlas may grow if the glyphs do not fit. Growing alternately
+	/// doubles the width and the height, up to `max_size` pixels each.
+	pub grow: bool,
+
+	/// The maximum width and height of a growing atlas.
+	pub max_size: u32
+}
+
+impl Default for AtlasOptions {
+	fn default() -> Self {
+		Self {
+			width: 256,
+			height: 256,
+			padding: 1,
+			grow: false,
+			max_size: 4096
+		}
+	}
+}
+
+/// A rectangle in texture coordinates, ranging from 0 to 1 with the origin in the top
+/// left corner of the atlas.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct UvRect {
+	pub u_min: f32,
+	pub v_min: f32,
+	pub u_max: f32,
+	pub v_max: f32
+}
+
+/// A glyph placed in an [`Atlas`].
+#[derive(Clone, Copy, Debug)]
+pub struct AtlasGlyph<'a> {
+	pub glyph: &'a Glyph,
+
+	/// The column of the top left corner of the glyph bitmap in the atlas.
+	pub x: u32,
+
+	/// The row of the top left corner of the glyph bitmap in the atlas.
+	pub y: u32,
+
+	/// The width of the glyph bitmap in pixels.
+	pub width: u32,
+
+	/// The height of the glyph bitmap in pixels.
+	pub height: u32,
+
+	/// The texture coordinates of the glyph bitmap.
+	pub uv: UvRect,
+
+	/// The horizontal distance from the pen position to the left edge of the bitmap.
+	pub bearing_x: i32,
+
+	/// The vertical distance from the baseline up to the top edge of the bitmap.
+	pub bearing_y: i32,
+
+	/// The horizontal distance the pen moves after drawing the glyph.
+	pub advance: i32
+}
+
+/// Glyph bitmaps of a [`Font`] packed into a single texture.
+#[derive(Clone, Debug)]
+pub struct Atlas<'a> {
+	width: u32,
+	height: u32,
+	glyphs: Vec<AtlasGlyph<'a>>
+}
+
+impl<'a> Atlas<'a> {
+	/// Get the width of the atlas in pixels.
+	pub fn width(&self) -> u32 {
+		self.width
+	}
+
+	/// Get the height of the atlas in pixels.
+	pub fn height(&self) -> u32 {
+		self.height
+	}
+
+	/// Get the placed glyphs, ordered by their encoding.
+	pub fn glyphs(&self) -> &[AtlasGlyph<'a>] {
+		&self.glyphs
+	}
+
+	/// Get the placement of a glyph.
+	pub fn glyph<I: GlyphIdx>(&self, ch: I) -> Option<&AtlasGlyph<'a>> {
+		let enc = ch.encoding();
+		self.glyphs
+			.binary_search_by_key(&enc, |ag| ag.glyph.encoding)
+			.ok()
+			.map(|idx| &self.glyphs[idx])
+	}
+
+	/// Render the atlas into an 8-bit alpha buffer, stored row by row from the top
+	/// left corner. Set pixels have the value 255, all other pixels are 0.
+	pub fn render(&self) -> Vec<u8> {
+		let width = self.width as usize;
+		let mut buf = vec![0u8; width * self.height as usize];
+		for ag in &self.glyphs {
+			let bitmap = ag.glyph.bitmap();
+			for y in 0 .. bitmap.height() {
+				for x in 0 .. bitmap.width() {
+					if bitmap.get(x, y).unwrap_or(false) {
+						buf[(ag.y as usize + y) * width + ag.x as usize + x] = 0xFF;
+					}
+				}
+			}
+		}
+		buf
+	}
+}
+
+impl Font {
+	/// Pack the bitmaps of all glyphs of this font into an [`Atlas`].
+	pub fn atlas(&self, options: &AtlasOptions) -> Result<Atlas<'_>, AtlasError> {
+		build(self.glyphs().into_iter().collect(), options)
+	}
+
+	/// Pack the bitmaps of the glyphs selected by `chars` into an [`Atlas`].
+	pub fn atlas_subset(
+		&self,
+		chars: &CharSet,
+		options: &AtlasOptions
+	) -> Result<Atlas<'_>, AtlasError> {
+		let glyphs = self
+			.glyphs()
+			.into_iter()
+			.filter(|glyph| chars.contains(glyph))
+			.collect();
+		build(glyphs, options)
+	}
+}
+
+fn build<'a>(
+	glyphs: Vec<&'a Glyph>,
+	options: &AtlasOptions
+) -> Result<Atlas<'a>, AtlasError> {
+	let sizes: Vec<(u32, u32)> = glyphs
+		.iter()
+		.map(|glyph| (glyph.bbox.width, glyph.bbox.height))
+		.collect();
+	let (mut width, mut height) = (options.width, options.height);
+	let placements = loop {
+		let err = match pack(&sizes, width, height, options.padding) {
+			Ok((placements, 1)) => break placements,
+			Ok(_) => AtlasError::AtlasFull(width, height),
+			Err(idx) => AtlasError::GlyphTooLarge(glyphs[idx].encoding)
+		};
+		let grown = match width <= height {
+			true => (width.saturating_mul(2), height),
+			false => (width, height.saturating_mul(2))
+		};
+		if !options.grow
+			|| grown.0.max(grown.1) > options.max_size
+			|| grown == (width, height)
+		{
+			return Err(err);
+		}
+		(width, height) = grown;
+	};
+
+	let glyphs = glyphs
+		.into_iter()
+		.zip(placements)
+		.map(|(glyph, placement)| {
+			let bbox = glyph.bbox;
+			AtlasGlyph {
+				glyph,
+				x: placement.x,
+				y: placement.y,
+				width: bbox.width,
+				height: bbox.height,
+				uv: UvRect {
+					u_min: placement.x as f32 / width as f32,
+					v_min: placement.y as f32 / height as f32,
+					u_max: (placement.x + bbox.width) as f32 / width as f32,
+					v_max: (placement.y + bbox.height) as f32 / height as f32
+				},
+				bearing_x: bbox.offset_x,
+				bearing_y: bbox.offset_y + bbox.height as i32,
+				advance: advance(glyph)
+			}
+		})
+		.collect();
+	Ok(Atlas {
+		width,
+		height,
+		glyphs
+	})
+}
+
+/// The position of a rectangle in an atlas.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) struct Placement {
+	pub(crate) page: usize,
+	pub(crate) x: u32,
+	pub(crate) y: u32
+}
+
+/// Pack rectangles of the given sizes into pages of a fixed size using a shelf
+/// packer, keeping `padding` empty pixels between rectangles and around the border
+/// of each page. Empty rectangles are placed at the origin of the first page.
+///
+/// Returns the placement of each rectangle together with the number of pages, which
+/// is at least one, or the index of the first rectangle that does not fit into an
+/// empty page.
+pub(crate) fn pack(
+	sizes: &[(u32, u32)],
+	page_width: u32,
+	page_height: u32,
+	padding: u32
+) -> Result<(Vec<Placement>, usize), usize> {
+	let fits = |(width, height): (u32, u32)| {
+		width + 2 * padding <= page_width && height + 2 * padding <= page_height
+	};
+	if let Some(idx) = sizes
+		.iter()
+		.position(|&(w, h)| w > 0 && h > 0 && !fits((w, h)))
+	{
+		return Err(idx);
+	}
+
+	// tall rectangles first, so that each shelf wastes as little space as possible
+	let mut order: Vec<usize> = (0 .. sizes.len())
+		.filter(|idx| sizes[*idx].0 > 0 && sizes[*idx].1 > 0)
+		.collect();
+	order.sort_by_key(|idx| Reverse((sizes[*idx].1, sizes[*idx].0)));
+
+	let mut placements = vec![Placement::default(); sizes.len()];
+	let mut page = 0;
+	let (mut x, mut y, mut shelf_height) = (padding, padding, 0);
+	for idx in order {
+		let (width, height) = sizes[idx];
+		if x + width + padding > page_width {
+			x = padding;
+			y += shelf_height + padding;
+			shelf_height = 0;
+		}
+		if y + height + padding > page_height {
+			page += 1;
+			(x, y, shelf_height) = (padding, padding, 0);
+		}
+		placements[idx] = Placement { page, x, y };
+		x += width + padding;
+		shelf_height = shelf_height.max(height);
+	}
+	Ok((placements, page + 1))
+}
@@ -26,6 +26,11 @@ impl Bitmap<'_> {
 		(self.bbox.height as i32 - 1 + self.bbox.offset_y) as usize
 	}
 
+	/// Get the bounding box of this bitmap, relative to its origin.
+	pub fn bounding_box(self) -> BoundingBox {
+		self.bbox
+	}
+
 	pub fn get(self, x: usize, y: usize) -> Result<bool, OutOfBounds> {
 		let row = self.data.get(y).ok_or(OutOfBounds(x, y))?;
 		row.get(x).ok_or(OutOfBounds(x, y))
@@ -53,3 +58,67 @@ impl Bitmap<'_> {
 		buf
 	}
 }
+
+/// An owned bitmap, for example the result of rendering a [`Layout`](crate::Layout).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BitmapBuf {
+	pub(crate) data: Vec<BitVec>,
+	pub(crate) bbox: BoundingBox
+}
+
+impl BitmapBuf {
+	/// Create a new bitmap covering the bounding box with all pixels unset.
+	pub fn new(bbox: BoundingBox) -> Self {
+		let row = BitVec::from_elem((bbox.width as usize).div_ceil(8) * 8, false);
+		Self {
+			data: vec![row; bbox.height as usize],
+			bbox
+		}
+	}
+
+	/// Get the bounding box of this bitmap, relative to its origin.
+	pub fn bounding_box(&self) -> BoundingBox {
+		self.bbox
+	}
+
+	/// Borrow this bitmap.
+	pub fn as_bitmap(&self) -> Bitmap<'_> {
+		Bitmap {
+			data: &self.data,
+			bbox: self.bbox
+		}
+	}
+
+	/// Set the pixel in column `x` and row `y`, counted from the top left corner.
+	pub fn set(&mut self, x: usize, y: usize, value: bool) -> Result<(), OutOfBounds> {
+		if x >= self.bbox.width as usize {
+			return Err(OutOfBounds(x, y));
+		}
+		let row = self.data.get_mut(y).ok_or(OutOfBounds(x, y))?;
+		row.set(x, value);
+		Ok(())
+	}
+
+	/// Draw `bitmap` with its origin placed at (`x`, `y`) relative to the origin of this
+	/// bitmap. Set pixels are combined using OR, pixels outside of this bitmap's
+	/// bounding box are clipped.
+	pub fn draw(&mut self, bitmap: Bitmap<'_>, x: i32, y: i32) {
+		let top = self.bbox.offset_y + self.bbox.height as i32 - 1;
+		let src_top = y + bitmap.bbox.offset_y + bitmap.bbox.height as i32 - 1;
+		for sy in 0 .. bitmap.height() {
+			let dy = top - (src_top - sy as i32);
+			if dy < 0 || dy >= self.bbox.height as i32 {
+				continue;
+			}
+			for sx in 0 .. bitmap.width() {
+				let dx = x + bitmap.bbox.offset_x + sx as i32 - self.bbox.offset_x;
+				if dx < 0 || dx >= self.bbox.width as i32 {
+					continue;
+				}
+				if bitmap.get(sx, sy).unwrap_or(false) {
+					self.data[dy as usize].set(dx as usize, true);
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,146 @@
+//! AngelCode BMFont descriptors with glyph atlas pages.
+//!
+//! See <https://www.angelcode.com/products/bmfont/doc/file_format.html> for a
+//! description of the text, XML and binary descriptor formats. The atlas pages are
+//! stored as TGA images, which need no additional dependencies.
+
+use crate::tga::TgaError;
+use std::io;
+use thiserror::Error;
+
+mod reader;
+mod writer;
+
+/// The magic bytes of binary descriptors, followed by the version.
+pub(crate) const BINARY_MAGIC: &[u8; 3] = b"BMF";
+
+/// The version of binary descriptors.
+pub(crate) const BINARY_VERSION: u8 = 3;
+
+pub(crate) const BLOCK_INFO: u8 = 1;
+pub(crate) const BLOCK_COMMON: u8 = 2;
+pub(crate) const BLOCK_PAGES: u8 = 3;
+pub(crate) const BLOCK_CHARS: u8 = 4;
+
+/// The size of a character in the chars block of binary descriptors.
+pub(crate) const CHAR_SIZE: usize = 20;
+
+#[derive(Debug, Error)]
+pub enum BmFontError {
+	#[error("I/O Error: {0}")]
+	IOError(#[from] io::Error),
+
+	#[error("Syntax error in line {0}")]
+	SyntaxError(usize),
+
+	#[error("Unsupported binary descriptor version {0}")]
+	UnsupportedVersion(u8),
+
+	#[error("Unexpected end of file")]
+	UnexpectedEof,
+
+	#[error("Missing {0}")]
+	Missing(&'static str),
+
+	#[error("Unsupported TGA image: {0}")]
+	UnsupportedImage(&'static str),
+
+	#[error("Character {0} is outside of its page")]
+	CharOutsidePage(u32),
+
+	#[error("Glyph {0} does not fit into an atlas page")]
+	GlyphTooLarge(u32),
+
+	#[error("Metrics of glyph {0} exceed the range supported by the format")]
+	MetricsOutOfRange(u32),
+
+	#[error("The {0} exceeds the range supported by the format")]
+	ValueOutOfRange(&'static str)
+}
+
+impl From<TgaError> for BmFontError {
+	fn from(err: TgaError) -> Self {
+		match err {
+			TgaError::UnexpectedEof => Self::UnexpectedEof,
+			TgaError::Unsupported(what) => Self::UnsupportedImage(what)
+		}
+	}
+}
+
+/// The format of a BMFont descriptor.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum BmFontFormat {
+	/// Lines of tags with `key=value` attributes.
+	#[default]
+	Text,
+
+	/// The same tags and attributes as XML elements.
+	Xml,
+
+	/// The compact binary format, version 3.
+	Binary
+}
+
+/// Options for writing BMFont descriptors and atlas pages.
+#[derive(Clone, Copy, Debug)]
+pub struct BmFontOptions {
+	/// The format of the descriptor.
+	pub format: BmFontFormat,
+
+	/// The width of each atlas page in pixels.
+	pub page_width: u32,
+
+	/// The height of each atlas page in pixels.
+	pub page_height: u32,
+
+	/// The number of empty pixels between glyphs and around the border of each page,
+	/// which is stored as the spacing of the font.
+	pub padding: u32
+}
+
+impl Default for BmFontOptions {
+	fn default() -> Self {
+		Self {
+			format: BmFontFormat::Text,
+			page_width: 256,
+			page_height: 256,
+			padding: 1
+		}
+	}
+}
+
+/// The descriptor of a font, independent of its format.
+#[derive(Debug, Default)]
+pub(crate) struct Descriptor {
+	pub(crate) face: String,
+	pub(crate) size: i32,
+	pub(crate) bold: bool,
+	pub(crate) italic: bool,
+	pub(crate) unicode: bool,
+	pub(crate) spacing: (u32, u32),
+	pub(crate) line_height: u32,
+	pub(crate) base: u32,
+	pub(crate) scale_w: u32,
+	pub(crate) scale_h: u32,
+
+	/// Whether the glyphs are stored in the alpha channel of the pages.
+	pub(crate) alpha_glyph: bool,
+
+	pub(crate) pages: Vec<String>,
+	pub(crate) chars: Vec<Char>
+}
+
+/// A character of a descriptor, located on one of its pages.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Char {
+	pub(crate) id: u32,
+	pub(crate) x: u32,
+	pub(crate) y: u32,
+	pub(crate) width: u32,
+	pub(crate) height: u32,
+	pub(crate) xoffset: i32,
+	pub(crate) yoffset: i32,
+	pub(crate) xadvance: i32,
+	pub(crate) page: u32,
+	pub(crate) chnl: u32
+}
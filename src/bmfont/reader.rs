@@ -0,0 +1,416 @@
+use super::*;
+use crate::{
+	font::GlyphWrapper, layout::advance, tga, BoundingBox, Font, Glyph, Size, Value
+};
+use bit_vec::BitVec;
+use std::{
+	collections::{BTreeSet, HashMap},
+	fs,
+	path::Path
+};
+
+/// The magic bytes of PNG images.
+const PNG_MAGIC: &[u8; 8] = b"\x89PNG\r\n\x1a\n";
+
+/// A tag of the text or XML format with its attributes and line number.
+struct Tag {
+	name: String,
+	attrs: HashMap<String, String>,
+	line: usize
+}
+
+impl Tag {
+	fn get<T: std::str::FromStr>(&self, key: &str) -> Result<Option<T>, BmFontError> {
+		self.attrs
+			.get(key)
+			.map(|value| value.trim().parse())
+			.transpose()
+			.map_err(|_| BmFontError::SyntaxError(self.line))
+	}
+
+	fn number(&self, key: &str) -> Result<i64, BmFontError> {
+		Ok(self.get(key)?.unwrap_or(0))
+	}
+
+	fn flag(&self, key: &str) -> Result<bool, BmFontError> {
+		Ok(self.number(key)? != 0)
+	}
+
+	fn string(&self, key: &str) -> String {
+		self.attrs.get(key).cloned().unwrap_or_default()
+	}
+}
+
+/// Parse `key=value` attributes, where values may be quoted.
+fn attributes(
+	mut input: &str,
+	line: usize,
+	unescape: bool
+) -> Result<HashMap<String, String>, BmFontError> {
+	let mut attrs = HashMap::new();
+	loop {
+		input = input.trim_start();
+		if input.is_empty() {
+			return Ok(attrs);
+		}
+		let (key, rest) = input
+			.split_once('=')
+			.ok_or(BmFontError::SyntaxError(line))?;
+		let rest = rest.trim_start();
+		let (value, rest) = match rest.chars().next() {
+			Some(quote @ ('"' | '\'')) => rest[1 ..]
+				.split_once(quote)
+				.ok_or(BmFontError::SyntaxError(line))?,
+			_ => rest.split_once(char::is_whitespace).unwrap_or((rest, ""))
+		};
+		let value = match unescape {
+			true => value
+				.replace("&quot;", "\"")
+				.replace("&apos;", "'")
+				.replace("&lt;", "<")
+				.replace("&gt;", ">")
+				.replace("&amp;", "&"),
+			false => value.to_owned()
+		};
+		attrs.insert(key.trim().to_owned(), value);
+		input = rest;
+	}
+}
+
+/// Parse the tags of the text format, one per line.
+fn text_tags(data: &str) -> Result<Vec<Tag>, BmFontError> {
+	data.lines()
+		.enumerate()
+		.filter(|(_, line)| !line.trim().is_empty())
+		.map(|(idx, line)| {
+			let line_no = idx + 1;
+			let line = line.trim();
+			let (name, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+			Ok(Tag {
+				name: name.to_owned(),
+				attrs: attributes(rest, line_no, false)?,
+				line: line_no
+			})
+		})
+		.collect()
+}
+
+/// Parse the elements of the XML format, ignoring their nesting.
+fn xml_tags(data: &str) -> Result<Vec<Tag>, BmFontError> {
+	let mut tags = Vec::new();
+	let mut rest = data;
+	while let Some(start) = rest.find('<') {
+		let line = data.len() - rest.len() + start;
+		let line = data[.. line].matches('\n').count() + 1;
+		let end = rest[start ..]
+			.find('>')
+			.ok_or(BmFontError::SyntaxError(line))?;
+		let element = &rest[start + 1 .. start + end];
+		rest = &rest[start + end + 1 ..];
+		if element.starts_with(['?', '!', '/']) {
+			continue;
+		}
+		let element = element.trim_end_matches('/');
+		let (name, attrs) = element
+			.split_once(char::is_whitespace)
+			.unwrap_or((element, ""));
+		tags.push(Tag {
+			name: name.to_owned(),
+			attrs: attributes(attrs, line, true)?,
+			line
+		});
+	}
+	Ok(tags)
+}
+
+/// Build a descriptor from the tags of the text or XML format.
+fn descriptor(tags: &[Tag]) -> Result<Descriptor, BmFontError> {
+	let mut desc = Descriptor {
+		alpha_glyph: true,
+		..Default::default()
+	};
+	let mut pages = Vec::new();
+	for tag in tags {
+		match tag.name.as_str() {
+			"info" => {
+				desc.face = tag.string("face");
+				desc.size = tag.get("size")?.unwrap_or(0);
+				desc.bold = tag.flag("bold")?;
+				desc.italic = tag.flag("italic")?;
+				desc.unicode = tag.flag("unicode")?;
+				let spacing: Vec<u32> = tag
+					.string("spacing")
+					.split(',')
+					.map(|value| value.trim().parse().unwrap_or(0))
+					.collect();
+				desc.spacing = (
+					spacing.first().copied().unwrap_or(0),
+					spacing.get(1).copied().unwrap_or(0)
+				);
+			},
+			"common" => {
+				desc.line_height = tag.get("lineHeight")?.unwrap_or(0);
+				desc.base = tag.get("base")?.unwrap_or(0);
+				desc.scale_w = tag.get("scaleW")?.unwrap_or(0);
+				desc.scale_h = tag.get("scaleH")?.unwrap_or(0);
+				desc.alpha_glyph = matches!(tag.get("alphaChnl")?, None | Some(0 | 2));
+			},
+			"page" => pages.push((tag.number("id")?, tag.string("file"))),
+			// the invalid character uses the id -1
+			"char" if tag.number("id")? >= 0 => desc.chars.push(Char {
+				id: tag.get("id")?.unwrap_or(0),
+				x: tag.get("x")?.unwrap_or(0),
+				y: tag.get("y")?.unwrap_or(0),
+				width: tag.get("width")?.unwrap_or(0),
+				height: tag.get("height")?.unwrap_or(0),
+				xoffset: tag.get("xoffset")?.unwrap_or(0),
+				yoffset: tag.get("yoffset")?.unwrap_or(0),
+				xadvance: tag.get("xadvance")?.unwrap_or(0),
+				page: tag.get("page")?.unwrap_or(0),
+				chnl: tag.get("chnl")?.unwrap_or(15)
+			}),
+			_ => {}
+		}
+	}
+	pages.sort();
+	desc.pages = pages.into_iter().map(|(_, file)| file).collect();
+	Ok(desc)
+}
+
+/// Parse the blocks of the binary format.
+fn binary_descriptor(data: &[u8]) -> Result<Descriptor, BmFontError> {
+	let version = *data.get(3).ok_or(BmFontError::UnexpectedEof)?;
+	if version != BINARY_VERSION {
+		return Err(BmFontError::UnsupportedVersion(version));
+	}
+	let u16_at = |block: &[u8], pos: usize| -> Result<u16, BmFontError> {
+		let bytes = block
+			.get(pos .. pos + 2)
+			.ok_or(BmFontError::UnexpectedEof)?;
+		Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+	};
+	let string = |bytes: &[u8]| String::from_utf8_lossy(bytes).into_owned();
+
+	let mut desc = Descriptor::default();
+	let mut pos = 4;
+	while pos < data.len() {
+		let kind = data[pos];
+		let len = data
+			.get(pos + 1 .. pos + 5)
+			.ok_or(BmFontError::UnexpectedEof)?;
+		let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+		let block = data
+			.get(pos + 5 .. pos + 5 + len)
+			.ok_or(BmFontError::UnexpectedEof)?;
+		pos += 5 + len;
+		match kind {
+			BLOCK_INFO => {
+				let bits = *block.get(2).ok_or(BmFontError::UnexpectedEof)?;
+				desc.size = i32::from(u16_at(block, 0)? as i16);
+				desc.unicode = bits & 0x02 != 0;
+				desc.italic = bits & 0x04 != 0;
+				desc.bold = bits & 0x08 != 0;
+				let spacing = block.get(11 .. 13).ok_or(BmFontError::UnexpectedEof)?;
+				desc.spacing = (spacing[0].into(), spacing[1].into());
+				let face = block.get(14 ..).unwrap_or_default();
+				desc.face = string(face.split(|b| *b == 0).next().unwrap_or_default());
+			},
+			BLOCK_COMMON => {
+				desc.line_height = u16_at(block, 0)?.into();
+				desc.base = u16_at(block, 2)?.into();
+				desc.scale_w = u16_at(block, 4)?.into();
+				desc.scale_h = u16_at(block, 6)?.into();
+				let alpha = *block.get(11).ok_or(BmFontError::UnexpectedEof)?;
+				desc.alpha_glyph = matches!(alpha, 0 | 2);
+			},
+			BLOCK_PAGES => {
+				desc.pages = block
+					.split(|b| *b == 0)
+					.filter(|name| !name.is_empty())
+					.map(string)
+					.collect();
+			},
+			BLOCK_CHARS => {
+				for ch in block.chunks_exact(CHAR_SIZE) {
+					let u16_at = |pos: usize| u16::from_le_bytes([ch[pos], ch[pos + 1]]);
+					desc.chars.push(Char {
+						id: u32::from_le_bytes(ch[0 .. 4].try_into().unwrap()),
+						x: u16_at(4).into(),
+						y: u16_at(6).into(),
+						width: u16_at(8).into(),
+						height: u16_at(10).into(),
+						xoffset: (u16_at(12) as i16).into(),
+						yoffset: (u16_at(14) as i16).into(),
+						xadvance: (u16_at(16) as i16).into(),
+						page: ch[18].into(),
+						chnl: ch[19].into()
+					});
+				}
+			},
+			_ => {}
+		}
+	}
+	desc.chars.retain(|ch| ch.id != u32::MAX);
+	Ok(desc)
+}
+
+impl Font {
+	/// Read an AngelCode BMFont descriptor in the text, XML or binary format, together
+	/// with its atlas pages, which are looked up relative to the descriptor and must
+	/// be TGA images.
+	///
+	/// A pixel is set if it is at least half covered, which is determined by the
+	/// channel each character is stored in. Characters in all channels use the alpha
+	/// channel if the descriptor says it contains the glyphs, and the brightest color
+	/// channel otherwise. The bounding box of each glyph is the rectangle of its
+	/// character.
+	pub fn read_bmfont<P: AsRef<Path>>(path: P) -> Result<Self, BmFontError> {
+		let path = path.as_ref();
+		let data = fs::read(path)?;
+		let desc = match data.starts_with(BINARY_MAGIC) {
+			true => binary_descriptor(&data)?,
+			false => {
+				let data = String::from_utf8_lossy(&data);
+				let tags = match data.trim_start().starts_with('<') {
+					true => xml_tags(&data)?,
+					false => text_tags(&data)?
+				};
+				descriptor(&tags)?
+			}
+		};
+
+		let dir = path.parent().unwrap_or(Path::new(""));
+		let pages = desc
+			.pages
+			.iter()
+			.map(|file| {
+				let data = fs::read(dir.join(file))?;
+				if data.starts_with(PNG_MAGIC) {
+					return Err(BmFontError::UnsupportedImage("PNG pages"));
+				}
+				Ok(tga::decode(&data)?)
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+
+		let ascent = desc.base as i32;
+		let pixel_size = desc
+			.size
+			.checked_abs()
+			.filter(|size| size.checked_mul(10).is_some())
+			.ok_or(BmFontError::ValueOutOfRange("font size"))?;
+		let mut glyphs = BTreeSet::new();
+		for ch in &desc.chars {
+			let page = pages
+				.get(ch.page as usize)
+				.ok_or(BmFontError::Missing("page"))?;
+			let (x, y) = (ch.x as usize, ch.y as usize);
+			let (width, height) = (ch.width as usize, ch.height as usize);
+			if x + width > page.width || y + height > page.height {
+				return Err(BmFontError::CharOutsidePage(ch.id));
+			}
+			let coverage = |[r, g, b, a]: [u8; 4]| match ch.chnl {
+				1 => b,
+				2 => g,
+				4 => r,
+				8 => a,
+				_ if desc.alpha_glyph && page.has_alpha => a,
+				_ => r.max(g).max(b)
+			};
+			let bitmap = (y .. y + height)
+				.map(|py| {
+					let mut row = BitVec::from_elem(width.div_ceil(8) * 8, false);
+					for px in 0 .. width {
+						row.set(
+							px,
+							coverage(page.pixels[py * page.width + x + px]) >= 0x80
+						);
+					}
+					row
+				})
+				.collect();
+
+			let dwidth = f64::from(ch.xadvance);
+			let swidth = match pixel_size {
+				0 => 0.0,
+				size => (dwidth * 1000.0 / f64::from(size)).round()
+			};
+			glyphs.insert(GlyphWrapper(Glyph {
+				name: match desc.unicode {
+					true => format!("uni{:04X}", ch.id),
+					false => format!("char{}", ch.id)
+				},
+				encoding: ch.id,
+				swidth: Some((swidth, 0.0)),
+				dwidth: Some((dwidth, 0.0)),
+				bbox: BoundingBox {
+					width: ch.width,
+					height: ch.height,
+					offset_x: ch.xoffset,
+					offset_y: ascent - ch.yoffset - ch.height as i32
+				},
+				bitmap
+			}));
+		}
+
+		let family = desc.face;
+		let weight_name = match desc.bold {
+			true => "Bold",
+			false => "Medium"
+		};
+		let slant = match desc.italic {
+			true => "I",
+			false => "R"
+		};
+		let advances: Vec<i32> = glyphs.iter().map(|gw| advance(&gw.0)).collect();
+		let spacing = match advances.windows(2).all(|w| w[0] == w[1]) {
+			true => "C",
+			false => "P"
+		};
+		let average_width = match advances.len() {
+			0 => 0,
+			len => advances.iter().sum::<i32>() * 10 / len as i32
+		};
+		let (registry, encoding) = match desc.unicode {
+			true => ("iso10646", "1"),
+			false => ("misc", "fontspecific")
+		};
+		let name = format!(
+			"-misc-{family}-{weight_name}-{slant}-Normal--{pixel_size}-{}-72-72-{spacing}-\
+			 {average_width}-{registry}-{encoding}",
+			pixel_size * 10
+		);
+
+		let bbox = glyphs
+			.iter()
+			.fold(BoundingBox::default(), |bbox, gw| bbox.union(gw.0.bbox));
+		Ok(Self {
+			version: None,
+			name,
+			bbox,
+			size: Size {
+				pt: pixel_size as u32,
+				xres: 72,
+				yres: 72
+			},
+			properties: HashMap::from([
+				("FAMILY_NAME".into(), Value::String(family)),
+				("WEIGHT_NAME".into(), Value::String(weight_name.into())),
+				("SLANT".into(), Value::String(slant.into())),
+				("PIXEL_SIZE".into(), Value::Integer(pixel_size)),
+				("POINT_SIZE".into(), Value::Integer(pixel_size * 10)),
+				("RESOLUTION_X".into(), Value::Integer(72)),
+				("RESOLUTION_Y".into(), Value::Integer(72)),
+				("SPACING".into(), Value::String(spacing.into())),
+				("AVERAGE_WIDTH".into(), Value::Integer(average_width)),
+				("CHARSET_REGISTRY".into(), Value::String(registry.into())),
+				("CHARSET_ENCODING".into(), Value::String(encoding.into())),
+				("FONT_ASCENT".into(), Value::Integer(ascent)),
+				(
+					"FONT_DESCENT".into(),
+					Value::Integer(desc.line_height as i32 - ascent)
+				)
+			]),
+			glyphs
+		})
+	}
+}
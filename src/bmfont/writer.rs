@@ -0,0 +1,356 @@
+use super::*;
+use crate::{atlas::pack, layout::advance, tga, Font, Glyph};
+use std::{fmt::Write as _, fs, path::Path};
+
+/// The value of an attribute of a descriptor tag.
+enum Attr {
+	Number(i64),
+	String(String),
+
+	/// A comma separated list of numbers.
+	List(Vec<i64>)
+}
+
+impl Attr {
+	fn text(&self, key: &'static str) -> Result<String, BmFontError> {
+		Ok(match self {
+			Self::Number(value) => value.to_string(),
+			// the text format has no escapes for quotes inside of values
+			Self::String(value) if value.contains('"') => {
+				return Err(BmFontError::ValueOutOfRange(key));
+			},
+			Self::String(value) => format!("\"{value}\""),
+			Self::List(values) => list(values)
+		})
+	}
+
+	fn xml(&self) -> String {
+		let value = match self {
+			Self::Number(value) => value.to_string(),
+			Self::String(value) => value
+				.replace('&', "&amp;")
+				.replace('"', "&quot;")
+				.replace('<', "&lt;")
+				.replace('>', "&gt;"),
+			Self::List(values) => list(values)
+		};
+		format!("\"{value}\"")
+	}
+}
+
+fn list(values: &[i64]) -> String {
+	values
+		.iter()
+		.map(i64::to_string)
+		.collect::<Vec<_>>()
+		.join(",")
+}
+
+/// A tag of a descriptor with its attributes.
+type Tag = (&'static str, Vec<(&'static str, Attr)>);
+
+fn flag(value: bool) -> Attr {
+	Attr::Number(value.into())
+}
+
+/// Get the tags of the text and XML formats, excluding the `chars` tag.
+fn tags(desc: &Descriptor) -> (Vec<Tag>, Vec<Tag>, Vec<Tag>) {
+	let num = |value: u32| Attr::Number(value.into());
+	let header = vec![
+		("info", vec![
+			("face", Attr::String(desc.face.clone())),
+			("size", Attr::Number(desc.size.into())),
+			("bold", flag(desc.bold)),
+			("italic", flag(desc.italic)),
+			("charset", Attr::String(String::new())),
+			("unicode", flag(desc.unicode)),
+			("stretchH", Attr::Number(100)),
+			("smooth", Attr::Number(0)),
+			("aa", Attr::Number(1)),
+			("padding", Attr::List(vec![0, 0, 0, 0])),
+			(
+				"spacing",
+				Attr::List(vec![desc.spacing.0.into(), desc.spacing.1.into()])
+			),
+			("outline", Attr::Number(0)),
+		]),
+		("common", vec![
+			("lineHeight", num(desc.line_height)),
+			("base", num(desc.base)),
+			("scaleW", num(desc.scale_w)),
+			("scaleH", num(desc.scale_h)),
+			("pages", Attr::Number(desc.pages.len() as i64)),
+			("packed", Attr::Number(0)),
+			("alphaChnl", Attr::Number(0)),
+			("redChnl", Attr::Number(4)),
+			("greenChnl", Attr::Number(4)),
+			("blueChnl", Attr::Number(4)),
+		]),
+	];
+	let pages = desc
+		.pages
+		.iter()
+		.enumerate()
+		.map(|(id, file)| {
+			("page", vec![
+				("id", Attr::Number(id as i64)),
+				("file", Attr::String(file.clone())),
+			])
+		})
+		.collect();
+	let chars = desc
+		.chars
+		.iter()
+		.map(|ch| {
+			("char", vec![
+				("id", num(ch.id)),
+				("x", num(ch.x)),
+				("y", num(ch.y)),
+				("width", num(ch.width)),
+				("height", num(ch.height)),
+				("xoffset", Attr::Number(ch.xoffset.into())),
+				("yoffset", Attr::Number(ch.yoffset.into())),
+				("xadvance", Attr::Number(ch.xadvance.into())),
+				("page", num(ch.page)),
+				("chnl", num(ch.chnl)),
+			])
+		})
+		.collect();
+	(header, pages, chars)
+}
+
+fn text(desc: &Descriptor) -> Result<String, BmFontError> {
+	let (header, pages, chars) = tags(desc);
+	let mut buf = String::new();
+	let mut line = |(tag, attrs): &Tag| {
+		buf += tag;
+		for (key, value) in attrs {
+			write!(buf, " {key}={}", value.text(key)?).unwrap();
+		}
+		buf += "\n";
+		Ok::<_, BmFontError>(())
+	};
+	header.iter().try_for_each(&mut line)?;
+	pages.iter().try_for_each(&mut line)?;
+	line(&("chars", vec![("count", Attr::Number(chars.len() as i64))]))?;
+	chars.iter().try_for_each(&mut line)?;
+	Ok(buf)
+}
+
+fn xml(desc: &Descriptor) -> String {
+	let (header, pages, chars) = tags(desc);
+	let element = |indent: &str, (tag, attrs): &Tag| {
+		let mut buf = format!("{indent}<{tag}");
+		for (key, value) in attrs {
+			write!(buf, " {key}={}", value.xml()).unwrap();
+		}
+		buf + "/>\n"
+	};
+	let mut buf = String::from("<?xml version=\"1.0\"?>\n<font>\n");
+	for tag in &header {
+		buf += &element("  ", tag);
+	}
+	buf += "  <pages>\n";
+	for tag in &pages {
+		buf += &element("    ", tag);
+	}
+	buf += "  </pages>\n";
+	writeln!(buf, "  <chars count=\"{}\">", chars.len()).unwrap();
+	for tag in &chars {
+		buf += &element("    ", tag);
+	}
+	buf += "  </chars>\n</font>\n";
+	buf
+}
+
+fn u8_of(value: u32, what: &'static str) -> Result<u8, BmFontError> {
+	u8::try_from(value).map_err(|_| BmFontError::ValueOutOfRange(what))
+}
+
+fn u16_of(value: u32, what: &'static str) -> Result<u16, BmFontError> {
+	u16::try_from(value).map_err(|_| BmFontError::ValueOutOfRange(what))
+}
+
+fn binary(desc: &Descriptor) -> Result<Vec<u8>, BmFontError> {
+	let mut buf = BINARY_MAGIC.to_vec();
+	buf.push(BINARY_VERSION);
+	let block = |buf: &mut Vec<u8>, kind: u8, data: Vec<u8>| {
+		buf.push(kind);
+		buf.extend((data.len() as u32).to_le_bytes());
+		buf.extend(data);
+	};
+
+	let mut info = Vec::new();
+	let size = i16::try_from(desc.size)
+		.map_err(|_| BmFontError::ValueOutOfRange("font size"))?;
+	info.extend(size.to_le_bytes());
+	info.push(
+		(desc.unicode as u8) << 1 | (desc.italic as u8) << 2 | (desc.bold as u8) << 3
+	);
+	info.push(0); // charSet
+	info.extend(100u16.to_le_bytes()); // stretchH
+	info.push(1); // aa
+	info.extend([0; 4]); // padding
+	let (spacing_x, spacing_y) = desc.spacing;
+	info.extend([u8_of(spacing_x, "spacing")?, u8_of(spacing_y, "spacing")?]);
+	info.push(0); // outline
+	info.extend(desc.face.bytes().filter(|b| *b != 0));
+	info.push(0);
+	block(&mut buf, BLOCK_INFO, info);
+
+	let mut common = Vec::new();
+	let page_count = u32::try_from(desc.pages.len()).unwrap_or(u32::MAX);
+	for (value, what) in [
+		(desc.line_height, "line height"),
+		(desc.base, "base"),
+		(desc.scale_w, "page width"),
+		(desc.scale_h, "page height"),
+		(page_count, "number of pages")
+	] {
+		common.extend(u16_of(value, what)?.to_le_bytes());
+	}
+	common.extend([0, 0, 4, 4, 4]); // bitField and the channels
+	block(&mut buf, BLOCK_COMMON, common);
+
+	let mut pages = Vec::new();
+	for page in &desc.pages {
+		pages.extend(page.bytes());
+		pages.push(0);
+	}
+	block(&mut buf, BLOCK_PAGES, pages);
+
+	let mut chars = Vec::with_capacity(desc.chars.len() * CHAR_SIZE);
+	for ch in &desc.chars {
+		chars.extend(ch.id.to_le_bytes());
+		for value in [ch.x, ch.y, ch.width, ch.height] {
+			chars.extend(u16_of(value, "character position")?.to_le_bytes());
+		}
+		for value in [ch.xoffset, ch.yoffset, ch.xadvance] {
+			let value = i16::try_from(value)
+				.map_err(|_| BmFontError::MetricsOutOfRange(ch.id))?;
+			chars.extend(value.to_le_bytes());
+		}
+		chars.extend([u8_of(ch.page, "page index")?, u8_of(ch.chnl, "channel")?]);
+	}
+	block(&mut buf, BLOCK_CHARS, chars);
+	Ok(buf)
+}
+
+impl Font {
+	/// Write this font as an AngelCode BMFont descriptor to `path`, together with
+	/// its atlas pages as TGA images next to it. The pages are named after the
+	/// descriptor, e.g. `font.fnt` has the pages `font_0.tga`, `font_1.tga` and so on.
+	///
+	/// The glyph bitmaps are packed into as many pages as needed, with their
+	/// bounding boxes determining the offsets and `DWIDTH` the advance of each
+	/// character. The pages contain white glyphs on a transparent background.
+	pub fn write_bmfont<P: AsRef<Path>>(
+		&self,
+		path: P,
+		options: &BmFontOptions
+	) -> Result<(), BmFontError> {
+		let path = path.as_ref();
+		let glyphs: Vec<&Glyph> = self.glyphs().into_iter().collect();
+		let page_width = u16::try_from(options.page_width).map_err(|_| {
+			BmFontError::UnsupportedImage("pages wider than 65535 pixels")
+		})?;
+		let page_height = u16::try_from(options.page_height).map_err(|_| {
+			BmFontError::UnsupportedImage("pages higher than 65535 pixels")
+		})?;
+
+		let sizes: Vec<(u32, u32)> = glyphs
+			.iter()
+			.map(|glyph| (glyph.bbox.width, glyph.bbox.height))
+			.collect();
+		let (placements, page_count) = pack(
+			&sizes,
+			options.page_width,
+			options.page_height,
+			options.padding
+		)
+		.map_err(|idx| BmFontError::GlyphTooLarge(glyphs[idx].encoding))?;
+
+		let (ascent, descent) = self.ascent_descent();
+		let page_size = usize::from(page_width) * usize::from(page_height);
+		let mut pages = vec![vec![[0xFF, 0xFF, 0xFF, 0]; page_size]; page_count];
+		let mut chars = Vec::with_capacity(glyphs.len());
+		for (glyph, placement) in glyphs.iter().zip(&placements) {
+			let bitmap = glyph.bitmap();
+			let page = &mut pages[placement.page];
+			for y in 0 .. bitmap.height() {
+				for x in 0 .. bitmap.width() {
+					if bitmap.get(x, y).unwrap_or(false) {
+						let px = placement.x as usize + x;
+						let py = placement.y as usize + y;
+						page[py * usize::from(page_width) + px][3] = 0xFF;
+					}
+				}
+			}
+
+			let bbox = glyph.bbox;
+			let yoffset = ascent - bbox.offset_y - bbox.height as i32;
+			let advance = advance(glyph);
+			if [bbox.offset_x, yoffset, advance]
+				.iter()
+				.any(|v| i16::try_from(*v).is_err())
+			{
+				return Err(BmFontError::MetricsOutOfRange(glyph.encoding));
+			}
+			chars.push(Char {
+				id: glyph.encoding,
+				x: placement.x,
+				y: placement.y,
+				width: bbox.width,
+				height: bbox.height,
+				xoffset: bbox.offset_x,
+				yoffset,
+				xadvance: advance,
+				page: placement.page as u32,
+				chnl: 15
+			});
+		}
+
+		let stem = path
+			.file_stem()
+			.map(|stem| stem.to_string_lossy().into_owned())
+			.unwrap_or_default();
+		let dir = path.parent().unwrap_or(Path::new(""));
+		let mut page_files = Vec::with_capacity(page_count);
+		for (idx, page) in pages.iter().enumerate() {
+			let file = format!("{stem}_{idx}.tga");
+			fs::write(dir.join(&file), tga::encode(page_width, page_height, page))?;
+			page_files.push(file);
+		}
+
+		let desc = Descriptor {
+			face: self
+				.string_property("FAMILY_NAME")
+				.unwrap_or(&self.name)
+				.to_owned(),
+			size: self.pixels_per_em(),
+			bold: self
+				.string_property("WEIGHT_NAME")
+				.is_some_and(|name| name.to_ascii_lowercase().contains("bold")),
+			italic: matches!(self.string_property("SLANT"), Some("I" | "O")),
+			unicode: match self.string_property("CHARSET_REGISTRY") {
+				Some(registry) => registry.eq_ignore_ascii_case("iso10646"),
+				None => true
+			},
+			spacing: (options.padding, options.padding),
+			line_height: (ascent + descent).max(0) as u32,
+			base: ascent.max(0) as u32,
+			scale_w: options.page_width,
+			scale_h: options.page_height,
+			alpha_glyph: true,
+			pages: page_files,
+			chars
+		};
+		let data = match options.format {
+			BmFontFormat::Text => text(&desc)?.into_bytes(),
+			BmFontFormat::Xml => xml(&desc).into_bytes(),
+			BmFontFormat::Binary => binary(&desc)?
+		};
+		fs::write(path, data)?;
+		Ok(())
+	}
+}
@@ -0,0 +1,64 @@
+//! Transparent decompression of font files.
+
+use crate::{pcf, pf2, psf, Error, Font};
+use std::{
+	fs::File,
+	io::{self, BufRead, BufReader, Cursor, Read},
+	path::Path
+};
+
+/// The magic bytes at the start of a gzip stream.
+const GZIP_MAGIC: &[u8; 2] = b"\x1f\x8b";
+
+/// Read the first `len` bytes of the input, or all of it if it is shorter. Unlike a
+/// single `fill_buf`, this does not stop at short reads.
+fn read_magic<R: Read>(reader: &mut R, len: usize) -> io::Result<Vec<u8>> {
+	let mut magic = Vec::with_capacity(len);
+	reader.take(len as u64).read_to_end(&mut magic)?;
+	Ok(magic)
+}
+
+/// Wrap a reader so that it decompresses its contents if they start with the magic
+/// bytes of a supported compression format. Otherwise, the reader is returned as is.
+pub(crate) fn decompress<'a, R: BufRead + 'a>(
+	mut reader: R
+) -> io::Result<Box<dyn BufRead + 'a>> {
+	let magic = read_magic(&mut reader, GZIP_MAGIC.len())?;
+	let compressed = magic.starts_with(GZIP_MAGIC);
+	let reader = Cursor::new(magic).chain(reader);
+	if compressed {
+		#[cfg(feature = "gzip")]
+		return Ok(Box::new(BufReader::new(
+			flate2::bufread::MultiGzDecoder::new(reader)
+		)));
+		#[cfg(not(feature = "gzip"))]
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidData,
+			"Reading gzip compressed fonts requires the gzip feature"
+		));
+	}
+	Ok(Box::new(reader))
+}
+
+impl Font {
+	/// Open a font file. BDF, PCF, PSF and PF2 fonts are supported, and the format is
+	/// detected from the file contents rather than its extension. With the `gzip`
+	/// feature enabled, compressed files like `.pcf.gz` are decompressed on the fly.
+	pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+		let mut reader = decompress(BufReader::new(File::open(path)?))?;
+		let magic = read_magic(&mut reader, pf2::MAGIC.len())?;
+		let reader = Cursor::new(magic.as_slice()).chain(reader);
+		// the binary formats never start with the gzip magic, so their readers will not
+		// decompress the input again
+		if magic.starts_with(pcf::MAGIC) {
+			return Ok(Self::read_pcf(reader)?);
+		}
+		if magic.starts_with(psf::PSF1_MAGIC) || magic.starts_with(psf::PSF2_MAGIC) {
+			return Ok(Self::read_psf(reader)?);
+		}
+		if magic.starts_with(pf2::MAGIC) {
+			return Ok(Self::read_pf2(reader)?);
+		}
+		Self::read_decompressed(reader)
+	}
+}
@@ -0,0 +1,148 @@
+use crate::{CharSet, Font};
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	ops::RangeInclusive
+};
+use unicode_blocks::find_unicode_block;
+use unicode_general_category::{get_general_category, GeneralCategory};
+use unicode_script::UnicodeScript;
+
+/// Check whether a code point is assigned to a character.
+pub(crate) fn is_assigned(ch: char) -> bool {
+	!matches!(
+		get_general_category(ch),
+		GeneralCategory::Unassigned | GeneralCategory::Surrogate
+	)
+}
+
+/// The coverage of a single Unicode block.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BlockCoverage {
+	/// The name of the block, e.g. `Basic Latin`.
+	pub name: &'static str,
+
+	/// The code points of the block.
+	pub range: RangeInclusive<u32>,
+
+	/// The number of assigned characters in the block.
+	pub assigned: u32,
+
+	/// The number of assigned characters in the block that have a glyph.
+	pub covered: u32
+}
+
+impl BlockCoverage {
+	/// Get the fraction of the assigned characters that are covered.
+	pub fn ratio(&self) -> f64 {
+		if self.assigned == 0 {
+			return 0.0;
+		}
+		self.covered as f64 / self.assigned as f64
+	}
+
+	/// Check whether all assigned characters of the block are covered.
+	pub fn is_complete(&self) -> bool {
+		self.covered == self.assigned
+	}
+}
+
+/// The coverage of a single Unicode script.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScriptCoverage {
+	/// The name of the script, e.g. `Latin`.
+	pub name: &'static str,
+
+	/// The number of assigned characters of the script that have a glyph.
+	pub covered: u32
+}
+
+/// The Unicode coverage of a font.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Coverage {
+	/// All blocks with at least one covered character, ordered by code point.
+	pub blocks: Vec<BlockCoverage>,
+
+	/// All scripts with at least one covered character, ordered by name.
+	pub scripts: Vec<ScriptCoverage>,
+
+	/// Glyphs whose encoding is not an assigned Unicode character.
+	pub unassigned: Vec<u32>
+}
+
+/// The difference in coverage of two fonts.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CoverageComparison {
+	/// Encodings contained in both fonts.
+	pub common: Vec<u32>,
+
+	/// Encodings only contained in the first font.
+	pub only_first: Vec<u32>,
+
+	/// Encodings only contained in the second font.
+	pub only_second: Vec<u32>
+}
+
+impl Font {
+	/// Group the encoded glyphs of this font by Unicode block and script.
+	pub fn coverage(&self) -> Coverage {
+		let mut blocks = BTreeMap::new();
+		let mut scripts = BTreeMap::new();
+		let mut unassigned = Vec::new();
+
+		for glyph in self.glyphs() {
+			let Some(ch) = char::from_u32(glyph.encoding).filter(|ch| is_assigned(*ch))
+			else {
+				unassigned.push(glyph.encoding);
+				continue;
+			};
+			match find_unicode_block(ch) {
+				Some(block) => {
+					blocks
+						.entry(block.start())
+						.or_insert_with(|| BlockCoverage {
+							name: block.name(),
+							range: block.start() ..= block.end(),
+							assigned: (block.start() ..= block.end())
+								.filter_map(char::from_u32)
+								.filter(|ch| is_assigned(*ch))
+								.count() as u32,
+							covered: 0
+						})
+						.covered += 1;
+				},
+				None => unassigned.push(glyph.encoding)
+			}
+			*scripts.entry(ch.script().full_name()).or_insert(0) += 1;
+		}
+
+		Coverage {
+			blocks: blocks.into_values().collect(),
+			scripts: scripts
+				.into_iter()
+				.map(|(name, covered)| ScriptCoverage { name, covered })
+				.collect(),
+			unassigned
+		}
+	}
+
+	/// Get the characters of a target repertoire that are missing from this font.
+	/// Ranges in the repertoire only contribute assigned characters, and glyph names
+	/// are ignored.
+	pub fn missing_chars(&self, repertoire: &CharSet) -> Vec<u32> {
+		repertoire
+			.iter_encodings()
+			.filter(|enc| self.glyph(*enc).is_none())
+			.collect()
+	}
+
+	/// Compare the encodings covered by this font with another font.
+	pub fn compare_coverage(&self, other: &Font) -> CoverageComparison {
+		let first: BTreeSet<u32> = self.glyphs.iter().map(|gw| gw.0.encoding).collect();
+		let second: BTreeSet<u32> = other.glyphs.iter().map(|gw| gw.0.encoding).collect();
+		CoverageComparison {
+			common: first.intersection(&second).copied().collect(),
+			only_first: first.difference(&second).copied().collect(),
+			only_second: second.difference(&first).copied().collect()
+		}
+	}
+}
@@ -0,0 +1,100 @@
+//! Windows raster fonts stored as `.FNT` resources, optionally inside of `.FON`
+//! files which are New Executable (NE) containers.
+//!
+//! The format is described in the Microsoft Knowledge Base article Q65123, "Font
+//! File Format".
+
+use std::io;
+use thiserror::Error;
+
+mod reader;
+mod writer;
+
+/// The size of the version 2 header.
+pub(crate) const V2_HEADER_SIZE: usize = 118;
+
+/// The size of the version 3 header.
+pub(crate) const V3_HEADER_SIZE: usize = 148;
+
+/// The resource type of fonts in NE executables.
+pub(crate) const RT_FONT: u16 = 0x8008;
+
+/// The low bit of `dfPitchAndFamily`, which confusingly indicates variable pitch.
+pub(crate) const VARIABLE_PITCH: u8 = 0x01;
+
+pub(crate) const DFF_FIXED: u32 = 0x01;
+pub(crate) const DFF_PROPORTIONAL: u32 = 0x02;
+
+pub(crate) const FW_NORMAL: u16 = 400;
+pub(crate) const FW_BOLD: u16 = 700;
+
+/// The `dfCharSet` values and their `CHARSET_REGISTRY` and `CHARSET_ENCODING`.
+pub(crate) const CHARSETS: &[(u8, &str, &str)] = &[
+	(0, "microsoft", "cp1252"),
+	(2, "microsoft", "symbol"),
+	(161, "microsoft", "cp1253"),
+	(162, "microsoft", "cp1254"),
+	(163, "microsoft", "cp1258"),
+	(177, "microsoft", "cp1255"),
+	(178, "microsoft", "cp1256"),
+	(186, "microsoft", "cp1257"),
+	(204, "microsoft", "cp1251"),
+	(222, "microsoft", "cp874"),
+	(238, "microsoft", "cp1250"),
+	(255, "ibm", "cp437")
+];
+
+/// The Unicode characters of the bytes 0x80 to 0x9F in code page 1252, which is
+/// written for Unicode fonts, or 0 for undefined bytes. All other bytes match Unicode.
+const CP1252_HIGH: [u16; 32] = [
+	0x20AC, 0, 0x201A, 0x0192, 0x201E, 0x2026, 0x2020, 0x2021, 0x02C6, 0x2030, 0x0160,
+	0x2039, 0x0152, 0, 0x017D, 0, 0, 0x2018, 0x2019, 0x201C, 0x201D, 0x2022, 0x2013,
+	0x2014, 0x02DC, 0x2122, 0x0161, 0x203A, 0x0153, 0, 0x017E, 0x0178
+];
+
+/// Get the byte of a Unicode character in code page 1252.
+pub(crate) fn cp1252_byte(code: u32) -> Option<u8> {
+	match code {
+		0x80 ..= 0x9F => None,
+		0 ..= 0xFF => Some(code as u8),
+		_ => CP1252_HIGH
+			.iter()
+			.position(|ch| u32::from(*ch) == code)
+			.map(|idx| 0x80 + idx as u8)
+	}
+}
+
+#[derive(Debug, Error)]
+pub enum FntError {
+	#[error("I/O Error: {0}")]
+	IOError(#[from] io::Error),
+
+	#[error("Unexpected end of file")]
+	UnexpectedEof,
+
+	#[error("Unsupported FNT version {0:#x}")]
+	UnsupportedVersion(u16),
+
+	#[error("Vector fonts are not supported")]
+	VectorFont,
+
+	#[error("Not a NE executable")]
+	InvalidExecutable,
+
+	#[error("Glyph {0} exceeds its character cell")]
+	GlyphOutsideCell(u32),
+
+	#[error("The font is too large for FNT version 2")]
+	TooLarge
+}
+
+/// The version of the FNT format.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum FntVersion {
+	/// The Windows 2.0 format, limited to 64 KiB of bitmap data.
+	#[default]
+	V2,
+
+	/// The Windows 3.0 format with 32 bit offsets.
+	V3
+}
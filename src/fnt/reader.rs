@@ -0,0 +1,254 @@
+use super::*;
+use crate::{
+	compress::decompress, font::GlyphWrapper, BoundingBox, Font, Glyph, Size, Value
+};
+use bit_vec::BitVec;
+use std::{
+	collections::{BTreeSet, HashMap},
+	io::{BufReader, Read}
+};
+
+/// A little endian cursor over the data of a FNT resource or NE executable.
+struct Data<'a>(&'a [u8]);
+
+impl<'a> Data<'a> {
+	fn bytes(&self, offset: usize, len: usize) -> Result<&'a [u8], FntError> {
+		offset
+			.checked_add(len)
+			.and_then(|end| self.0.get(offset .. end))
+			.ok_or(FntError::UnexpectedEof)
+	}
+
+	fn u8(&self, offset: usize) -> Result<u8, FntError> {
+		Ok(self.bytes(offset, 1)?[0])
+	}
+
+	fn u16(&self, offset: usize) -> Result<u16, FntError> {
+		let bytes = self.bytes(offset, 2)?;
+		Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+	}
+
+	fn u32(&self, offset: usize) -> Result<u32, FntError> {
+		let bytes = self.bytes(offset, 4)?;
+		Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+	}
+
+	/// Read a string that is terminated by a NUL byte or the given length.
+	fn string(&self, offset: usize, max_len: usize) -> String {
+		let bytes = self.0.get(offset ..).unwrap_or_default();
+		let bytes = &bytes[.. bytes.len().min(max_len)];
+		let len = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+		String::from_utf8_lossy(&bytes[.. len]).into_owned()
+	}
+}
+
+/// Parse a single FNT resource.
+fn parse_fnt(data: &[u8]) -> Result<Font, FntError> {
+	let data = Data(data);
+	let version = data.u16(0)?;
+	let (header_size, entry_size) = match version {
+		0x200 => (V2_HEADER_SIZE, 4),
+		0x300 => (V3_HEADER_SIZE, 6),
+		_ => return Err(FntError::UnsupportedVersion(version))
+	};
+	if data.u16(66)? & 1 != 0 {
+		return Err(FntError::VectorFont);
+	}
+
+	let copyright = data.string(6, 60);
+	let points = data.u16(68)?;
+	let vert_res = data.u16(70)?;
+	let horiz_res = data.u16(72)?;
+	let ascent = i32::from(data.u16(74)?);
+	let internal_leading = i32::from(data.u16(76)?);
+	let italic = data.u8(80)? != 0;
+	let weight = data.u16(83)?;
+	let charset = data.u8(85)?;
+	let pix_width = data.u16(86)?;
+	let pix_height = data.u16(88)?;
+	let avg_width = data.u16(91)?;
+	let first_char = data.u8(95)?;
+	let last_char = data.u8(96)?;
+	let default_char = data.u8(97)?;
+	let face = match data.u32(105)? {
+		0 => String::new(),
+		offset => data.string(offset as usize, usize::MAX)
+	};
+
+	let size = Size {
+		pt: points.into(),
+		xres: horiz_res.into(),
+		yres: vert_res.into()
+	};
+	let height = usize::from(pix_height);
+	let mut glyphs = BTreeSet::new();
+	for (i, encoding) in (first_char ..= last_char).enumerate() {
+		let entry = header_size + i * entry_size;
+		let width = data.u16(entry)?;
+		let offset = match version {
+			0x200 => data.u16(entry + 2)?.into(),
+			_ => data.u32(entry + 2)? as usize
+		};
+		let columns = usize::from(width).div_ceil(8);
+		let bitmap_data = data.bytes(offset, columns * height)?;
+		let bitmap = (0 .. height)
+			.map(|y| {
+				let row: Vec<u8> = (0 .. columns)
+					.map(|col| bitmap_data[col * height + y])
+					.collect();
+				let mut row = BitVec::from_bytes(&row);
+				for x in usize::from(width) .. row.len() {
+					row.set(x, false);
+				}
+				row
+			})
+			.collect();
+
+		let dwidth = f64::from(width);
+		let swidth = match size.pt > 0 && size.xres > 0 {
+			true => (dwidth * 72000.0 / (size.pt as f64 * size.xres as f64)).round(),
+			false => 0.0
+		};
+		glyphs.insert(GlyphWrapper(Glyph {
+			name: format!("char{encoding}"),
+			encoding: encoding.into(),
+			swidth: Some((swidth, 0.0)),
+			dwidth: Some((dwidth, 0.0)),
+			bbox: BoundingBox {
+				width: width.into(),
+				height: pix_height.into(),
+				offset_x: 0,
+				offset_y: ascent - i32::from(pix_height)
+			},
+			bitmap
+		}));
+	}
+
+	let (registry, encoding) = match CHARSETS.iter().find(|(cs, ..)| *cs == charset) {
+		Some((_, registry, encoding)) => ((*registry).to_owned(), (*encoding).to_owned()),
+		None => ("microsoft".to_owned(), format!("charset{charset}"))
+	};
+	let weight_name = match weight >= FW_BOLD {
+		true => "Bold",
+		false => "Medium"
+	};
+	let slant = match italic {
+		true => "I",
+		false => "R"
+	};
+	let spacing = match pix_width {
+		0 => "P",
+		_ => "C"
+	};
+	let pixel_size = i32::from(pix_height) - internal_leading;
+	let average_width = i32::from(avg_width) * 10;
+	let name = format!(
+		"-misc-{face}-{weight_name}-{slant}-Normal--{pixel_size}-{}-{horiz_res}-{vert_res}-\
+		 {spacing}-{average_width}-{registry}-{encoding}",
+		points * 10
+	);
+
+	let mut properties: HashMap<String, Value> = HashMap::from([
+		("FAMILY_NAME".into(), Value::String(face)),
+		("WEIGHT_NAME".into(), Value::String(weight_name.into())),
+		("SLANT".into(), Value::String(slant.into())),
+		("PIXEL_SIZE".into(), Value::Integer(pixel_size)),
+		("POINT_SIZE".into(), Value::Integer(i32::from(points) * 10)),
+		("RESOLUTION_X".into(), Value::Integer(horiz_res.into())),
+		("RESOLUTION_Y".into(), Value::Integer(vert_res.into())),
+		("SPACING".into(), Value::String(spacing.into())),
+		("AVERAGE_WIDTH".into(), Value::Integer(average_width)),
+		("CHARSET_REGISTRY".into(), Value::String(registry)),
+		("CHARSET_ENCODING".into(), Value::String(encoding)),
+		("FONT_ASCENT".into(), Value::Integer(ascent)),
+		(
+			"FONT_DESCENT".into(),
+			Value::Integer(i32::from(pix_height) - ascent)
+		),
+		(
+			"DEFAULT_CHAR".into(),
+			Value::Integer(i32::from(first_char) + i32::from(default_char))
+		)
+	]);
+	if !copyright.is_empty() {
+		properties.insert("COPYRIGHT".into(), Value::String(copyright));
+	}
+
+	let bbox = glyphs
+		.iter()
+		.fold(BoundingBox::default(), |bbox, gw| bbox.union(gw.0.bbox));
+	Ok(Font {
+		version: None,
+		name,
+		bbox,
+		size,
+		properties,
+		glyphs
+	})
+}
+
+/// Get the FNT resources of a NE executable.
+fn fon_resources(data: &[u8]) -> Result<Vec<&[u8]>, FntError> {
+	let exe = Data(data);
+	if exe.bytes(0, 2)? != b"MZ" {
+		return Err(FntError::InvalidExecutable);
+	}
+	let ne = exe.u32(0x3C)? as usize;
+	if exe.bytes(ne, 2)? != b"NE" {
+		return Err(FntError::InvalidExecutable);
+	}
+
+	let mut pos = ne + usize::from(exe.u16(ne + 0x24)?);
+	// shifting the 16 bit offsets by more than 16 bits would exceed 32 bit files
+	let align_shift = exe.u16(pos)?;
+	if align_shift > 16 {
+		return Err(FntError::InvalidExecutable);
+	}
+	pos += 2;
+	let mut resources = Vec::new();
+	loop {
+		let type_id = exe.u16(pos)?;
+		if type_id == 0 {
+			break;
+		}
+		let count = usize::from(exe.u16(pos + 2)?);
+		pos += 8;
+		for _ in 0 .. count {
+			if type_id == RT_FONT {
+				let offset = u64::from(exe.u16(pos)?) << align_shift;
+				let len = u64::from(exe.u16(pos + 2)?) << align_shift;
+				let offset = usize::try_from(offset)
+					.ok()
+					.filter(|offset| *offset < data.len())
+					.ok_or(FntError::InvalidExecutable)?;
+				// the length is rounded up to the alignment and may exceed the file
+				let len = usize::try_from(len)
+					.unwrap_or(usize::MAX)
+					.min(data.len() - offset);
+				resources.push(exe.bytes(offset, len)?);
+			}
+			pos += 12;
+		}
+	}
+	Ok(resources)
+}
+
+impl Font {
+	/// Read a Windows raster font resource in the `.FNT` format, version 2 or 3.
+	///
+	/// The glyph encodings are the byte values of the font's character set, which is
+	/// stored in the `CHARSET_REGISTRY` and `CHARSET_ENCODING` properties.
+	pub fn read_fnt<R: Read>(reader: R) -> Result<Self, FntError> {
+		let mut buf = Vec::new();
+		decompress(BufReader::new(reader))?.read_to_end(&mut buf)?;
+		parse_fnt(&buf)
+	}
+
+	/// Read all fonts contained in a Windows `.FON` file. The fonts are read like
+	/// [`read_fnt`](Self::read_fnt) does.
+	pub fn read_fon<R: Read>(reader: R) -> Result<Vec<Self>, FntError> {
+		let mut buf = Vec::new();
+		decompress(BufReader::new(reader))?.read_to_end(&mut buf)?;
+		fon_resources(&buf)?.into_iter().map(parse_fnt).collect()
+	}
+}
@@ -0,0 +1,223 @@
+use super::*;
+use crate::{layout::advance, Font, Glyph};
+use log::warn;
+use std::{collections::BTreeMap, io::Write};
+
+/// Render a glyph into its character cell, returning the bitmap in column-major
+/// order as stored in FNT files.
+fn render(
+	glyph: &Glyph,
+	width: u16,
+	height: u16,
+	ascent: i32
+) -> Result<Vec<u8>, FntError> {
+	let height = usize::from(height);
+	let mut data = vec![0u8; usize::from(width).div_ceil(8) * height];
+	let bitmap = glyph.bitmap();
+	let bbox = glyph.bbox;
+	let top = ascent - bbox.offset_y - bbox.height as i32;
+	for y in 0 .. bitmap.height() {
+		for x in 0 .. bitmap.width() {
+			if !bitmap.get(x, y).unwrap_or(false) {
+				continue;
+			}
+			let cx = bbox.offset_x + x as i32;
+			let cy = top + y as i32;
+			if cx < 0 || cx >= width.into() || cy < 0 || cy >= height as i32 {
+				return Err(FntError::GlyphOutsideCell(glyph.encoding));
+			}
+			let (cx, cy) = (cx as usize, cy as usize);
+			data[cx / 8 * height + cy] |= 0x80 >> (cx % 8);
+		}
+	}
+	Ok(data)
+}
+
+impl Font {
+	/// Write this font as a Windows raster font resource in the `.FNT` format.
+	///
+	/// Only glyphs with an encoding up to 255 can be written, and every glyph must fit
+	/// between its origin and its advance. Gaps between the first and last glyph are
+	/// filled with the default glyph. The character set is taken from the
+	/// `CHARSET_REGISTRY` and `CHARSET_ENCODING` properties and defaults to ANSI.
+	/// Unicode and Latin-1 fonts are transcoded to ANSI, which is code page 1252.
+	pub fn write_fnt<W: Write>(
+		&self,
+		mut writer: W,
+		version: FntVersion
+	) -> Result<(), FntError> {
+		let (registry, encoding) = (
+			self.string_property("CHARSET_REGISTRY"),
+			self.string_property("CHARSET_ENCODING")
+		);
+		let unicode = match (registry, encoding) {
+			(Some(registry), _) if registry.eq_ignore_ascii_case("iso10646") => true,
+			(Some(registry), Some(encoding)) => {
+				registry.eq_ignore_ascii_case("iso8859") && encoding == "1"
+			},
+			_ => false
+		};
+		let encode = |code: u32| match unicode {
+			true => cp1252_byte(code),
+			false => u8::try_from(code).ok()
+		};
+
+		let glyphs: BTreeMap<u8, &Glyph> = self
+			.glyphs()
+			.into_iter()
+			.filter_map(|glyph| match encode(glyph.encoding) {
+				Some(byte) => Some((byte, glyph)),
+				None => {
+					warn!("Skipping glyph {} which cannot be encoded", glyph.encoding);
+					None
+				}
+			})
+			.collect();
+		let first_char = glyphs.keys().next().copied().unwrap_or(0x20);
+		let last_char = glyphs.keys().next_back().copied().unwrap_or(0x20);
+		let default_glyph = self
+			.default_glyph()
+			.filter(|g| encode(g.encoding).is_some());
+		let cell_glyphs: Vec<Option<&Glyph>> = (first_char ..= last_char)
+			.map(|byte| glyphs.get(&byte).copied().or(default_glyph))
+			.collect();
+
+		let (ascent, descent) = self.ascent_descent();
+		let pix_height =
+			u16::try_from(ascent + descent).map_err(|_| FntError::TooLarge)?;
+		let ascent_u16 = u16::try_from(ascent).map_err(|_| FntError::TooLarge)?;
+		let widths: Vec<u16> = cell_glyphs
+			.iter()
+			.map(|glyph| {
+				glyph
+					.map(|g| advance(g).clamp(0, 0xFFFF) as u16)
+					.unwrap_or(0)
+			})
+			.collect();
+		let max_width = widths.iter().copied().max().unwrap_or(0);
+		let fixed = widths.iter().all(|w| *w == max_width);
+		let avg_width = match self.integer_property("AVERAGE_WIDTH") {
+			Some(avg) => ((avg + 5) / 10).clamp(0, 0xFFFF) as u16,
+			None if widths.is_empty() => 0,
+			None => {
+				(widths.iter().map(|w| u32::from(*w)).sum::<u32>() / widths.len() as u32)
+					as u16
+			},
+		};
+		let internal_leading = match self.integer_property("PIXEL_SIZE") {
+			Some(px) => (i32::from(pix_height) - px).clamp(0, 0xFFFF) as u16,
+			None => 0
+		};
+		let charset = match (registry, encoding) {
+			_ if unicode => 0,
+			(Some(registry), Some(encoding)) => CHARSETS
+				.iter()
+				.find(|(_, r, e)| {
+					r.eq_ignore_ascii_case(registry) && e.eq_ignore_ascii_case(encoding)
+				})
+				.map(|(cs, ..)| *cs)
+				.or_else(|| encoding.strip_prefix("charset")?.parse().ok())
+				.unwrap_or(0),
+			_ => 0
+		};
+		let italic = matches!(self.string_property("SLANT"), Some("I" | "O"));
+		let weight = match self.string_property("WEIGHT_NAME") {
+			Some(name) if name.to_ascii_lowercase().contains("bold") => FW_BOLD,
+			_ => FW_NORMAL
+		};
+		let default_char = match self
+			.integer_property("DEFAULT_CHAR")
+			.and_then(|ch| encode(u32::try_from(ch).ok()?))
+		{
+			Some(byte) if (first_char ..= last_char).contains(&byte) => byte - first_char,
+			_ => 0
+		};
+		let break_char = 0x20u8.saturating_sub(first_char);
+		let face = self
+			.string_property("FAMILY_NAME")
+			.unwrap_or(&self.name)
+			.to_owned();
+		let copyright = self.string_property("COPYRIGHT").unwrap_or_default();
+
+		let (header_size, entry_size) = match version {
+			FntVersion::V2 => (V2_HEADER_SIZE, 4),
+			FntVersion::V3 => (V3_HEADER_SIZE, 6)
+		};
+		let bits_offset = header_size + (cell_glyphs.len() + 1) * entry_size;
+		let mut bitmaps = Vec::new();
+		let mut offsets = Vec::with_capacity(cell_glyphs.len() + 1);
+		for (glyph, width) in cell_glyphs.iter().zip(&widths) {
+			offsets.push(bits_offset + bitmaps.len());
+			if let Some(glyph) = glyph {
+				bitmaps.extend(render(glyph, *width, pix_height, ascent)?);
+			}
+		}
+		offsets.push(bits_offset + bitmaps.len());
+		let face_offset = bits_offset + bitmaps.len();
+		let size = face_offset + face.len() + 1;
+		if version == FntVersion::V2 && face_offset > 0xFFFF {
+			return Err(FntError::TooLarge);
+		}
+
+		let mut buf = Vec::with_capacity(size);
+		let u16 = |buf: &mut Vec<u8>, v: u16| buf.extend(v.to_le_bytes());
+		let u32 = |buf: &mut Vec<u8>, v: u32| buf.extend(v.to_le_bytes());
+		u16(&mut buf, match version {
+			FntVersion::V2 => 0x200,
+			FntVersion::V3 => 0x300
+		});
+		u32(&mut buf, size as u32);
+		let mut copyright = copyright.as_bytes()[.. copyright.len().min(60)].to_vec();
+		copyright.resize(60, 0);
+		buf.extend(copyright);
+		u16(&mut buf, 0); // dfType
+		u16(&mut buf, self.size.pt as u16);
+		u16(&mut buf, self.size.yres as u16);
+		u16(&mut buf, self.size.xres as u16);
+		u16(&mut buf, ascent_u16);
+		u16(&mut buf, internal_leading);
+		u16(&mut buf, 0); // dfExternalLeading
+		buf.extend([italic as u8, 0, 0]);
+		u16(&mut buf, weight);
+		buf.push(charset);
+		u16(&mut buf, match fixed {
+			true => max_width,
+			false => 0
+		});
+		u16(&mut buf, pix_height);
+		buf.push(match fixed {
+			true => 0,
+			false => VARIABLE_PITCH
+		});
+		u16(&mut buf, avg_width);
+		u16(&mut buf, max_width);
+		buf.extend([first_char, last_char, default_char, break_char]);
+		let width_bytes: u32 = widths.iter().map(|w| u32::from(w.div_ceil(8))).sum();
+		u16(&mut buf, ((width_bytes + 1) & !1) as u16);
+		u32(&mut buf, 0); // dfDevice
+		u32(&mut buf, face_offset as u32);
+		u32(&mut buf, 0); // dfBitsPointer
+		u32(&mut buf, bits_offset as u32);
+		buf.push(0); // dfReserved
+		if version == FntVersion::V3 {
+			u32(&mut buf, match fixed {
+				true => DFF_FIXED,
+				false => DFF_PROPORTIONAL
+			});
+			buf.resize(V3_HEADER_SIZE, 0);
+		}
+
+		for (width, offset) in widths.iter().chain(&[0]).zip(offsets) {
+			u16(&mut buf, *width);
+			match version {
+				FntVersion::V2 => u16(&mut buf, offset as u16),
+				FntVersion::V3 => u32(&mut buf, offset as u32)
+			}
+		}
+		buf.extend(bitmaps);
+		buf.extend(face.as_bytes());
+		buf.push(0);
+		writer.write_all(&buf)?;
+		Ok(())
+	}
+}
@@ -7,7 +7,7 @@ use std::{
 	fmt::{self, Debug, Display, Formatter}
 };
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
 pub struct BoundingBox {
 	pub width: u32,
 	pub height: u32,
@@ -15,6 +15,45 @@ pub struct BoundingBox {
 	pub offset_y: i32
 }
 
+impl BoundingBox {
+	/// Check whether this bounding box contains no pixels.
+	pub fn is_empty(self) -> bool {
+		self.width == 0 || self.height == 0
+	}
+
+	/// Get the smallest bounding box containing both bounding boxes. Empty bounding
+	/// boxes are ignored.
+	pub fn union(self, other: Self) -> Self {
+		if other.is_empty() {
+			return self;
+		}
+		if self.is_empty() {
+			return other;
+		}
+		let left = self.offset_x.min(other.offset_x);
+		let bottom = self.offset_y.min(other.offset_y);
+		let right =
+			(self.offset_x + self.width as i32).max(other.offset_x + other.width as i32);
+		let top = (self.offset_y + self.height as i32)
+			.max(other.offset_y + other.height as i32);
+		Self {
+			width: (right - left) as u32,
+			height: (top - bottom) as u32,
+			offset_x: left,
+			offset_y: bottom
+		}
+	}
+
+	/// Move this bounding box by the given offset.
+	pub fn translate(self, x: i32, y: i32) -> Self {
+		Self {
+			offset_x: self.offset_x + x,
+			offset_y: self.offset_y + y,
+			..self
+		}
+	}
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct Size {
 	pub pt: u32,
@@ -22,7 +61,7 @@ pub struct Size {
 	pub yres: u32
 }
 
-#[derive(Clone)]
+#[derive(Clone, Eq, PartialEq)]
 pub enum Value {
 	Integer(i32),
 	String(String)
@@ -92,7 +131,7 @@ impl Glyph {
 }
 
 /// A glyph wrapper that can be compared by its encoding.
-pub(crate) struct GlyphWrapper(Glyph);
+pub(crate) struct GlyphWrapper(pub(crate) Glyph);
 
 impl From<Glyph> for GlyphWrapper {
 	fn from(glyph: Glyph) -> Self {
@@ -185,6 +224,29 @@ impl Font {
 		self.properties.get(key)
 	}
 
+	/// Get the value of a property if it is a string.
+	pub(crate) fn string_property(&self, key: &str) -> Option<&str> {
+		match self.property(key)? {
+			Value::String(s) => Some(s),
+			Value::Integer(_) => None
+		}
+	}
+
+	/// Get the value of a property if it is an integer.
+	pub(crate) fn integer_property(&self, key: &str) -> Option<i32> {
+		match self.property(key)? {
+			Value::Integer(i) => Some(*i),
+			Value::String(_) => None
+		}
+	}
+
+	/// Get an iterator over all properties of the font.
+	pub fn properties(&self) -> impl Iterator<Item = (&str, &Value)> {
+		self.properties
+			.iter()
+			.map(|(key, value)| (key.as_str(), value))
+	}
+
 	/// Get an iterator over all glyphs of the font.
 	pub fn glyphs(&self) -> impl IntoIterator<Item = &Glyph> {
 		self.glyphs.iter().map(|gw| &gw.0)
@@ -194,4 +256,12 @@ impl Font {
 	pub fn glyph<I: GlyphIdx>(&self, ch: I) -> Option<&Glyph> {
 		self.glyphs.get(&ch.encoding()).map(|gw| &gw.0)
 	}
+
+	/// Get the glyph referenced by the `DEFAULT_CHAR` property, if any.
+	pub fn default_glyph(&self) -> Option<&Glyph> {
+		match self.property("DEFAULT_CHAR")? {
+			Value::Integer(enc) => self.glyph(u32::try_from(*enc).ok()?),
+			Value::String(_) => None
+		}
+	}
 }
@@ -0,0 +1,176 @@
+//! Grayscale rendering of bitmaps at arbitrary scales.
+
+use crate::{Bitmap, BoundingBox, Layout};
+
+/// Tolerance for rounding scaled coordinates, so that products like `5.0 * 0.3` do not
+/// add an empty row or column.
+const EPSILON: f64 = 1e-9;
+
+/// Options for rendering grayscale bitmaps.
+#[derive(Clone, Copy, Debug)]
+pub struct GrayscaleOptions {
+	/// The number of output pixels per bitmap pixel, e.g. 0.75 to display a 16 pixel
+	/// font with a height of 12 pixels. Scales that are not positive render an empty
+	/// bitmap.
+	pub scale: f64,
+
+	/// The gamma of the display. The coverage of each pixel is encoded as
+	/// `coverage.powf(1.0 / gamma)`, so a gamma of 1.0 keeps the coverage linear and
+	/// larger values make partially covered pixels brighter.
+	pub gamma: f64
+}
+
+impl Default for GrayscaleOptions {
+	fn default() -> Self {
+		Self {
+			scale: 1.0,
+			gamma: 1.0
+		}
+	}
+}
+
+/// An 8-bit grayscale bitmap, where each value is the coverage of the pixel from 0
+/// (empty) to 255 (fully covered).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GrayscaleBitmap {
+	bbox: BoundingBox,
+	data: Vec<u8>
+}
+
+impl GrayscaleBitmap {
+	/// Get the width of this bitmap in pixels.
+	pub fn width(&self) -> u32 {
+		self.bbox.width
+	}
+
+	/// Get the height of this bitmap in pixels.
+	pub fn height(&self) -> u32 {
+		self.bbox.height
+	}
+
+	/// Get the bounding box of this bitmap relative to the origin, in output pixels.
+	pub fn bounding_box(&self) -> BoundingBox {
+		self.bbox
+	}
+
+	/// Get the coverage values, stored row by row from the top left corner.
+	pub fn data(&self) -> &[u8] {
+		&self.data
+	}
+
+	/// Get the coverage of the pixel in column `x` and row `y`, counted from the top left
+	/// corner.
+	pub fn get(&self, x: usize, y: usize) -> Option<u8> {
+		if x >= self.width() as usize || y >= self.height() as usize {
+			return None;
+		}
+		Some(self.data[y * self.width() as usize + x])
+	}
+}
+
+/// Get the output pixels covering `len` bitmap pixels starting at `offset`, both
+/// relative to the origin.
+fn scaled_range(offset: i32, len: u32, scale: f64) -> (i32, u32) {
+	if len == 0 {
+		return (0, 0);
+	}
+	let start = (offset as f64 * scale + EPSILON).floor() as i32;
+	let end = ((offset as f64 + len as f64) * scale - EPSILON).ceil() as i32;
+	(start, (end - start).max(0) as u32)
+}
+
+/// For each of `out_len` output pixels starting at `out_offset`, list the bitmap pixels
+/// starting at `offset` that overlap it, together with the length of the overlap in
+/// output pixels.
+fn overlaps(
+	offset: i32,
+	len: u32,
+	out_offset: i32,
+	out_len: u32,
+	scale: f64
+) -> Vec<Vec<(usize, f64)>> {
+	(0 .. out_len as i32)
+		.map(|i| {
+			let lo = (out_offset + i) as f64 / scale;
+			let hi = (out_offset + i + 1) as f64 / scale;
+			let first = (lo.floor() as i32 - offset).max(0);
+			let last = (hi.ceil() as i32 - offset).min(len as i32);
+			(first .. last)
+				.map(|idx| {
+					let px = (offset + idx) as f64;
+					let overlap = hi.min(px + 1.0) - lo.max(px);
+					(idx as usize, overlap * scale)
+				})
+				.filter(|(_, overlap)| *overlap > 0.0)
+				.collect()
+		})
+		.collect()
+}
+
+impl Bitmap<'_> {
+	/// Render this bitmap at a scale that need not be an integer. Every output pixel
+	/// gets the fraction of its area that is covered by set pixels, which is the limit
+	/// of averaging over more and more samples per pixel, so that thin lines become
+	/// lighter instead of disappearing or doubling when downscaling.
+	///
+	/// The output is aligned to the origin of this bitmap, so the bounding box of the
+	/// output is the bounding box of this bitmap scaled and rounded outwards.
+	pub fn render_grayscale(self, options: &GrayscaleOptions) -> GrayscaleBitmap {
+		let scale = options.scale;
+		if !(scale > 0.0 && scale.is_finite()) {
+			return GrayscaleBitmap {
+				bbox: BoundingBox::default(),
+				data: Vec::new()
+			};
+		}
+		let exponent = match options.gamma > 0.0 {
+			true => options.gamma.recip(),
+			false => 1.0
+		};
+
+		let bbox = self.bbox;
+		let (offset_x, width) = scaled_range(bbox.offset_x, bbox.width, scale);
+		let (offset_y, height) = scaled_range(bbox.offset_y, bbox.height, scale);
+		let columns = overlaps(bbox.offset_x, bbox.width, offset_x, width, scale);
+		// rows are counted from the bottom, and flipped when drawing
+		let rows = overlaps(bbox.offset_y, bbox.height, offset_y, height, scale);
+
+		let mut data = Vec::with_capacity(width as usize * height as usize);
+		for row in rows.iter().rev() {
+			for column in &columns {
+				let coverage: f64 = row
+					.iter()
+					.flat_map(|&(y, overlap_y)| {
+						let y = bbox.height as usize - 1 - y;
+						column.iter().map(move |&(x, overlap_x)| {
+							match self.get(x, y).unwrap_or(false) {
+								true => overlap_x * overlap_y,
+								false => 0.0
+							}
+						})
+					})
+					.sum();
+				let value = coverage.clamp(0.0, 1.0).powf(exponent);
+				data.push((value * 255.0).round() as u8);
+			}
+		}
+
+		GrayscaleBitmap {
+			bbox: BoundingBox {
+				width,
+				height,
+				offset_x,
+				offset_y
+			},
+			data
+		}
+	}
+}
+
+impl Layout<'_> {
+	/// Render this layout like [`Bitmap::render_grayscale`]. The glyphs are placed at
+	/// their scaled pen positions, which need not fall on whole output pixels.
+	pub fn render_grayscale(&self, options: &GrayscaleOptions) -> GrayscaleBitmap {
+		self.render().as_bitmap().render_grayscale(options)
+	}
+}
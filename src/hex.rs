@@ -0,0 +1,222 @@
+//! The `.hex` format GNU Unifont is maintained in.
+//!
+//! Each line contains a code point and the glyph bitmap as hexadecimal digits,
+//! separated by a colon. All glyphs are 16 pixels high and usually 8 or 16 pixels
+//! wide. Combining marks are stored like any other glyph, and their horizontal offset
+//! is kept in a separate file using the same `code point:offset` syntax. See
+//! <https://unifoundry.com/unifont/> for details.
+
+use crate::{font::GlyphWrapper, layout::advance, BoundingBox, Font, Glyph, Size, Value};
+use bit_vec::BitVec;
+use log::warn;
+use std::{
+	collections::{BTreeSet, HashMap},
+	io::{self, BufRead, Write}
+};
+use thiserror::Error;
+
+/// The height of all glyphs.
+const HEIGHT: u32 = 16;
+
+/// The number of rows above the baseline.
+const ASCENT: i32 = 14;
+
+#[derive(Debug, Error)]
+pub enum HexError {
+	#[error("I/O Error: {0}")]
+	IOError(#[from] io::Error),
+
+	#[error("Syntax Error in line {0}")]
+	SyntaxError(usize),
+
+	#[error("Glyph {0} is {1} pixels wide, which is not a multiple of 8")]
+	UnsupportedWidth(u32, u32),
+
+	#[error("Glyph {0} exceeds the 16 pixels high character cell")]
+	GlyphOutsideCell(u32)
+}
+
+/// Parse the lines of a `.hex` or combining file into code point and value.
+fn lines<R: BufRead>(
+	reader: R
+) -> impl Iterator<Item = Result<(usize, u32, String), HexError>> {
+	reader
+		.lines()
+		.enumerate()
+		.map(|(ll, line)| (ll + 1, line))
+		.filter(|(_, line)| !matches!(line, Ok(line) if line.trim().is_empty()))
+		.map(|(ll, line)| {
+			let line = line?;
+			let (cp, value) = line
+				.trim()
+				.split_once(':')
+				.ok_or(HexError::SyntaxError(ll))?;
+			// from_str_radix also accepts a leading sign
+			if cp.is_empty() || !cp.bytes().all(|b| b.is_ascii_hexdigit()) {
+				return Err(HexError::SyntaxError(ll));
+			}
+			let cp =
+				u32::from_str_radix(cp, 16).map_err(|_| HexError::SyntaxError(ll))?;
+			Ok((ll, cp, value.to_owned()))
+		})
+}
+
+/// Get the width of the character cell of a glyph, and the x coordinate of its left
+/// edge. Combining marks without an advance keep their own offset.
+fn cell(glyph: &Glyph) -> (u32, i32) {
+	match advance(glyph) {
+		0 => (glyph.bbox.width, glyph.bbox.offset_x),
+		adv => (adv.max(0) as u32, 0)
+	}
+}
+
+impl Font {
+	/// Read a font in the GNU Unifont `.hex` format. The glyphs are placed on a
+	/// baseline 2 pixels above the bottom of the 16 pixels high character cell.
+	pub fn read_hex<R: BufRead>(reader: R) -> Result<Self, HexError> {
+		let size = Size {
+			pt: HEIGHT,
+			xres: 75,
+			yres: 75
+		};
+		let mut glyphs = BTreeSet::new();
+		for line in lines(reader) {
+			let (ll, encoding, digits) = line?;
+			if digits.is_empty()
+				|| digits.len() % (2 * HEIGHT as usize) != 0
+				|| !digits.bytes().all(|b| b.is_ascii_hexdigit())
+			{
+				return Err(HexError::SyntaxError(ll));
+			}
+			let bytes = (0 .. digits.len())
+				.step_by(2)
+				.map(|i| u8::from_str_radix(digits.get(i .. i + 2)?, 16).ok())
+				.collect::<Option<Vec<u8>>>()
+				.ok_or(HexError::SyntaxError(ll))?;
+			let stride = bytes.len() / HEIGHT as usize;
+			let width = stride as u32 * 8;
+			let dwidth = width as f64;
+			glyphs.insert(GlyphWrapper(Glyph {
+				name: format!("uni{encoding:04X}"),
+				encoding,
+				swidth: Some((
+					(dwidth * 72000.0 / (size.pt as f64 * size.xres as f64)).round(),
+					0.0
+				)),
+				dwidth: Some((dwidth, 0.0)),
+				bbox: BoundingBox {
+					width,
+					height: HEIGHT,
+					offset_x: 0,
+					offset_y: ASCENT - HEIGHT as i32
+				},
+				bitmap: bytes.chunks(stride).map(BitVec::from_bytes).collect()
+			}));
+		}
+
+		let bbox = glyphs
+			.iter()
+			.fold(BoundingBox::default(), |bbox, gw| bbox.union(gw.0.bbox));
+		Ok(Self {
+			version: None,
+			name: format!(
+				"-gnu-Unifont-Medium-R-Normal-Sans-{HEIGHT}-{}-75-75-c-80-iso10646-1",
+				HEIGHT * 10
+			),
+			bbox,
+			size,
+			properties: HashMap::from([
+				("FONT_ASCENT".into(), Value::Integer(ASCENT)),
+				(
+					"FONT_DESCENT".into(),
+					Value::Integer(HEIGHT as i32 - ASCENT)
+				)
+			]),
+			glyphs
+		})
+	}
+
+	/// Apply a Unifont combining file, which lists the horizontal offset of combining
+	/// marks. The listed glyphs lose their advance and are moved by the offset, which
+	/// is usually negative so that the mark overlaps the preceding glyph.
+	pub fn read_hex_combining<R: BufRead>(&mut self, reader: R) -> Result<(), HexError> {
+		for line in lines(reader) {
+			let (ll, encoding, offset) = line?;
+			let offset: i32 = offset
+				.trim()
+				.parse()
+				.map_err(|_| HexError::SyntaxError(ll))?;
+			let Some(mut gw) = self.glyphs.take(&encoding) else {
+				warn!("Ignoring combining offset of missing glyph U+{encoding:04X}");
+				continue;
+			};
+			gw.0.bbox.offset_x = offset;
+			gw.0.swidth = Some((0.0, 0.0));
+			gw.0.dwidth = Some((0.0, 0.0));
+			self.bbox = self.bbox.union(gw.0.bbox);
+			self.glyphs.insert(gw);
+		}
+		Ok(())
+	}
+
+	/// Write this font in the GNU Unifont `.hex` format. Every glyph must fit into a
+	/// 16 pixels high character cell with the baseline 2 pixels above its bottom,
+	/// and its advance must be a multiple of 8. Combining marks without an advance
+	/// keep the width of their bounding box, and their offset can be written using
+	/// [`write_hex_combining`](Self::write_hex_combining).
+	pub fn write_hex<W: Write>(&self, mut writer: W) -> Result<(), HexError> {
+		for glyph in self.glyphs() {
+			let (width, left) = cell(glyph);
+			if width == 0 || width % 8 != 0 {
+				return Err(HexError::UnsupportedWidth(glyph.encoding, width));
+			}
+			let stride = width as usize / 8;
+			let mut data = vec![0u8; stride * HEIGHT as usize];
+			let bitmap = glyph.bitmap();
+			let bbox = glyph.bbox;
+			let top = ASCENT - bbox.offset_y - bbox.height as i32;
+			for y in 0 .. bitmap.height() {
+				for x in 0 .. bitmap.width() {
+					if !bitmap.get(x, y).unwrap_or(false) {
+						continue;
+					}
+					let cx = bbox.offset_x - left + x as i32;
+					let cy = top + y as i32;
+					if cx < 0 || cx >= width as i32 || cy < 0 || cy >= HEIGHT as i32 {
+						return Err(HexError::GlyphOutsideCell(glyph.encoding));
+					}
+					let (cx, cy) = (cx as usize, cy as usize);
+					data[cy * stride + cx / 8] |= 0x80 >> (cx % 8);
+				}
+			}
+
+			write_code_point(&mut writer, glyph.encoding)?;
+			for byte in data {
+				write!(writer, "{byte:02X}")?;
+			}
+			writeln!(writer)?;
+		}
+		Ok(())
+	}
+
+	/// Write the Unifont combining file for this font, listing the horizontal offset
+	/// of every glyph without an advance.
+	pub fn write_hex_combining<W: Write>(&self, mut writer: W) -> Result<(), HexError> {
+		for glyph in self.glyphs() {
+			if advance(glyph) == 0 {
+				write_code_point(&mut writer, glyph.encoding)?;
+				writeln!(writer, "{}", glyph.bbox.offset_x)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Write a code point followed by a colon, using 4 digits for the Basic Multilingual
+/// Plane and 6 digits otherwise, like Unifont does.
+fn write_code_point<W: Write>(writer: &mut W, cp: u32) -> io::Result<()> {
+	match cp {
+		0 ..= 0xFFFF => write!(writer, "{cp:04X}:"),
+		_ => write!(writer, "{cp:06X}:")
+	}
+}
@@ -0,0 +1,223 @@
+use crate::{BitmapBuf, BoundingBox, Font, Glyph};
+use unicode_general_category::{get_general_category, GeneralCategory};
+use unicode_normalization::char::decompose_canonical;
+
+/// A glyph placed relative to the origin of a [`Layout`].
+#[derive(Clone, Copy, Debug)]
+pub struct PositionedGlyph<'a> {
+	pub glyph: &'a Glyph,
+
+	/// The x coordinate of the glyph origin.
+	pub x: i32,
+
+	/// The y coordinate of the glyph origin.
+	pub y: i32
+}
+
+impl PositionedGlyph<'_> {
+	/// Get the bounding box of this glyph, relative to the origin of the layout.
+	pub fn bounding_box(&self) -> BoundingBox {
+		self.glyph.bbox.translate(self.x, self.y)
+	}
+}
+
+/// A line of text laid out using the glyphs of a [`Font`].
+#[derive(Clone, Debug)]
+pub struct Layout<'a> {
+	glyphs: Vec<PositionedGlyph<'a>>,
+	advance: i32
+}
+
+impl<'a> Layout<'a> {
+	/// Get the positioned glyphs of this layout.
+	pub fn glyphs(&self) -> &[PositionedGlyph<'a>] {
+		&self.glyphs
+	}
+
+	/// Get the horizontal distance the pen moved while laying out the text.
+	pub fn advance(&self) -> i32 {
+		self.advance
+	}
+
+	/// Get the smallest bounding box containing all glyphs of this layout.
+	pub fn bounding_box(&self) -> BoundingBox {
+		self.glyphs.iter().fold(BoundingBox::default(), |bbox, pg| {
+			bbox.union(pg.bounding_box())
+		})
+	}
+
+	/// Render this layout into a bitmap.
+	pub fn render(&self) -> BitmapBuf {
+		let mut buf = BitmapBuf::new(self.bounding_box());
+		for pg in &self.glyphs {
+			buf.draw(pg.glyph.bitmap(), pg.x, pg.y);
+		}
+		buf
+	}
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum CharClass {
+	/// A character that is drawn and advances the pen.
+	Base,
+
+	/// A non-spacing or enclosing mark that attaches to the preceding base.
+	Mark,
+
+	/// A character that is neither drawn nor advances the pen.
+	Ignorable
+}
+
+impl CharClass {
+	fn of(ch: char) -> Self {
+		match ch {
+			// combining grapheme joiner and variation selectors
+			'\u{034F}'
+			| '\u{180B}' ..= '\u{180F}'
+			| '\u{FE00}' ..= '\u{FE0F}'
+			| '\u{E0100}' ..= '\u{E01EF}' => Self::Ignorable,
+			// prepended concatenation marks are visible format characters
+			'\u{0600}' ..= '\u{0605}'
+			| '\u{06DD}'
+			| '\u{070F}'
+			| '\u{0890}' ..= '\u{0891}'
+			| '\u{08E2}'
+			| '\u{110BD}'
+			| '\u{110CD}' => Self::Base,
+			_ => match get_general_category(ch) {
+				GeneralCategory::NonspacingMark | GeneralCategory::EnclosingMark => {
+					Self::Mark
+				},
+				GeneralCategory::Format => Self::Ignorable,
+				_ => Self::Base
+			}
+		}
+	}
+}
+
+/// Get the horizontal advance of the glyph in device pixels.
+pub(crate) fn advance(glyph: &Glyph) -> i32 {
+	match glyph.dwidth {
+		Some((dwx, _)) => dwx.round() as i32,
+		None => glyph.bbox.width as i32
+	}
+}
+
+struct LayoutBuilder<'a> {
+	font: &'a Font,
+	glyphs: Vec<PositionedGlyph<'a>>,
+	pen: i32,
+
+	/// The origin and advance of the last base glyph.
+	base: Option<(i32, i32)>
+}
+
+impl<'a> LayoutBuilder<'a> {
+	fn push_base(&mut self, glyph: &'a Glyph) {
+		let adv = advance(glyph);
+		self.glyphs.push(PositionedGlyph {
+			glyph,
+			x: self.pen,
+			y: 0
+		});
+		self.base = Some((self.pen, adv));
+		self.pen += adv;
+	}
+
+	fn push_mark(&mut self, glyph: &'a Glyph) {
+		let Some((base_x, base_adv)) = self.base else {
+			// a mark without a base is drawn like a regular glyph
+			self.push_base(glyph);
+			self.base = None;
+			return;
+		};
+		// marks with a negative offset (like unifont's) are designed to be drawn at
+		// the pen position after the base, other marks are centered on the base
+		let x = if glyph.bbox.offset_x < 0 {
+			base_x + base_adv
+		} else {
+			base_x + (base_adv - advance(glyph)) / 2
+		};
+		self.glyphs.push(PositionedGlyph { glyph, x, y: 0 });
+	}
+
+	fn push_char(&mut self, ch: char) {
+		let class = CharClass::of(ch);
+		if class == CharClass::Ignorable {
+			return;
+		}
+
+		if let Some(glyph) = self.font.glyph(ch) {
+			match class {
+				CharClass::Mark => self.push_mark(glyph),
+				_ => self.push_base(glyph)
+			}
+			return;
+		}
+
+		if let Some(parts) = self.font.decomposition(ch) {
+			for part in parts {
+				self.push_char(part);
+			}
+			return;
+		}
+
+		// missing marks are dropped, missing bases are replaced by the default glyph
+		if class == CharClass::Base {
+			match self.font.default_glyph() {
+				Some(glyph) => self.push_base(glyph),
+				None => self.base = None
+			}
+		}
+	}
+}
+
+impl Font {
+	/// Lay out a line of text. Combining marks are attached to the preceding base
+	/// glyph without advancing the pen, and zero-width format characters are skipped.
+	/// Characters missing from the font are synthesized from their canonical
+	/// decomposition if possible, or replaced by the [default glyph][Self::default_glyph]
+	/// otherwise.
+	pub fn layout(&self, text: &str) -> Layout<'_> {
+		let mut builder = LayoutBuilder {
+			font: self,
+			glyphs: Vec::new(),
+			pen: 0,
+			base: None
+		};
+		for ch in text.chars() {
+			builder.push_char(ch);
+		}
+		Layout {
+			glyphs: builder.glyphs,
+			advance: builder.pen
+		}
+	}
+
+	/// Get the canonical decomposition of a character, if it decomposes and every part
+	/// of it is contained in the font.
+	fn decomposition(&self, ch: char) -> Option<Vec<char>> {
+		let mut parts = Vec::new();
+		decompose_canonical(ch, |part| parts.push(part));
+		(parts.len() > 1 && parts.iter().all(|part| self.glyph(*part).is_some()))
+			.then_some(parts)
+	}
+
+	/// Synthesize a glyph for a precomposed character that is missing from the font by
+	/// overlaying the glyphs of its canonical decomposition. Returns `None` if the
+	/// character does not decompose or any part of it is missing from the font.
+	pub fn synthesize_glyph(&self, ch: char) -> Option<Glyph> {
+		let parts = self.decomposition(ch)?;
+		let base = self.glyph(parts[0])?;
+		let layout = self.layout(&parts.into_iter().collect::<String>());
+		let bitmap = layout.render();
+		Some(Glyph {
+			name: format!("uni{:04X}", ch as u32),
+			encoding: ch as u32,
+			swidth: base.swidth,
+			dwidth: Some((layout.advance() as f64, 0.0)),
+			bbox: bitmap.bbox,
+			bitmap: bitmap.data
+		})
+	}
+}
@@ -17,22 +17,89 @@
 //! let font = Font::read(reader)?;
 //! ```
 //!
+//! Alternatively, [`Font::open`] detects whether a file is a BDF, PCF, PSF or PF2 font.
+//!
+//! ## Features
+//!
+//! No features are enabled by default. With the optional `gzip` feature, compressed
+//! fonts like `.pcf.gz` are decompressed on the fly by [`Font::open`]. The optional
+//! `png` feature renders bitmaps to PNG images, and the `image` feature converts them
+//! into images of the [`image`] crate. The `sprite-sheet` feature exports glyphs to
+//! editable sprite sheets with a JSON sidecar and imports them back.
+//!
+//! ```toml
+//! [dependencies]
+//! bdf-reader = { version = "0.1", features = ["gzip"] }
+//! ```
+//!
 //!  [wikipedia]: https://en.wikipedia.org/wiki/Glyph_Bitmap_Distribution_Format
+//!  [`image`]: https://crates.io/crates/image
 
 use std::{io, str::FromStr};
 use thiserror::Error;
 
+mod atlas;
 mod bitmap;
+mod bmfont;
+mod compress;
+mod coverage;
+mod fnt;
 mod font;
+mod grayscale;
+mod hex;
+mod layout;
+mod lint;
+mod merge;
+mod netpbm;
+mod outline;
+mod pcf;
+mod pf2;
+mod psf;
+#[cfg(any(feature = "image", feature = "png"))]
+mod raster;
 mod reader;
+mod sdf;
+mod sfnt;
+mod specimen;
+#[cfg(feature = "sprite-sheet")]
+mod sprite;
+mod subset;
+mod svg;
+mod tga;
 mod tokens;
 
-pub use bitmap::Bitmap;
+pub use atlas::{Atlas, AtlasError, AtlasGlyph, AtlasOptions, UvRect};
+pub use bitmap::{Bitmap, BitmapBuf};
+pub use bmfont::{BmFontError, BmFontFormat, BmFontOptions};
+pub use coverage::{BlockCoverage, Coverage, CoverageComparison, ScriptCoverage};
+pub use fnt::{FntError, FntVersion};
 pub use font::{BoundingBox, Font, Glyph, Size, Value};
+pub use grayscale::{GrayscaleBitmap, GrayscaleOptions};
+pub use hex::HexError;
+pub use layout::{Layout, PositionedGlyph};
+pub use lint::{Check, Finding, Severity};
+pub use merge::{ConflictPolicy, MergeError, MergeOptions, MergeReport, Rebaseline};
+pub use netpbm::NetpbmEncoding;
+pub use outline::{Contour, OutlineOptions, Winding};
+pub use pcf::{PcfError, PcfOptions};
+pub use pf2::Pf2Error;
+pub use psf::{PsfError, PsfOptions, PsfVersion};
+#[cfg(feature = "png")]
+pub use raster::PngError;
+#[cfg(any(feature = "image", feature = "png"))]
+pub use raster::RenderOptions;
 use reader::State;
+pub use sdf::{DistanceField, SdfOptions};
+pub use sfnt::SfntError;
+pub use specimen::SpecimenOptions;
+#[cfg(feature = "sprite-sheet")]
+pub use sprite::{SpriteSheetError, SpriteSheetImage, SpriteSheetOptions};
+pub use subset::CharSet;
+pub use svg::SvgOptions;
 use tokens::Token;
 
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum Error {
 	#[error("I/O Error: {0}")]
 	IOError(#[from] io::Error),
@@ -65,5 +132,14 @@ pub enum Error {
 	InvalidPropertyValue(#[source] <i32 as FromStr>::Err),
 
 	#[error("Invalid bitmap value: {0}")]
-	InvalidBitmapValue(String)
+	InvalidBitmapValue(String),
+
+	#[error("PCF Error: {0}")]
+	PcfError(#[from] PcfError),
+
+	#[error("PSF Error: {0}")]
+	PsfError(#[from] PsfError),
+
+	#[error("PF2 Error: {0}")]
+	Pf2Error(#[from] Pf2Error)
 }
@@ -0,0 +1,256 @@
+//! Checks for spec violations and inconsistencies that are not caught while parsing.
+
+use crate::{Font, Glyph};
+use std::fmt::{self, Display, Formatter};
+
+/// The XLFD properties that every font should define.
+const XLFD_PROPERTIES: &[&str] = &[
+	"FOUNDRY",
+	"FAMILY_NAME",
+	"WEIGHT_NAME",
+	"SLANT",
+	"SETWIDTH_NAME",
+	"ADD_STYLE_NAME",
+	"PIXEL_SIZE",
+	"POINT_SIZE",
+	"RESOLUTION_X",
+	"RESOLUTION_Y",
+	"SPACING",
+	"AVERAGE_WIDTH",
+	"CHARSET_REGISTRY",
+	"CHARSET_ENCODING"
+];
+
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Severity {
+	/// The font works, but is likely to render differently than intended.
+	Warning,
+
+	/// The font violates the specification and may be rejected by other software.
+	Error
+}
+
+/// The check that produced a [`Finding`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Check {
+	/// The glyph bounding box is not contained in the font bounding box.
+	GlyphOutsideBoundingBox,
+
+	/// A bitmap row has fewer bits than the glyph is wide.
+	ShortBitmapRow,
+
+	/// A bitmap row has more hex digits than required for the glyph width.
+	ExtraBitmapData,
+
+	/// A bitmap row has padding bits set beyond the glyph width.
+	NonZeroPadding,
+
+	/// The glyph has no `SWIDTH` or `DWIDTH`.
+	MissingWidth,
+
+	/// The `DWIDTH` does not match the `SWIDTH` at the font's size and resolution.
+	InconsistentWidth,
+
+	/// A required property is not defined.
+	MissingProperty,
+
+	/// `FONT_ASCENT` and `FONT_DESCENT` do not match the font bounding box.
+	AscentDescentMismatch,
+
+	/// `PIXEL_SIZE` does not match `POINT_SIZE` at `RESOLUTION_Y`.
+	PixelSizeMismatch
+}
+
+impl Check {
+	/// Get the severity of findings produced by this check.
+	pub fn severity(self) -> Severity {
+		match self {
+			Self::GlyphOutsideBoundingBox | Self::ShortBitmapRow | Self::MissingWidth => {
+				Severity::Error
+			},
+			_ => Severity::Warning
+		}
+	}
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Finding {
+	pub check: Check,
+
+	/// The encoding of the affected glyph, or `None` if the finding concerns the
+	/// whole font.
+	pub glyph: Option<u32>,
+
+	pub message: String
+}
+
+impl Finding {
+	/// Get the severity of this finding.
+	pub fn severity(&self) -> Severity {
+		self.check.severity()
+	}
+}
+
+impl Display for Finding {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		write!(f, "{:?}: ", self.severity())?;
+		if let Some(enc) = self.glyph {
+			write!(f, "glyph {enc}: ")?;
+		}
+		write!(f, "{}", self.message)
+	}
+}
+
+struct Linter<'a> {
+	font: &'a Font,
+	findings: Vec<Finding>
+}
+
+impl Linter<'_> {
+	fn report(&mut self, check: Check, glyph: Option<&Glyph>, message: String) {
+		self.findings.push(Finding {
+			check,
+			glyph: glyph.map(|glyph| glyph.encoding),
+			message
+		});
+	}
+
+	fn check_glyph(&mut self, glyph: &Glyph) {
+		let bbox = glyph.bbox;
+		if !bbox.is_empty() && bbox.union(self.font.bbox) != self.font.bbox {
+			self.report(
+				Check::GlyphOutsideBoundingBox,
+				Some(glyph),
+				format!("BBX {bbox:?} exceeds FONTBOUNDINGBOX {:?}", self.font.bbox)
+			);
+		}
+
+		let width = bbox.width as usize;
+		let padded = width.div_ceil(8) * 8;
+		for (y, row) in glyph.bitmap.iter().enumerate() {
+			if row.len() < width {
+				self.report(
+					Check::ShortBitmapRow,
+					Some(glyph),
+					format!(
+						"row {y} has {} bits but the glyph is {width} wide",
+						row.len()
+					)
+				);
+			} else if row.len() > padded {
+				self.report(
+					Check::ExtraBitmapData,
+					Some(glyph),
+					format!(
+						"row {y} has {} hex digits but only {} are required",
+						row.len() / 4,
+						padded / 4
+					)
+				);
+			}
+			if row.iter().skip(width).any(|bit| bit) {
+				self.report(
+					Check::NonZeroPadding,
+					Some(glyph),
+					format!("row {y} has padding bits set")
+				);
+			}
+		}
+
+		let (Some((swx, _)), Some((dwx, _))) = (glyph.swidth, glyph.dwidth) else {
+			self.report(
+				Check::MissingWidth,
+				Some(glyph),
+				"SWIDTH and DWIDTH are mandatory".into()
+			);
+			return;
+		};
+		let size = self.font.size;
+		let expected = swx * size.pt as f64 / 1000.0 * size.xres as f64 / 72.0;
+		if (expected.round() - dwx).abs() >= 1.0 {
+			self.report(
+				Check::InconsistentWidth,
+				Some(glyph),
+				format!(
+					"DWIDTH {dwx} does not match SWIDTH {swx} ({expected:.2} pixels)"
+				)
+			);
+		}
+	}
+
+	fn check_properties(&mut self) {
+		for key in XLFD_PROPERTIES
+			.iter()
+			.chain(&["FONT_ASCENT", "FONT_DESCENT"])
+		{
+			if self.font.property(key).is_none() {
+				self.report(
+					Check::MissingProperty,
+					None,
+					format!("missing property {key}")
+				);
+			}
+		}
+
+		let bbox = self.font.bbox;
+		if let (Some(ascent), Some(descent)) = (
+			self.font.integer_property("FONT_ASCENT"),
+			self.font.integer_property("FONT_DESCENT")
+		) {
+			if ascent + descent != bbox.height as i32 {
+				self.report(
+					Check::AscentDescentMismatch,
+					None,
+					format!(
+						"FONT_ASCENT {ascent} + FONT_DESCENT {descent} does not match the \
+						 FONTBOUNDINGBOX height {}",
+						bbox.height
+					)
+				);
+			}
+			if bbox.offset_y != -descent {
+				self.report(
+					Check::AscentDescentMismatch,
+					None,
+					format!(
+						"FONT_DESCENT {descent} does not match the FONTBOUNDINGBOX y offset {}",
+						bbox.offset_y
+					)
+				);
+			}
+		}
+
+		if let (Some(pixel_size), Some(point_size), Some(res_y)) = (
+			self.font.integer_property("PIXEL_SIZE"),
+			self.font.integer_property("POINT_SIZE"),
+			self.font.integer_property("RESOLUTION_Y")
+		) {
+			let expected = (point_size as f64 / 10.0 * res_y as f64 / 72.0).round();
+			if pixel_size as f64 != expected {
+				self.report(
+					Check::PixelSizeMismatch,
+					None,
+					format!(
+						"PIXEL_SIZE {pixel_size} does not match POINT_SIZE {point_size} at \
+						 RESOLUTION_Y {res_y} ({expected} pixels)"
+					)
+				);
+			}
+		}
+	}
+}
+
+impl Font {
+	/// Check this font for spec violations and inconsistencies.
+	pub fn lint(&self) -> Vec<Finding> {
+		let mut linter = Linter {
+			font: self,
+			findings: Vec::new()
+		};
+		linter.check_properties();
+		for glyph in self.glyphs() {
+			linter.check_glyph(glyph);
+		}
+		linter.findings
+	}
+}
@@ -0,0 +1,269 @@
+use crate::{font::GlyphWrapper, BoundingBox, Font, Glyph, Size, Value};
+use bit_vec::BitVec;
+use thiserror::Error;
+
+/// Properties that describe the metrics of the whole font. These are recomputed or kept
+/// when merging instead of being subject to the [`ConflictPolicy`].
+const METRIC_PROPERTIES: &[&str] = &[
+	"FONT_ASCENT",
+	"FONT_DESCENT",
+	"PIXEL_SIZE",
+	"POINT_SIZE",
+	"RESOLUTION_X",
+	"RESOLUTION_Y"
+];
+
+#[derive(Debug, Error)]
+pub enum MergeError {
+	#[error("Glyph {0} is contained in both fonts")]
+	GlyphConflict(u32),
+
+	#[error("Property {0} has conflicting values")]
+	PropertyConflict(String)
+}
+
+/// What to do when both fonts contain a glyph with the same encoding or a property
+/// with the same name but a different value.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ConflictPolicy {
+	/// Keep the glyph or property of the font that is merged into.
+	#[default]
+	KeepFirst,
+
+	/// Replace the glyph or property with the one from the incoming font.
+	Overwrite,
+
+	/// Abort the merge with an error.
+	Error
+}
+
+/// How to move the glyphs of the incoming font vertically.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Rebaseline {
+	/// Keep the glyphs on their original baseline.
+	#[default]
+	Keep,
+
+	/// Move the glyphs so that the descent of the incoming font aligns with the
+	/// descent of this font.
+	AlignDescent,
+
+	/// Move the glyphs up by the given amount of pixels.
+	Shift(i32)
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct MergeOptions {
+	/// The conflict policy for glyphs.
+	pub glyphs: ConflictPolicy,
+
+	/// The conflict policy for properties.
+	pub properties: ConflictPolicy,
+
+	/// Integer factor to scale the incoming glyphs by. Scaling is applied before the
+	/// glyphs are rebaselined.
+	pub scale: u32,
+
+	/// Vertical adjustment of the incoming glyphs.
+	pub rebaseline: Rebaseline
+}
+
+impl Default for MergeOptions {
+	fn default() -> Self {
+		Self {
+			glyphs: ConflictPolicy::default(),
+			properties: ConflictPolicy::default(),
+			scale: 1,
+			rebaseline: Rebaseline::default()
+		}
+	}
+}
+
+/// The changes made by [`Font::merge`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MergeReport {
+	/// Encodings of the glyphs that were added to the font.
+	pub added_glyphs: Vec<u32>,
+
+	/// Encodings of the glyphs that were replaced by the incoming font.
+	pub replaced_glyphs: Vec<u32>,
+
+	/// Encodings of the incoming glyphs that were dropped in favour of existing ones.
+	pub kept_glyphs: Vec<u32>,
+
+	/// Names of the properties that were added to the font.
+	pub added_properties: Vec<String>,
+
+	/// Names of the properties that were replaced by the incoming font.
+	pub replaced_properties: Vec<String>,
+
+	/// The pixel sizes of this font and of the incoming font after scaling, if they
+	/// differ. The size of this font is kept either way.
+	pub pixel_size_mismatch: Option<(i32, i32)>
+}
+
+fn scale_glyph(glyph: &mut Glyph, scale: u32) {
+	if scale == 1 {
+		return;
+	}
+	let bitmap = glyph.bitmap();
+	let width = bitmap.width() * scale as usize;
+	let mut data = Vec::with_capacity(bitmap.height() * scale as usize);
+	for y in 0 .. bitmap.height() {
+		let mut row = BitVec::from_elem(width.div_ceil(8) * 8, false);
+		for x in 0 .. width {
+			row.set(x, bitmap.get(x / scale as usize, y).unwrap_or(false));
+		}
+		for _ in 0 .. scale {
+			data.push(row.clone());
+		}
+	}
+
+	let factor = scale as f64;
+	glyph.bitmap = data;
+	glyph.bbox = BoundingBox {
+		width: glyph.bbox.width * scale,
+		height: glyph.bbox.height * scale,
+		offset_x: glyph.bbox.offset_x * scale as i32,
+		offset_y: glyph.bbox.offset_y * scale as i32
+	};
+	glyph.dwidth = glyph.dwidth.map(|(dwx, dwy)| (dwx * factor, dwy * factor));
+}
+
+/// Recompute the scalable width of a glyph from its device width at the size of the
+/// font it is merged into.
+fn rescale_swidth(glyph: &mut Glyph, size: Size) {
+	if size.pt == 0 || size.xres == 0 || size.yres == 0 {
+		return;
+	}
+	if let Some((dwx, dwy)) = glyph.dwidth {
+		let swidth = |dw: f64, res: u32| (dw * 72000.0 / (size.pt * res) as f64).round();
+		glyph.swidth = Some((swidth(dwx, size.xres), swidth(dwy, size.yres)));
+	}
+}
+
+impl Font {
+	/// Get the ascent and descent of the font, preferring the `FONT_ASCENT` and
+	/// `FONT_DESCENT` properties over the font bounding box.
+	pub(crate) fn ascent_descent(&self) -> (i32, i32) {
+		(
+			self.integer_property("FONT_ASCENT")
+				.unwrap_or(self.bbox.height as i32 + self.bbox.offset_y),
+			self.integer_property("FONT_DESCENT")
+				.unwrap_or(-self.bbox.offset_y)
+		)
+	}
+
+	/// Get the number of pixels per em, preferring the `PIXEL_SIZE` property over the
+	/// ascent and descent.
+	pub(crate) fn pixels_per_em(&self) -> i32 {
+		let (ascent, descent) = self.ascent_descent();
+		self.integer_property("PIXEL_SIZE")
+			.filter(|px| *px > 0)
+			.unwrap_or(ascent + descent)
+			.max(1)
+	}
+
+	/// Merge the glyphs and properties of another font into this font.
+	///
+	/// The incoming glyphs are scaled and rebaselined according to the options before
+	/// they are merged. The font bounding box as well as the `FONT_ASCENT` and
+	/// `FONT_DESCENT` properties are extended to cover all glyphs, while the name and
+	/// size of this font are kept. This includes the `SIZE` and the `PIXEL_SIZE`,
+	/// `POINT_SIZE` and `RESOLUTION_*` properties; the `SWIDTH` of the incoming glyphs
+	/// is recomputed for this size, and a different pixel size of the scaled incoming
+	/// font is reported in [`MergeReport::pixel_size_mismatch`].
+	///
+	/// If a conflict is resolved with [`ConflictPolicy::Error`], this font is left
+	/// unchanged.
+	pub fn merge(
+		&mut self,
+		other: Font,
+		options: &MergeOptions
+	) -> Result<MergeReport, MergeError> {
+		let scale = options.scale.max(1);
+		let (other_ascent, other_descent) = other.ascent_descent();
+		let (other_ascent, other_descent) =
+			(other_ascent * scale as i32, other_descent * scale as i32);
+		let shift = match options.rebaseline {
+			Rebaseline::Keep => 0,
+			Rebaseline::AlignDescent => other_descent - self.ascent_descent().1,
+			Rebaseline::Shift(shift) => shift
+		};
+
+		if options.glyphs == ConflictPolicy::Error {
+			if let Some(gw) = other
+				.glyphs
+				.iter()
+				.find(|gw| self.glyphs.contains(&gw.0.encoding))
+			{
+				return Err(MergeError::GlyphConflict(gw.0.encoding));
+			}
+		}
+		if options.properties == ConflictPolicy::Error {
+			for (key, value) in &other.properties {
+				if METRIC_PROPERTIES.contains(&key.as_str()) {
+					continue;
+				}
+				if self.properties.get(key).is_some_and(|v| v != value) {
+					return Err(MergeError::PropertyConflict(key.clone()));
+				}
+			}
+		}
+
+		let mut report = MergeReport::default();
+		let (pixel_size, other_pixel_size) =
+			(self.pixels_per_em(), other.pixels_per_em() * scale as i32);
+		if pixel_size != other_pixel_size {
+			report.pixel_size_mismatch = Some((pixel_size, other_pixel_size));
+		}
+
+		for (key, value) in other.properties {
+			if METRIC_PROPERTIES.contains(&key.as_str()) {
+				continue;
+			}
+			match self.properties.get(&key) {
+				None => report.added_properties.push(key.clone()),
+				Some(v) if *v == value => continue,
+				Some(_) if options.properties == ConflictPolicy::Overwrite => {
+					report.replaced_properties.push(key.clone())
+				},
+				Some(_) => continue
+			}
+			self.properties.insert(key, value);
+		}
+
+		let mut bbox = self.bbox;
+		for GlyphWrapper(mut glyph) in other.glyphs {
+			let encoding = glyph.encoding;
+			if self.glyphs.contains(&encoding) {
+				if options.glyphs != ConflictPolicy::Overwrite {
+					report.kept_glyphs.push(encoding);
+					continue;
+				}
+				report.replaced_glyphs.push(encoding);
+			} else {
+				report.added_glyphs.push(encoding);
+			}
+
+			scale_glyph(&mut glyph, scale);
+			rescale_swidth(&mut glyph, self.size);
+			glyph.bbox.offset_y += shift;
+			bbox = bbox.union(glyph.bbox);
+			self.glyphs.replace(glyph.into());
+		}
+		self.bbox = bbox;
+
+		if !report.added_glyphs.is_empty() || !report.replaced_glyphs.is_empty() {
+			let (ascent, descent) = self.ascent_descent();
+			let ascent = ascent.max(other_ascent + shift);
+			let descent = descent.max(other_descent - shift);
+			self.properties
+				.insert("FONT_ASCENT".into(), Value::Integer(ascent));
+			self.properties
+				.insert("FONT_DESCENT".into(), Value::Integer(descent));
+		}
+
+		Ok(report)
+	}
+}
@@ -0,0 +1,162 @@
+//! Netpbm output of bitmaps, see <https://netpbm.sourceforge.net/doc/pbm.html> and
+//! <https://netpbm.sourceforge.net/doc/pgm.html>.
+
+use crate::{Bitmap, BitmapBuf, BoundingBox, Font};
+use std::io::{self, Write};
+
+/// The maximum line length of plain files recommended by the specification.
+const PLAIN_LINE_LEN: usize = 70;
+
+/// The encoding of Netpbm images.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum NetpbmEncoding {
+	/// ASCII digits, i.e. `P1` for PBM and `P2` for PGM. Easy to read and to diff.
+	Plain,
+
+	/// Binary samples, i.e. `P4` for PBM and `P5` for PGM.
+	#[default]
+	Raw
+}
+
+/// Write lines of plain samples, breaking lines before they exceed the recommended
+/// length.
+fn write_plain<W: Write>(
+	writer: &mut W,
+	rows: impl Iterator<Item = Vec<String>>
+) -> io::Result<()> {
+	for row in rows {
+		let mut len = 0;
+		for sample in row {
+			if len > 0 && len + 1 + sample.len() > PLAIN_LINE_LEN {
+				writeln!(writer)?;
+				len = 0;
+			}
+			if len > 0 {
+				write!(writer, " ")?;
+				len += 1;
+			}
+			write!(writer, "{sample}")?;
+			len += sample.len();
+		}
+		writeln!(writer)?;
+	}
+	Ok(())
+}
+
+impl Bitmap<'_> {
+	fn row(self, y: usize) -> Vec<bool> {
+		(0 .. self.width())
+			.map(|x| self.get(x, y).unwrap_or(false))
+			.collect()
+	}
+
+	/// Write this bitmap as a PBM image, with set pixels in black.
+	pub fn write_pbm<W: Write>(
+		self,
+		mut writer: W,
+		encoding: NetpbmEncoding
+	) -> io::Result<()> {
+		let magic = match encoding {
+			NetpbmEncoding::Plain => "P1",
+			NetpbmEncoding::Raw => "P4"
+		};
+		writeln!(writer, "{magic}\n{} {}", self.width(), self.height())?;
+		match encoding {
+			NetpbmEncoding::Plain => write_plain(
+				&mut writer,
+				(0 .. self.height()).map(|y| {
+					self.row(y)
+						.into_iter()
+						.map(|set| match set {
+							true => "1".to_owned(),
+							false => "0".to_owned()
+						})
+						.collect()
+				})
+			),
+			NetpbmEncoding::Raw => {
+				// rows are padded to full bytes, with the leftmost pixel in the high bit
+				for y in 0 .. self.height() {
+					let mut bytes = vec![0u8; self.width().div_ceil(8)];
+					for (x, set) in self.row(y).into_iter().enumerate() {
+						bytes[x / 8] |= u8::from(set) << (7 - x % 8);
+					}
+					writer.write_all(&bytes)?;
+				}
+				Ok(())
+			}
+		}
+	}
+
+	/// Write this bitmap as a PGM image with a maximum value of 255, with set pixels
+	/// in black on a white background.
+	pub fn write_pgm<W: Write>(
+		self,
+		mut writer: W,
+		encoding: NetpbmEncoding
+	) -> io::Result<()> {
+		let magic = match encoding {
+			NetpbmEncoding::Plain => "P2",
+			NetpbmEncoding::Raw => "P5"
+		};
+		writeln!(writer, "{magic}\n{} {}\n255", self.width(), self.height())?;
+		let gray = |set: bool| match set {
+			true => 0u8,
+			false => 255
+		};
+		match encoding {
+			NetpbmEncoding::Plain => write_plain(
+				&mut writer,
+				(0 .. self.height()).map(|y| {
+					self.row(y)
+						.into_iter()
+						.map(|set| gray(set).to_string())
+						.collect()
+				})
+			),
+			NetpbmEncoding::Raw => {
+				for y in 0 .. self.height() {
+					writer.write_all(
+						&self.row(y).into_iter().map(gray).collect::<Vec<u8>>()
+					)?;
+				}
+				Ok(())
+			}
+		}
+	}
+}
+
+impl Font {
+	/// Render all glyphs of this font into a grid with `columns` cells per row, in the
+	/// order of their encoding. Each cell has the size of the font bounding box, with
+	/// the glyph origin placed at the same position in every cell, and cells are
+	/// separated by `spacing` empty pixels. The result can be written as a PBM or PGM
+	/// image using [`Bitmap::write_pbm`] and [`Bitmap::write_pgm`], or as a PNG image
+	/// with the `png` feature.
+	pub fn glyph_grid(&self, columns: usize, spacing: u32) -> BitmapBuf {
+		let glyphs: Vec<_> = self.glyphs().into_iter().collect();
+		let columns = columns.max(1);
+		let rows = glyphs.len().div_ceil(columns);
+		let cell_width = self.bbox.width + spacing;
+		let cell_height = self.bbox.height + spacing;
+		let width = (columns as u32 * cell_width).saturating_sub(spacing);
+		let height = (rows as u32 * cell_height).saturating_sub(spacing);
+		let mut buf = BitmapBuf::new(BoundingBox {
+			width,
+			height,
+			offset_x: 0,
+			offset_y: -(height as i32)
+		});
+		for (idx, glyph) in glyphs.into_iter().enumerate() {
+			let (col, row) = ((idx % columns) as u32, (idx / columns) as u32);
+			let left = (col * cell_width) as i32;
+			let bottom = -((row * cell_height + self.bbox.height) as i32);
+			buf.draw(
+				glyph.bitmap(),
+				left - self.bbox.offset_x,
+				bottom - self.bbox.offset_y
+			);
+		}
+		buf
+	}
+}
@@ -0,0 +1,208 @@
+//! Conversion of bitmaps into polygon outlines.
+
+use crate::Bitmap;
+use std::collections::BTreeMap;
+
+/// A closed polygon, given by its corner points.
+pub(crate) type Polygon = Vec<(i32, i32)>;
+
+/// The direction of outer contours. Holes always run the other way.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Winding {
+	/// Outer contours run clockwise, like in TrueType fonts.
+	#[default]
+	Clockwise,
+
+	/// Outer contours run counter-clockwise, like in PostScript and CFF fonts.
+	CounterClockwise
+}
+
+/// Options for tracing the outlines of bitmaps.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OutlineOptions {
+	/// The direction of outer contours.
+	pub winding: Winding,
+
+	/// Whether to replace stairs of single pixel steps by diagonal lines, which gives
+	/// smoother shapes when upscaling pixel art. Corners that are not part of such a
+	/// stair, like the corners of a single pixel, are kept.
+	pub smooth_diagonals: bool
+}
+
+/// A closed contour traced from a bitmap.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Contour {
+	/// The corner points in pixel units relative to the glyph origin, with the y axis
+	/// pointing up. The last point connects back to the first one.
+	pub points: Vec<(i32, i32)>,
+
+	/// Whether this contour is a hole inside of an outer contour.
+	pub hole: bool
+}
+
+/// Rotate a direction clockwise in a coordinate system with the y axis pointing up.
+fn turn_right((dx, dy): (i32, i32)) -> (i32, i32) {
+	(dy, -dx)
+}
+
+fn turn_left((dx, dy): (i32, i32)) -> (i32, i32) {
+	(-dy, dx)
+}
+
+impl Bitmap<'_> {
+	/// Trace the outlines of the set pixels. The points are pixel corners relative to
+	/// the origin, with the y axis pointing up. Outer contours run clockwise and holes
+	/// counter-clockwise, so the filled area is always on the right, and only the
+	/// corners of each contour are included.
+	///
+	/// Pixels that only touch at a corner end up in separate contours.
+	pub(crate) fn contours(self) -> Vec<Polygon> {
+		let bbox = self.bbox;
+		let top = bbox.offset_y + bbox.height as i32;
+		// rows keep the padding bits beyond the width, which are not part of the glyph
+		let (width, height) = (self.width() as i32, self.height() as i32);
+		let is_set = |x: i32, y: i32| {
+			(0 .. width).contains(&x)
+				&& (0 .. height).contains(&y)
+				&& self.get(x as usize, y as usize).unwrap_or(false)
+		};
+
+		// the unit edges between set and unset pixels, keyed by their start point
+		let mut edges: BTreeMap<(i32, i32), Vec<(i32, i32)>> = BTreeMap::new();
+		for row in 0 .. height {
+			for col in 0 .. width {
+				if !is_set(col, row) {
+					continue;
+				}
+				let (x, y) = (bbox.offset_x + col, top - row - 1);
+				for (neighbour, start, dir) in [
+					((col - 1, row), (x, y), (0, 1)),
+					((col, row - 1), (x, y + 1), (1, 0)),
+					((col + 1, row), (x + 1, y + 1), (0, -1)),
+					((col, row + 1), (x + 1, y), (-1, 0))
+				] {
+					if !is_set(neighbour.0, neighbour.1) {
+						edges.entry(start).or_default().push(dir);
+					}
+				}
+			}
+		}
+
+		let mut contours = Vec::new();
+		while let Some((&start, dirs)) = edges.iter_mut().next() {
+			let first = dirs.pop().unwrap();
+			let (mut pos, mut dir) = (start, first);
+			let mut contour = Vec::new();
+			loop {
+				pos = (pos.0 + dir.0, pos.1 + dir.1);
+				// prefer turning right, which keeps diagonal neighbours apart, and close
+				// the contour once its first edge is the preferred way to continue
+				let closes = |next: (i32, i32)| pos == start && next == first;
+				let next = edges.get(&pos).and_then(|dirs| {
+					[turn_right(dir), dir, turn_left(dir)]
+						.into_iter()
+						.find(|next| closes(*next) || dirs.contains(next))
+				});
+				match next {
+					Some(next) if !closes(next) => {
+						let dirs = edges.get_mut(&pos).unwrap();
+						dirs.swap_remove(dirs.iter().position(|d| *d == next).unwrap());
+						if next != dir {
+							contour.push(pos);
+						}
+						dir = next;
+					},
+					_ => {
+						if first != dir {
+							contour.insert(0, start);
+						}
+						break;
+					}
+				}
+			}
+			edges.retain(|_, dirs| !dirs.is_empty());
+			contours.push(contour);
+		}
+		contours
+	}
+}
+
+/// Twice the signed area of a polygon, which is positive for counter-clockwise
+/// polygons.
+fn double_area(points: &[(i32, i32)]) -> i64 {
+	let n = points.len();
+	(0 .. n)
+		.map(|i| {
+			let ((x0, y0), (x1, y1)) = (points[i], points[(i + 1) % n]);
+			i64::from(x0) * i64::from(y1) - i64::from(x1) * i64::from(y0)
+		})
+		.sum()
+}
+
+/// The cross product of the edges before and after a corner, which is positive for
+/// left turns.
+fn cross((x0, y0): (i32, i32), (x1, y1): (i32, i32), (x2, y2): (i32, i32)) -> i64 {
+	i64::from(x1 - x0) * i64::from(y2 - y1) - i64::from(y1 - y0) * i64::from(x2 - x1)
+}
+
+/// Remove the corners of single pixel stair steps, i.e. corners between two edges of
+/// length one that turn the other way than a neighbouring corner, and then any points
+/// that are on a straight line.
+fn smooth(points: Polygon) -> Polygon {
+	let n = points.len();
+	let at = |i: usize| points[i % n];
+	let turn = |i: usize| cross(at(i + n - 1), at(i), at(i + 1)).signum();
+	let is_unit =
+		|a: (i32, i32), b: (i32, i32)| (a.0 - b.0).abs() + (a.1 - b.1).abs() == 1;
+	let smoothed: Polygon = (0 .. n)
+		.filter(|&i| {
+			let step = is_unit(at(i + n - 1), at(i)) && is_unit(at(i), at(i + 1));
+			!step || (turn(i + n - 1) == turn(i) && turn(i + 1) == turn(i))
+		})
+		.map(at)
+		.collect();
+
+	let n = smoothed.len();
+	let smoothed: Polygon = (0 .. n)
+		.filter(|&i| {
+			cross(
+				smoothed[(i + n - 1) % n],
+				smoothed[i],
+				smoothed[(i + 1) % n]
+			) != 0
+		})
+		.map(|i| smoothed[i])
+		.collect();
+	match smoothed.len() >= 3 {
+		true => smoothed,
+		false => points
+	}
+}
+
+impl Bitmap<'_> {
+	/// Trace the outlines of the set pixels into closed polygons. The points are
+	/// relative to the glyph origin, using the offsets of the bounding box, and
+	/// without smoothing they are the pixel corners where the outline changes
+	/// direction.
+	///
+	/// Outer contours and holes run in opposite directions, so that the contours can be
+	/// filled with both the non-zero and the even-odd rule. Pixels that only touch at a
+	/// corner end up in separate contours.
+	pub fn outline(self, options: &OutlineOptions) -> Vec<Contour> {
+		self.contours()
+			.into_iter()
+			.map(|points| {
+				let mut points = match options.smooth_diagonals {
+					true => smooth(points),
+					false => points
+				};
+				// the traced outer contours run clockwise
+				let hole = double_area(&points) > 0;
+				if options.winding == Winding::CounterClockwise {
+					points.reverse();
+				}
+				Contour { points, hole }
+			})
+			.collect()
+	}
+}
@@ -0,0 +1,239 @@
+//! The X11 Portable Compiled Format (PCF).
+//!
+//! See <https://fontforge.org/docs/techref/pcf-format.html> for a description of the
+//! format.
+
+use crate::{layout::advance, BoundingBox, Glyph};
+use std::io;
+use thiserror::Error;
+
+mod reader;
+mod writer;
+
+pub(crate) const MAGIC: &[u8; 4] = b"\x01fcp";
+
+pub(crate) const PCF_PROPERTIES: u32 = 1 << 0;
+pub(crate) const PCF_ACCELERATORS: u32 = 1 << 1;
+pub(crate) const PCF_METRICS: u32 = 1 << 2;
+pub(crate) const PCF_BITMAPS: u32 = 1 << 3;
+pub(crate) const PCF_INK_METRICS: u32 = 1 << 4;
+pub(crate) const PCF_BDF_ENCODINGS: u32 = 1 << 5;
+pub(crate) const PCF_SWIDTHS: u32 = 1 << 6;
+pub(crate) const PCF_GLYPH_NAMES: u32 = 1 << 7;
+pub(crate) const PCF_BDF_ACCELERATORS: u32 = 1 << 8;
+
+pub(crate) const PCF_DEFAULT_FORMAT: u32 = 0x000;
+pub(crate) const PCF_ACCEL_W_INKBOUNDS: u32 = 0x100;
+pub(crate) const PCF_COMPRESSED_METRICS: u32 = 0x100;
+pub(crate) const PCF_FORMAT_MASK: u32 = 0xFFFF_FF00;
+
+pub(crate) const PCF_GLYPH_PAD_MASK: u32 = 3;
+pub(crate) const PCF_BYTE_MASK: u32 = 1 << 2;
+pub(crate) const PCF_BIT_MASK: u32 = 1 << 3;
+pub(crate) const PCF_SCAN_UNIT_MASK: u32 = 3 << 4;
+
+/// The glyph index used in the encoding table for characters without a glyph.
+pub(crate) const NO_GLYPH: u16 = 0xFFFF;
+
+#[derive(Debug, Error)]
+pub enum PcfError {
+	#[error("I/O Error: {0}")]
+	IOError(#[from] io::Error),
+
+	#[error("Not a PCF file")]
+	InvalidMagic,
+
+	#[error("Unexpected end of table {0:#x}")]
+	UnexpectedEof(u32),
+
+	#[error("Missing required table {0:#x}")]
+	MissingTable(u32),
+
+	#[error("Unsupported format {1:#x} of table {0:#x}")]
+	UnsupportedFormat(u32, u32),
+
+	#[error("Glyph index {0} out of range")]
+	InvalidGlyphIndex(usize),
+
+	#[error("Missing FONT property")]
+	MissingFontName,
+
+	#[error("Invalid glyph padding {0} or scan unit {1}")]
+	InvalidOptions(u8, u8),
+
+	#[error("Metrics of glyph {0} exceed the range supported by PCF")]
+	MetricsOutOfRange(u32),
+
+	#[error("Too many glyphs: {0}")]
+	TooManyGlyphs(usize)
+}
+
+/// Options for [writing PCF fonts](crate::Font::write_pcf). The defaults match those
+/// of `bdftopcf` on little endian machines.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PcfOptions {
+	/// Store integers and scan units with the most significant byte first.
+	pub msb_byte_first: bool,
+
+	/// Store the leftmost pixel in the most significant bit of each byte.
+	pub msb_bit_first: bool,
+
+	/// The number of bytes each bitmap row is padded to. Must be 1, 2, 4 or 8.
+	pub glyph_padding: u8,
+
+	/// The number of bytes that are swapped as a unit if the byte order differs from
+	/// the bit order. Must be 1, 2 or 4 and not exceed the glyph padding.
+	pub scan_unit: u8
+}
+
+impl Default for PcfOptions {
+	fn default() -> Self {
+		Self {
+			msb_byte_first: false,
+			msb_bit_first: true,
+			glyph_padding: 4,
+			scan_unit: 1
+		}
+	}
+}
+
+impl PcfOptions {
+	/// Get the format flags for byte and bit order, glyph padding and scan unit.
+	fn format(self) -> Result<u32, PcfError> {
+		let invalid = || PcfError::InvalidOptions(self.glyph_padding, self.scan_unit);
+		let pad = match self.glyph_padding {
+			1 | 2 | 4 | 8 => self.glyph_padding.trailing_zeros(),
+			_ => return Err(invalid())
+		};
+		let unit = match self.scan_unit {
+			1 | 2 | 4 if self.scan_unit <= self.glyph_padding => {
+				self.scan_unit.trailing_zeros()
+			},
+			_ => return Err(invalid())
+		};
+		let mut format = pad | (unit << 4);
+		if self.msb_byte_first {
+			format |= PCF_BYTE_MASK;
+		}
+		if self.msb_bit_first {
+			format |= PCF_BIT_MASK;
+		}
+		Ok(format)
+	}
+}
+
+/// The metrics of a glyph as stored in the PCF file.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) struct Metrics {
+	pub(crate) left_bearing: i16,
+	pub(crate) right_bearing: i16,
+	pub(crate) width: i16,
+	pub(crate) ascent: i16,
+	pub(crate) descent: i16,
+	pub(crate) attributes: u16
+}
+
+impl Metrics {
+	/// Get the metrics of a glyph.
+	pub(crate) fn of(glyph: &Glyph) -> Result<Self, PcfError> {
+		let bbox = glyph.bbox;
+		let convert = |v: i64| {
+			i16::try_from(v).map_err(|_| PcfError::MetricsOutOfRange(glyph.encoding))
+		};
+		Ok(Self {
+			left_bearing: convert(bbox.offset_x.into())?,
+			right_bearing: convert(i64::from(bbox.offset_x) + i64::from(bbox.width))?,
+			width: convert(advance(glyph).into())?,
+			ascent: convert(i64::from(bbox.offset_y) + i64::from(bbox.height))?,
+			descent: convert((-bbox.offset_y).into())?,
+			attributes: 0
+		})
+	}
+
+	/// Get the metrics of the set pixels of a glyph. Glyphs without any set pixels
+	/// have all bearings and extents set to zero.
+	pub(crate) fn ink_of(glyph: &Glyph) -> Result<Self, PcfError> {
+		let metrics = Self::of(glyph)?;
+		let bitmap = glyph.bitmap();
+		let mut ink: Option<(usize, usize, usize, usize)> = None;
+		for y in 0 .. bitmap.height() {
+			for x in 0 .. bitmap.width() {
+				if bitmap.get(x, y).unwrap_or(false) {
+					ink = Some(match ink {
+						None => (x, x, y, y),
+						Some((x0, x1, y0, y1)) => {
+							(x0.min(x), x1.max(x), y0.min(y), y1.max(y))
+						},
+					});
+				}
+			}
+		}
+		let Some((x0, x1, y0, y1)) = ink else {
+			return Ok(Self {
+				width: metrics.width,
+				..Self::default()
+			});
+		};
+		let convert = |v: i64| {
+			i16::try_from(v).map_err(|_| PcfError::MetricsOutOfRange(glyph.encoding))
+		};
+		Ok(Self {
+			left_bearing: convert(i64::from(metrics.left_bearing) + x0 as i64)?,
+			right_bearing: convert(i64::from(metrics.left_bearing) + x1 as i64 + 1)?,
+			width: metrics.width,
+			ascent: convert(i64::from(metrics.ascent) - y0 as i64)?,
+			descent: convert(
+				i64::from(metrics.descent) - (bitmap.height() - 1 - y1) as i64
+			)?,
+			attributes: 0
+		})
+	}
+
+	/// Check whether these metrics can be stored in the compressed format.
+	pub(crate) fn is_compressible(self) -> bool {
+		[
+			self.left_bearing,
+			self.right_bearing,
+			self.width,
+			self.ascent,
+			self.descent
+		]
+		.into_iter()
+		.all(|v| (-0x80 ..= 0x7F).contains(&v))
+			&& self.attributes == 0
+	}
+
+	pub(crate) fn bounding_box(self) -> BoundingBox {
+		BoundingBox {
+			width: (i32::from(self.right_bearing) - i32::from(self.left_bearing)).max(0)
+				as u32,
+			height: (i32::from(self.ascent) + i32::from(self.descent)).max(0) as u32,
+			offset_x: self.left_bearing.into(),
+			offset_y: -i32::from(self.descent)
+		}
+	}
+}
+
+/// The contents of the accelerator tables.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) struct Accelerators {
+	pub(crate) no_overlap: bool,
+	pub(crate) constant_metrics: bool,
+	pub(crate) terminal_font: bool,
+	pub(crate) constant_width: bool,
+	pub(crate) ink_inside: bool,
+	pub(crate) ink_metrics: bool,
+	pub(crate) draw_right_to_left: bool,
+	pub(crate) font_ascent: i32,
+	pub(crate) font_descent: i32,
+	pub(crate) max_overlap: i32,
+	pub(crate) min_bounds: Metrics,
+	pub(crate) max_bounds: Metrics,
+	pub(crate) ink_bounds: Option<(Metrics, Metrics)>
+}
+
+/// Get the number of bytes per bitmap row, given the glyph width and the glyph padding
+/// in bytes.
+pub(crate) fn row_bytes(width: usize, pad: usize) -> usize {
+	width.div_ceil(pad * 8) * pad
+}
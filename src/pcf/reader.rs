@@ -0,0 +1,432 @@
+use super::*;
+use crate::{compress::decompress, font::GlyphWrapper, Font, Glyph, Size, Value};
+use bit_vec::BitVec;
+use log::debug;
+use std::{
+	collections::{BTreeSet, HashMap},
+	io::{BufReader, Read}
+};
+
+/// A cursor over the data of a single table.
+struct TableReader<'a> {
+	ty: u32,
+	format: u32,
+	data: &'a [u8],
+	pos: usize
+}
+
+impl<'a> TableReader<'a> {
+	fn new(ty: u32, data: &'a [u8]) -> Result<Self, PcfError> {
+		let mut table = Self {
+			ty,
+			format: 0,
+			data,
+			pos: 0
+		};
+		// the format is always stored least significant byte first
+		table.format = u32::from_le_bytes(table.read_bytes()?);
+		Ok(table)
+	}
+
+	fn msb_first(&self) -> bool {
+		self.format & PCF_BYTE_MASK != 0
+	}
+
+	fn read_slice(&mut self, len: usize) -> Result<&'a [u8], PcfError> {
+		let slice = self
+			.data
+			.get(self.pos .. self.pos + len)
+			.ok_or(PcfError::UnexpectedEof(self.ty))?;
+		self.pos += len;
+		Ok(slice)
+	}
+
+	fn read_bytes<const N: usize>(&mut self) -> Result<[u8; N], PcfError> {
+		Ok(self.read_slice(N)?.try_into().unwrap())
+	}
+
+	fn read_u8(&mut self) -> Result<u8, PcfError> {
+		Ok(self.read_bytes::<1>()?[0])
+	}
+
+	fn read_u16(&mut self) -> Result<u16, PcfError> {
+		let bytes = self.read_bytes()?;
+		Ok(match self.msb_first() {
+			true => u16::from_be_bytes(bytes),
+			false => u16::from_le_bytes(bytes)
+		})
+	}
+
+	fn read_i16(&mut self) -> Result<i16, PcfError> {
+		Ok(self.read_u16()? as i16)
+	}
+
+	fn read_i32(&mut self) -> Result<i32, PcfError> {
+		let bytes = self.read_bytes()?;
+		Ok(match self.msb_first() {
+			true => i32::from_be_bytes(bytes),
+			false => i32::from_le_bytes(bytes)
+		})
+	}
+
+	fn read_count(&mut self) -> Result<usize, PcfError> {
+		let count = self.read_i32()?;
+		usize::try_from(count).map_err(|_| PcfError::UnexpectedEof(self.ty))
+	}
+
+	fn skip_padding(&mut self, len: usize) {
+		self.pos += (4 - len % 4) % 4;
+	}
+
+	fn check_format(&self, format: u32) -> Result<(), PcfError> {
+		if self.format & PCF_FORMAT_MASK != format {
+			return Err(PcfError::UnsupportedFormat(self.ty, self.format));
+		}
+		Ok(())
+	}
+
+	fn read_string(strings: &[u8], offset: usize) -> String {
+		let bytes = strings.get(offset ..).unwrap_or_default();
+		let len = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+		String::from_utf8_lossy(&bytes[.. len]).into_owned()
+	}
+
+	fn read_uncompressed_metrics(&mut self) -> Result<Metrics, PcfError> {
+		Ok(Metrics {
+			left_bearing: self.read_i16()?,
+			right_bearing: self.read_i16()?,
+			width: self.read_i16()?,
+			ascent: self.read_i16()?,
+			descent: self.read_i16()?,
+			attributes: self.read_u16()?
+		})
+	}
+
+	fn read_compressed_metrics(&mut self) -> Result<Metrics, PcfError> {
+		let mut read = || Ok::<_, PcfError>(i16::from(self.read_u8()?) - 0x80);
+		Ok(Metrics {
+			left_bearing: read()?,
+			right_bearing: read()?,
+			width: read()?,
+			ascent: read()?,
+			descent: read()?,
+			attributes: 0
+		})
+	}
+
+	fn read_properties(mut self) -> Result<Vec<(String, Value)>, PcfError> {
+		self.check_format(PCF_DEFAULT_FORMAT)?;
+		let nprops = self.read_count()?;
+		// each property takes 9 bytes, check this before allocating for them
+		if nprops > (self.data.len() - self.pos) / 9 {
+			return Err(PcfError::UnexpectedEof(self.ty));
+		}
+		let mut raw = Vec::with_capacity(nprops);
+		for _ in 0 .. nprops {
+			let name = self.read_i32()? as usize;
+			let is_string = self.read_u8()? != 0;
+			let value = self.read_i32()?;
+			raw.push((name, is_string, value));
+		}
+		self.skip_padding(nprops);
+		let string_size = self.read_count()?;
+		let strings = self.read_slice(string_size)?;
+
+		Ok(raw
+			.into_iter()
+			.map(|(name, is_string, value)| {
+				let value = match is_string {
+					true => Value::String(Self::read_string(strings, value as usize)),
+					false => Value::Integer(value)
+				};
+				(Self::read_string(strings, name), value)
+			})
+			.collect())
+	}
+
+	fn read_metrics(mut self) -> Result<Vec<Metrics>, PcfError> {
+		if self.format & PCF_FORMAT_MASK == PCF_COMPRESSED_METRICS {
+			let count = self.read_u16()? as usize;
+			(0 .. count)
+				.map(|_| self.read_compressed_metrics())
+				.collect()
+		} else {
+			self.check_format(PCF_DEFAULT_FORMAT)?;
+			let count = self.read_count()?;
+			(0 .. count)
+				.map(|_| self.read_uncompressed_metrics())
+				.collect()
+		}
+	}
+
+	fn read_accelerators(mut self) -> Result<Accelerators, PcfError> {
+		let with_ink_bounds = match self.format & PCF_FORMAT_MASK {
+			PCF_DEFAULT_FORMAT => false,
+			PCF_ACCEL_W_INKBOUNDS => true,
+			_ => return Err(PcfError::UnsupportedFormat(self.ty, self.format))
+		};
+		let mut accel = Accelerators {
+			no_overlap: self.read_u8()? != 0,
+			constant_metrics: self.read_u8()? != 0,
+			terminal_font: self.read_u8()? != 0,
+			constant_width: self.read_u8()? != 0,
+			ink_inside: self.read_u8()? != 0,
+			ink_metrics: self.read_u8()? != 0,
+			draw_right_to_left: self.read_u8()? != 0,
+			..Default::default()
+		};
+		self.read_u8()?;
+		accel.font_ascent = self.read_i32()?;
+		accel.font_descent = self.read_i32()?;
+		accel.max_overlap = self.read_i32()?;
+		accel.min_bounds = self.read_uncompressed_metrics()?;
+		accel.max_bounds = self.read_uncompressed_metrics()?;
+		if with_ink_bounds {
+			accel.ink_bounds = Some((
+				self.read_uncompressed_metrics()?,
+				self.read_uncompressed_metrics()?
+			));
+		}
+		Ok(accel)
+	}
+
+	/// Read the bitmaps, returning the offset of each glyph, the bitmap data converted
+	/// to most significant bit and byte first, and the glyph padding in bytes.
+	fn read_bitmaps(mut self) -> Result<(Vec<usize>, Vec<u8>, usize), PcfError> {
+		self.check_format(PCF_DEFAULT_FORMAT)?;
+		let count = self.read_count()?;
+		let offsets = (0 .. count)
+			.map(|_| self.read_count())
+			.collect::<Result<Vec<_>, _>>()?;
+		let mut sizes = [0; 4];
+		for size in &mut sizes {
+			*size = self.read_count()?;
+		}
+		let pad_index = (self.format & PCF_GLYPH_PAD_MASK) as usize;
+		let mut data = self.read_slice(sizes[pad_index])?.to_vec();
+
+		let msb_bit_first = self.format & PCF_BIT_MASK != 0;
+		if !msb_bit_first {
+			for byte in &mut data {
+				*byte = byte.reverse_bits();
+			}
+		}
+		let scan_unit = 1 << ((self.format & PCF_SCAN_UNIT_MASK) >> 4);
+		if self.msb_first() != msb_bit_first && scan_unit > 1 {
+			for unit in data.chunks_mut(scan_unit) {
+				unit.reverse();
+			}
+		}
+
+		Ok((offsets, data, 1 << pad_index))
+	}
+
+	fn read_encodings(mut self) -> Result<(u16, Vec<(u32, usize)>), PcfError> {
+		self.check_format(PCF_DEFAULT_FORMAT)?;
+		let min_byte2 = self.read_i16()? as u32;
+		let max_byte2 = self.read_i16()? as u32;
+		let min_byte1 = self.read_i16()? as u32;
+		let max_byte1 = self.read_i16()? as u32;
+		let default_char = self.read_u16()?;
+
+		let mut encodings = Vec::new();
+		for byte1 in min_byte1 ..= max_byte1 {
+			for byte2 in min_byte2 ..= max_byte2 {
+				let idx = self.read_u16()?;
+				if idx != NO_GLYPH {
+					encodings.push(((byte1 << 8) | byte2, idx as usize));
+				}
+			}
+		}
+		Ok((default_char, encodings))
+	}
+
+	fn read_swidths(mut self) -> Result<Vec<i32>, PcfError> {
+		self.check_format(PCF_DEFAULT_FORMAT)?;
+		let count = self.read_count()?;
+		(0 .. count).map(|_| self.read_i32()).collect()
+	}
+
+	fn read_glyph_names(mut self) -> Result<Vec<String>, PcfError> {
+		self.check_format(PCF_DEFAULT_FORMAT)?;
+		let count = self.read_count()?;
+		let offsets = (0 .. count)
+			.map(|_| self.read_count())
+			.collect::<Result<Vec<_>, _>>()?;
+		let string_size = self.read_count()?;
+		let strings = self.read_slice(string_size)?;
+		Ok(offsets
+			.into_iter()
+			.map(|offset| Self::read_string(strings, offset))
+			.collect())
+	}
+}
+
+impl Font {
+	/// Read a font in the X11 Portable Compiled Format (PCF). With the `gzip` feature
+	/// enabled, gzip compressed input is decompressed on the fly.
+	pub fn read_pcf<R: Read>(reader: R) -> Result<Self, PcfError> {
+		let mut buf = Vec::new();
+		decompress(BufReader::new(reader))?.read_to_end(&mut buf)?;
+		if !buf.starts_with(MAGIC) {
+			return Err(PcfError::InvalidMagic);
+		}
+
+		// the table of contents is always stored least significant byte first
+		let mut toc = TableReader {
+			ty: 0,
+			format: 0,
+			data: &buf,
+			pos: MAGIC.len()
+		};
+		let table_count = toc.read_count()?;
+		let mut tables = HashMap::new();
+		for _ in 0 .. table_count {
+			let ty = toc.read_i32()? as u32;
+			let _format = toc.read_i32()?;
+			let size = toc.read_count()?;
+			let offset = toc.read_count()?;
+			debug!("Found PCF table {ty:#x} at offset {offset} with size {size}");
+			let data = buf
+				.get(offset .. offset + size)
+				.ok_or(PcfError::UnexpectedEof(ty))?;
+			tables.insert(ty, data);
+		}
+		let table = |ty| -> Result<Option<TableReader<'_>>, PcfError> {
+			tables
+				.get(&ty)
+				.map(|data| TableReader::new(ty, data))
+				.transpose()
+		};
+		let required = |ty| table(ty)?.ok_or(PcfError::MissingTable(ty));
+
+		let properties: HashMap<String, Value> = required(PCF_PROPERTIES)?
+			.read_properties()?
+			.into_iter()
+			.collect();
+		let metrics = required(PCF_METRICS)?.read_metrics()?;
+		let (offsets, bitmap_data, pad) = required(PCF_BITMAPS)?.read_bitmaps()?;
+		let (default_char, encodings) = required(PCF_BDF_ENCODINGS)?.read_encodings()?;
+		let swidths = table(PCF_SWIDTHS)?
+			.map(TableReader::read_swidths)
+			.transpose()?;
+		let names = table(PCF_GLYPH_NAMES)?
+			.map(TableReader::read_glyph_names)
+			.transpose()?;
+		let accel = match table(PCF_BDF_ACCELERATORS)? {
+			Some(table) => Some(table.read_accelerators()?),
+			None => table(PCF_ACCELERATORS)?
+				.map(TableReader::read_accelerators)
+				.transpose()?
+		};
+
+		// the name is taken from the FONT property once all tables have been read
+		let mut font = Self {
+			version: None,
+			name: String::new(),
+			bbox: BoundingBox::default(),
+			size: Size {
+				pt: 0,
+				xres: 0,
+				yres: 0
+			},
+			properties,
+			glyphs: BTreeSet::new()
+		};
+		let xres = font.integer_property("RESOLUTION_X").unwrap_or(75);
+		let yres = font.integer_property("RESOLUTION_Y").unwrap_or(75);
+		let point_size = font
+			.integer_property("POINT_SIZE")
+			.map(|pt| (pt as f64 / 10.0).round() as i32)
+			.or_else(|| {
+				font.integer_property("PIXEL_SIZE")
+					.map(|px| (px as f64 * 72.0 / yres as f64).round() as i32)
+			})
+			.unwrap_or_default();
+		let size = Size {
+			pt: point_size.max(0) as u32,
+			xres: xres.max(0) as u32,
+			yres: yres.max(0) as u32
+		};
+		font.size = size;
+
+		let glyphs = &mut font.glyphs;
+		for (encoding, idx) in encodings {
+			let metric = *metrics.get(idx).ok_or(PcfError::InvalidGlyphIndex(idx))?;
+			let bbox = metric.bounding_box();
+			let offset = *offsets.get(idx).ok_or(PcfError::InvalidGlyphIndex(idx))?;
+			let stride = row_bytes(bbox.width as usize, pad);
+			let data = bitmap_data
+				.get(offset .. offset + stride * bbox.height as usize)
+				.ok_or(PcfError::UnexpectedEof(PCF_BITMAPS))?;
+			let bitmap = data
+				.chunks(stride.max(1))
+				.take(bbox.height as usize)
+				.map(|row| BitVec::from_bytes(&row[.. (bbox.width as usize).div_ceil(8)]))
+				.collect();
+
+			let dwidth = f64::from(metric.width);
+			let swidth = match &swidths {
+				Some(swidths) => {
+					*swidths.get(idx).ok_or(PcfError::InvalidGlyphIndex(idx))? as f64
+				},
+				None if size.pt > 0 && size.xres > 0 => {
+					(dwidth * 72000.0 / (size.pt as f64 * size.xres as f64)).round()
+				},
+				None => 0.0
+			};
+			let name = match &names {
+				Some(names) => names
+					.get(idx)
+					.ok_or(PcfError::InvalidGlyphIndex(idx))?
+					.clone(),
+				None => format!("char{encoding}")
+			};
+			glyphs.insert(GlyphWrapper(Glyph {
+				name,
+				encoding,
+				swidth: Some((swidth, 0.0)),
+				dwidth: Some((dwidth, 0.0)),
+				bbox,
+				bitmap
+			}));
+		}
+
+		let bbox = match accel {
+			Some(accel) => BoundingBox {
+				width: (i32::from(accel.max_bounds.right_bearing)
+					- i32::from(accel.min_bounds.left_bearing))
+				.max(0) as u32,
+				height: (i32::from(accel.max_bounds.ascent)
+					+ i32::from(accel.max_bounds.descent))
+				.max(0) as u32,
+				offset_x: accel.min_bounds.left_bearing.into(),
+				offset_y: -i32::from(accel.max_bounds.descent)
+			},
+			None => glyphs
+				.iter()
+				.fold(BoundingBox::default(), |bbox, gw| bbox.union(gw.0.bbox))
+		};
+		let properties = &mut font.properties;
+		if let Some(accel) = accel {
+			properties
+				.entry("FONT_ASCENT".into())
+				.or_insert(Value::Integer(accel.font_ascent));
+			properties
+				.entry("FONT_DESCENT".into())
+				.or_insert(Value::Integer(accel.font_descent));
+		}
+		if glyphs.contains(&u32::from(default_char)) {
+			properties
+				.entry("DEFAULT_CHAR".into())
+				.or_insert(Value::Integer(default_char.into()));
+		}
+
+		font.name = match properties.remove("FONT") {
+			Some(Value::String(name)) => name,
+			_ => return Err(PcfError::MissingFontName)
+		};
+		font.bbox = bbox;
+		Ok(font)
+	}
+}
@@ -0,0 +1,410 @@
+use super::*;
+use crate::{Font, Value};
+use log::warn;
+use std::io::Write;
+
+/// A buffer for the data of a single table.
+struct TableWriter {
+	ty: u32,
+	format: u32,
+	buf: Vec<u8>
+}
+
+impl TableWriter {
+	fn new(ty: u32, format: u32) -> Self {
+		Self {
+			ty,
+			format,
+			// the format is always stored least significant byte first
+			buf: format.to_le_bytes().to_vec()
+		}
+	}
+
+	fn msb_first(&self) -> bool {
+		self.format & PCF_BYTE_MASK != 0
+	}
+
+	fn write_u8(&mut self, value: u8) {
+		self.buf.push(value);
+	}
+
+	fn write_u16(&mut self, value: u16) {
+		match self.msb_first() {
+			true => self.buf.extend(value.to_be_bytes()),
+			false => self.buf.extend(value.to_le_bytes())
+		}
+	}
+
+	fn write_i16(&mut self, value: i16) {
+		self.write_u16(value as u16);
+	}
+
+	fn write_i32(&mut self, value: i32) {
+		match self.msb_first() {
+			true => self.buf.extend(value.to_be_bytes()),
+			false => self.buf.extend(value.to_le_bytes())
+		}
+	}
+
+	fn write_count(&mut self, count: usize) {
+		self.write_i32(count as i32);
+	}
+
+	fn write_padding(&mut self) {
+		self.buf.resize(self.buf.len().div_ceil(4) * 4, 0);
+	}
+
+	fn write_uncompressed_metrics(&mut self, metrics: Metrics) {
+		self.write_i16(metrics.left_bearing);
+		self.write_i16(metrics.right_bearing);
+		self.write_i16(metrics.width);
+		self.write_i16(metrics.ascent);
+		self.write_i16(metrics.descent);
+		self.write_u16(metrics.attributes);
+	}
+
+	fn write_compressed_metrics(&mut self, metrics: Metrics) {
+		for v in [
+			metrics.left_bearing,
+			metrics.right_bearing,
+			metrics.width,
+			metrics.ascent,
+			metrics.descent
+		] {
+			self.write_u8((v + 0x80) as u8);
+		}
+	}
+}
+
+/// Collect NUL-terminated strings, returning their offsets.
+#[derive(Default)]
+struct Strings(Vec<u8>);
+
+impl Strings {
+	fn push(&mut self, s: &str) -> usize {
+		let offset = self.0.len();
+		self.0.extend(s.as_bytes());
+		self.0.push(0);
+		offset
+	}
+}
+
+fn properties_table(font: &Font, flags: u32) -> TableWriter {
+	let mut properties: Vec<(&str, &Value)> = font
+		.properties
+		.iter()
+		.filter(|(key, _)| key.as_str() != "FONT")
+		.map(|(key, value)| (key.as_str(), value))
+		.collect();
+	properties.sort_by_key(|(key, _)| *key);
+	let font_name = Value::String(font.name.clone());
+	properties.insert(0, ("FONT", &font_name));
+
+	let mut table = TableWriter::new(PCF_PROPERTIES, PCF_DEFAULT_FORMAT | flags);
+	let mut strings = Strings::default();
+	table.write_count(properties.len());
+	for (key, value) in &properties {
+		table.write_i32(strings.push(key) as i32);
+		match value {
+			Value::String(s) => {
+				table.write_u8(1);
+				table.write_i32(strings.push(s) as i32);
+			},
+			Value::Integer(i) => {
+				table.write_u8(0);
+				table.write_i32(*i);
+			}
+		}
+	}
+	table.write_padding();
+	table.write_count(strings.0.len());
+	table.buf.extend(strings.0);
+	table
+}
+
+fn metrics_table(ty: u32, metrics: &[Metrics], flags: u32) -> TableWriter {
+	if metrics.iter().all(|m| m.is_compressible()) {
+		let mut table = TableWriter::new(ty, PCF_COMPRESSED_METRICS | flags);
+		table.write_u16(metrics.len() as u16);
+		for m in metrics {
+			table.write_compressed_metrics(*m);
+		}
+		table
+	} else {
+		let mut table = TableWriter::new(ty, PCF_DEFAULT_FORMAT | flags);
+		table.write_count(metrics.len());
+		for m in metrics {
+			table.write_uncompressed_metrics(*m);
+		}
+		table
+	}
+}
+
+fn bitmaps_table(glyphs: &[&Glyph], options: &PcfOptions, flags: u32) -> TableWriter {
+	let pad = options.glyph_padding as usize;
+	let mut offsets = Vec::with_capacity(glyphs.len());
+	let mut data = Vec::new();
+	for glyph in glyphs {
+		offsets.push(data.len());
+		let bitmap = glyph.bitmap();
+		let stride = row_bytes(bitmap.width(), pad);
+		for y in 0 .. bitmap.height() {
+			let mut row = vec![0u8; stride];
+			for x in 0 .. bitmap.width() {
+				if bitmap.get(x, y).unwrap_or(false) {
+					row[x / 8] |= 0x80 >> (x % 8);
+				}
+			}
+			data.extend(row);
+		}
+	}
+
+	if !options.msb_bit_first {
+		for byte in &mut data {
+			*byte = byte.reverse_bits();
+		}
+	}
+	if options.msb_byte_first != options.msb_bit_first && options.scan_unit > 1 {
+		for unit in data.chunks_mut(options.scan_unit as usize) {
+			unit.reverse();
+		}
+	}
+
+	let mut table = TableWriter::new(PCF_BITMAPS, PCF_DEFAULT_FORMAT | flags);
+	table.write_count(glyphs.len());
+	for offset in offsets {
+		table.write_count(offset);
+	}
+	for pad in [1, 2, 4, 8] {
+		let size: usize = glyphs
+			.iter()
+			.map(|glyph| {
+				row_bytes(glyph.bbox.width as usize, pad) * glyph.bbox.height as usize
+			})
+			.sum();
+		table.write_count(size);
+	}
+	table.buf.extend(data);
+	table
+}
+
+fn encodings_table(glyphs: &[&Glyph], default_char: u16, flags: u32) -> TableWriter {
+	let byte1 = |enc: u32| (enc >> 8) as u16;
+	let byte2 = |enc: u32| (enc & 0xFF) as u16;
+	let min_byte1 = glyphs.iter().map(|g| byte1(g.encoding)).min().unwrap_or(0);
+	let max_byte1 = glyphs.iter().map(|g| byte1(g.encoding)).max().unwrap_or(0);
+	let min_byte2 = glyphs.iter().map(|g| byte2(g.encoding)).min().unwrap_or(0);
+	let max_byte2 = glyphs.iter().map(|g| byte2(g.encoding)).max().unwrap_or(0);
+
+	let cols = (max_byte2 - min_byte2 + 1) as usize;
+	let rows = (max_byte1 - min_byte1 + 1) as usize;
+	let mut indices = vec![NO_GLYPH; cols * rows];
+	for (idx, glyph) in glyphs.iter().enumerate() {
+		let row = (byte1(glyph.encoding) - min_byte1) as usize;
+		let col = (byte2(glyph.encoding) - min_byte2) as usize;
+		indices[row * cols + col] = idx as u16;
+	}
+
+	let mut table = TableWriter::new(PCF_BDF_ENCODINGS, PCF_DEFAULT_FORMAT | flags);
+	table.write_u16(min_byte2);
+	table.write_u16(max_byte2);
+	table.write_u16(min_byte1);
+	table.write_u16(max_byte1);
+	table.write_u16(default_char);
+	for idx in indices {
+		table.write_u16(idx);
+	}
+	table
+}
+
+fn accelerators(font: &Font, metrics: &[Metrics], ink: &[Metrics]) -> Accelerators {
+	fn bounds(metrics: &[Metrics]) -> (Metrics, Metrics) {
+		let mut iter = metrics.iter().copied();
+		let Some(first) = iter.next() else {
+			return Default::default();
+		};
+		iter.fold((first, first), |(min, max), m| {
+			(
+				Metrics {
+					left_bearing: min.left_bearing.min(m.left_bearing),
+					right_bearing: min.right_bearing.min(m.right_bearing),
+					width: min.width.min(m.width),
+					ascent: min.ascent.min(m.ascent),
+					descent: min.descent.min(m.descent),
+					attributes: min.attributes.min(m.attributes)
+				},
+				Metrics {
+					left_bearing: max.left_bearing.max(m.left_bearing),
+					right_bearing: max.right_bearing.max(m.right_bearing),
+					width: max.width.max(m.width),
+					ascent: max.ascent.max(m.ascent),
+					descent: max.descent.max(m.descent),
+					attributes: max.attributes.max(m.attributes)
+				}
+			)
+		})
+	}
+
+	let (font_ascent, font_descent) = font.ascent_descent();
+	let (min, max) = bounds(metrics);
+	let max_overlap = metrics
+		.iter()
+		.map(|m| i32::from(m.right_bearing) - i32::from(m.width))
+		.max()
+		.unwrap_or(0);
+	let constant_metrics = min == max;
+	let ink_metrics = metrics != ink;
+
+	Accelerators {
+		no_overlap: max_overlap <= min.left_bearing.into(),
+		constant_metrics,
+		terminal_font: constant_metrics
+			&& max.left_bearing == 0
+			&& max.right_bearing == max.width
+			&& i32::from(max.ascent) == font_ascent
+			&& i32::from(max.descent) == font_descent,
+		constant_width: min.width == max.width,
+		ink_inside: min.left_bearing >= 0
+			&& max_overlap <= 0
+			&& i32::from(min.ascent) >= -font_descent
+			&& i32::from(max.ascent) <= font_ascent
+			&& -i32::from(min.descent) <= font_ascent
+			&& i32::from(max.descent) <= font_descent,
+		ink_metrics,
+		draw_right_to_left: false,
+		font_ascent,
+		font_descent,
+		max_overlap,
+		min_bounds: min,
+		max_bounds: max,
+		ink_bounds: ink_metrics.then(|| bounds(ink))
+	}
+}
+
+fn accelerators_table(ty: u32, accel: &Accelerators, flags: u32) -> TableWriter {
+	let format = match accel.ink_bounds {
+		Some(_) => PCF_ACCEL_W_INKBOUNDS,
+		None => PCF_DEFAULT_FORMAT
+	};
+	let mut table = TableWriter::new(ty, format | flags);
+	for flag in [
+		accel.no_overlap,
+		accel.constant_metrics,
+		accel.terminal_font,
+		accel.constant_width,
+		accel.ink_inside,
+		accel.ink_metrics,
+		accel.draw_right_to_left
+	] {
+		table.write_u8(flag.into());
+	}
+	table.write_u8(0);
+	table.write_i32(accel.font_ascent);
+	table.write_i32(accel.font_descent);
+	table.write_i32(accel.max_overlap);
+	table.write_uncompressed_metrics(accel.min_bounds);
+	table.write_uncompressed_metrics(accel.max_bounds);
+	if let Some((min, max)) = accel.ink_bounds {
+		table.write_uncompressed_metrics(min);
+		table.write_uncompressed_metrics(max);
+	}
+	table
+}
+
+impl Font {
+	/// Write this font in the X11 Portable Compiled Format (PCF).
+	///
+	/// PCF only supports 16 bit encodings, glyphs with larger encodings are skipped.
+	pub fn write_pcf<W: Write>(
+		&self,
+		mut writer: W,
+		options: &PcfOptions
+	) -> Result<(), PcfError> {
+		let flags = options.format()?;
+		let glyphs: Vec<&Glyph> = self
+			.glyphs()
+			.into_iter()
+			.filter(|glyph| {
+				let supported = glyph.encoding <= 0xFFFF;
+				if !supported {
+					warn!("Skipping glyph {} not supported by PCF", glyph.encoding);
+				}
+				supported
+			})
+			.collect();
+		// glyph indices are 16 bit, and the last one marks missing glyphs
+		if glyphs.len() >= usize::from(NO_GLYPH) {
+			return Err(PcfError::TooManyGlyphs(glyphs.len()));
+		}
+		let metrics = glyphs
+			.iter()
+			.map(|glyph| Metrics::of(glyph))
+			.collect::<Result<Vec<_>, _>>()?;
+		let ink = glyphs
+			.iter()
+			.map(|glyph| Metrics::ink_of(glyph))
+			.collect::<Result<Vec<_>, _>>()?;
+		let accel = accelerators(self, &metrics, &ink);
+		let default_char = self
+			.default_glyph()
+			.and_then(|glyph| u16::try_from(glyph.encoding).ok())
+			.unwrap_or(NO_GLYPH);
+
+		let mut swidths = TableWriter::new(PCF_SWIDTHS, PCF_DEFAULT_FORMAT | flags);
+		swidths.write_count(glyphs.len());
+		for glyph in &glyphs {
+			let swidth = match glyph.swidth {
+				Some((swx, _)) => swx,
+				None if self.size.pt > 0 && self.size.xres > 0 => {
+					advance(glyph) as f64 * 72000.0
+						/ (self.size.pt as f64 * self.size.xres as f64)
+				},
+				None => 0.0
+			};
+			swidths.write_i32(swidth.round() as i32);
+		}
+
+		let mut names = TableWriter::new(PCF_GLYPH_NAMES, PCF_DEFAULT_FORMAT | flags);
+		let mut strings = Strings::default();
+		names.write_count(glyphs.len());
+		for glyph in &glyphs {
+			names.write_count(strings.push(&glyph.name));
+		}
+		names.write_count(strings.0.len());
+		names.buf.extend(strings.0);
+
+		let mut tables = vec![
+			properties_table(self, flags),
+			accelerators_table(PCF_ACCELERATORS, &accel, flags),
+			metrics_table(PCF_METRICS, &metrics, flags),
+			bitmaps_table(&glyphs, options, flags),
+		];
+		if accel.ink_metrics {
+			tables.push(metrics_table(PCF_INK_METRICS, &ink, flags));
+		}
+		tables.extend([
+			encodings_table(&glyphs, default_char, flags),
+			swidths,
+			names,
+			accelerators_table(PCF_BDF_ACCELERATORS, &accel, flags)
+		]);
+
+		let mut header = MAGIC.to_vec();
+		header.extend((tables.len() as i32).to_le_bytes());
+		let mut offset = header.len() + 16 * tables.len();
+		for table in &mut tables {
+			table.write_padding();
+			header.extend(table.ty.to_le_bytes());
+			header.extend(table.format.to_le_bytes());
+			header.extend((table.buf.len() as i32).to_le_bytes());
+			header.extend((offset as i32).to_le_bytes());
+			offset += table.buf.len();
+		}
+
+		writer.write_all(&header)?;
+		for table in tables {
+			writer.write_all(&table.buf)?;
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,46 @@
+//! The PFF2 font format used by the GRUB bootloader, usually with the `.pf2` file
+//! extension.
+//!
+//! A file is a sequence of sections, each consisting of a four character name, a
+//! big endian length and the section data. The glyphs are stored in the `DATA`
+//! section, which extends to the end of the file, and are located through the
+//! character index in the `CHIX` section.
+
+use std::io;
+use thiserror::Error;
+
+mod reader;
+mod writer;
+
+/// The `FILE` section at the start of every PF2 font.
+pub(crate) const MAGIC: &[u8; 12] = b"FILE\0\0\0\x04PFF2";
+
+/// The size of a character index entry.
+pub(crate) const CHIX_ENTRY_SIZE: usize = 9;
+
+/// The size of the header of a glyph in the `DATA` section.
+pub(crate) const GLYPH_HEADER_SIZE: usize = 10;
+
+/// The length of the `DATA` section, which extends to the end of the file.
+pub(crate) const DATA_LENGTH: u32 = 0xFFFF_FFFF;
+
+#[derive(Debug, Error)]
+pub enum Pf2Error {
+	#[error("I/O Error: {0}")]
+	IOError(#[from] io::Error),
+
+	#[error("Not a PF2 file")]
+	InvalidMagic,
+
+	#[error("Unexpected end of file")]
+	UnexpectedEof,
+
+	#[error("Missing section {0}")]
+	MissingSection(&'static str),
+
+	#[error("Unsupported storage flags {1:#x} of glyph {0}")]
+	UnsupportedStorage(u32, u8),
+
+	#[error("Metrics of glyph {0} exceed the range supported by the format")]
+	MetricsOutOfRange(u32)
+}
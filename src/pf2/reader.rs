@@ -0,0 +1,202 @@
+use super::*;
+use crate::{
+	compress::decompress, font::GlyphWrapper, layout::advance, BoundingBox, Font, Glyph,
+	Size, Value
+};
+use bit_vec::BitVec;
+use std::{
+	collections::{BTreeSet, HashMap},
+	io::{BufReader, Read}
+};
+
+/// A big endian cursor over a PF2 file.
+struct Data<'a>(&'a [u8]);
+
+impl<'a> Data<'a> {
+	fn bytes(&self, offset: usize, len: usize) -> Result<&'a [u8], Pf2Error> {
+		self.0
+			.get(offset .. offset.saturating_add(len))
+			.ok_or(Pf2Error::UnexpectedEof)
+	}
+
+	fn u16(&self, offset: usize) -> Result<u16, Pf2Error> {
+		let bytes = self.bytes(offset, 2)?;
+		Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+	}
+
+	fn i16(&self, offset: usize) -> Result<i16, Pf2Error> {
+		Ok(self.u16(offset)? as i16)
+	}
+
+	fn u32(&self, offset: usize) -> Result<u32, Pf2Error> {
+		let bytes = self.bytes(offset, 4)?;
+		Ok(u32::from_be_bytes(bytes.try_into().unwrap()))
+	}
+}
+
+/// Split a PF2 file into its sections, stopping at the `DATA` section.
+fn sections<'a>(data: &Data<'a>) -> Result<HashMap<[u8; 4], &'a [u8]>, Pf2Error> {
+	if !data.0.starts_with(MAGIC) {
+		return Err(Pf2Error::InvalidMagic);
+	}
+	let mut sections = HashMap::new();
+	let mut pos = MAGIC.len();
+	while pos < data.0.len() {
+		let name: [u8; 4] = data.bytes(pos, 4)?.try_into().unwrap();
+		let len = data.u32(pos + 4)?;
+		if &name == b"DATA" {
+			break;
+		}
+		sections.insert(name, data.bytes(pos + 8, len as usize)?);
+		pos += 8 + len as usize;
+	}
+	Ok(sections)
+}
+
+/// Get a string from a section, which is terminated by a NUL byte.
+fn string(section: &[u8]) -> String {
+	let len = section
+		.iter()
+		.position(|b| *b == 0)
+		.unwrap_or(section.len());
+	String::from_utf8_lossy(&section[.. len]).into_owned()
+}
+
+impl Font {
+	/// Read a font in GRUB's PF2 format.
+	///
+	/// The point size is treated as the pixel size at a resolution of 72 dpi, like
+	/// `grub-mkfont` does. The glyph encodings are Unicode code points.
+	pub fn read_pf2<R: Read>(reader: R) -> Result<Self, Pf2Error> {
+		let mut buf = Vec::new();
+		decompress(BufReader::new(reader))?.read_to_end(&mut buf)?;
+		let data = Data(&buf);
+		let sections = sections(&data)?;
+
+		let section = |name: &'static str| {
+			sections
+				.get(name.as_bytes())
+				.copied()
+				.ok_or(Pf2Error::MissingSection(name))
+		};
+		let number = |name: &'static str| -> Result<Option<u16>, Pf2Error> {
+			match sections.get(name.as_bytes()) {
+				Some(section) => Ok(Some(Data(section).u16(0)?)),
+				None => Ok(None)
+			}
+		};
+		let family = sections.get(b"FAMI").map(|s| string(s));
+		let bold = sections.get(b"WEIG").is_some_and(|s| string(s) == "bold");
+		let italic = sections.get(b"SLAN").is_some_and(|s| string(s) == "italic");
+		let point_size = number("PTSZ")?.unwrap_or(0);
+		let ascent = number("ASCE")?.unwrap_or(0);
+		let descent = number("DESC")?.unwrap_or(0);
+
+		let chix = section("CHIX")?;
+		let mut glyphs = BTreeSet::new();
+		for entry in chix.chunks_exact(CHIX_ENTRY_SIZE) {
+			let entry = Data(entry);
+			let encoding = entry.u32(0)?;
+			let storage = entry.bytes(4, 1)?[0];
+			if storage & 0x07 != 0 {
+				return Err(Pf2Error::UnsupportedStorage(encoding, storage));
+			}
+			let offset = entry.u32(5)? as usize;
+
+			let width = data.u16(offset)?;
+			let height = data.u16(offset + 2)?;
+			let offset_x = data.i16(offset + 4)?;
+			let offset_y = data.i16(offset + 6)?;
+			let device_width = data.i16(offset + 8)?;
+			let (width, height) = (usize::from(width), usize::from(height));
+			let bits =
+				data.bytes(offset + GLYPH_HEADER_SIZE, (width * height).div_ceil(8))?;
+			let bitmap = (0 .. height)
+				.map(|y| {
+					let mut row = BitVec::from_elem(width.div_ceil(8) * 8, false);
+					for x in 0 .. width {
+						let bit = y * width + x;
+						row.set(x, bits[bit / 8] & (0x80 >> (bit % 8)) != 0);
+					}
+					row
+				})
+				.collect();
+
+			let dwidth = f64::from(device_width);
+			let swidth = match point_size {
+				0 => 0.0,
+				size => (dwidth * 1000.0 / f64::from(size)).round()
+			};
+			glyphs.insert(GlyphWrapper(Glyph {
+				name: format!("uni{encoding:04X}"),
+				encoding,
+				swidth: Some((swidth, 0.0)),
+				dwidth: Some((dwidth, 0.0)),
+				bbox: BoundingBox {
+					width: width as u32,
+					height: height as u32,
+					offset_x: offset_x.into(),
+					offset_y: offset_y.into()
+				},
+				bitmap
+			}));
+		}
+
+		let family = family.unwrap_or_else(|| "Unknown".to_owned());
+		let weight_name = match bold {
+			true => "Bold",
+			false => "Medium"
+		};
+		let slant = match italic {
+			true => "I",
+			false => "R"
+		};
+		let advances: Vec<i32> = glyphs.iter().map(|gw| advance(&gw.0)).collect();
+		let spacing = match advances.windows(2).all(|w| w[0] == w[1]) {
+			true => "C",
+			false => "P"
+		};
+		let average_width = match advances.len() {
+			0 => 0,
+			len => advances.iter().sum::<i32>() * 10 / len as i32
+		};
+		let name = format!(
+			"-misc-{family}-{weight_name}-{slant}-Normal--{point_size}-{}-72-72-{spacing}-\
+			 {average_width}-iso10646-1",
+			u32::from(point_size) * 10
+		);
+
+		let bbox = glyphs
+			.iter()
+			.fold(BoundingBox::default(), |bbox, gw| bbox.union(gw.0.bbox));
+		Ok(Self {
+			version: None,
+			name,
+			bbox,
+			size: Size {
+				pt: point_size.into(),
+				xres: 72,
+				yres: 72
+			},
+			properties: HashMap::from([
+				("FAMILY_NAME".into(), Value::String(family)),
+				("WEIGHT_NAME".into(), Value::String(weight_name.into())),
+				("SLANT".into(), Value::String(slant.into())),
+				("PIXEL_SIZE".into(), Value::Integer(point_size.into())),
+				(
+					"POINT_SIZE".into(),
+					Value::Integer(i32::from(point_size) * 10)
+				),
+				("RESOLUTION_X".into(), Value::Integer(72)),
+				("RESOLUTION_Y".into(), Value::Integer(72)),
+				("SPACING".into(), Value::String(spacing.into())),
+				("AVERAGE_WIDTH".into(), Value::Integer(average_width)),
+				("CHARSET_REGISTRY".into(), Value::String("iso10646".into())),
+				("CHARSET_ENCODING".into(), Value::String("1".into())),
+				("FONT_ASCENT".into(), Value::Integer(ascent.into())),
+				("FONT_DESCENT".into(), Value::Integer(descent.into()))
+			]),
+			glyphs
+		})
+	}
+}
@@ -0,0 +1,124 @@
+use super::*;
+use crate::{layout::advance, sfnt::clamp_u16, Font, Glyph};
+use std::io::Write;
+
+/// Append a section with the given name and data.
+fn section(buf: &mut Vec<u8>, name: &[u8; 4], data: &[u8]) {
+	buf.extend(name);
+	buf.extend((data.len() as u32).to_be_bytes());
+	buf.extend(data);
+}
+
+/// Get the data of a section containing a NUL terminated string.
+fn string(value: &str) -> Vec<u8> {
+	let mut data: Vec<u8> = value.bytes().filter(|b| *b != 0).collect();
+	data.push(0);
+	data
+}
+
+/// Encode a glyph with its metrics and bitmap, whose rows are packed without any
+/// padding.
+fn glyph_data(glyph: &Glyph) -> Result<Vec<u8>, Pf2Error> {
+	let out_of_range = || Pf2Error::MetricsOutOfRange(glyph.encoding);
+	let bbox = glyph.bbox;
+	let width = u16::try_from(bbox.width).map_err(|_| out_of_range())?;
+	let height = u16::try_from(bbox.height).map_err(|_| out_of_range())?;
+	let offset_x = i16::try_from(bbox.offset_x).map_err(|_| out_of_range())?;
+	let offset_y = i16::try_from(bbox.offset_y).map_err(|_| out_of_range())?;
+	let device_width = i16::try_from(advance(glyph)).map_err(|_| out_of_range())?;
+
+	let mut data = Vec::new();
+	data.extend(width.to_be_bytes());
+	data.extend(height.to_be_bytes());
+	data.extend(offset_x.to_be_bytes());
+	data.extend(offset_y.to_be_bytes());
+	data.extend(device_width.to_be_bytes());
+
+	let bitmap = glyph.bitmap();
+	let mut bits = vec![0u8; (bitmap.width() * bitmap.height()).div_ceil(8)];
+	for y in 0 .. bitmap.height() {
+		for x in 0 .. bitmap.width() {
+			if bitmap.get(x, y).unwrap_or(false) {
+				let bit = y * bitmap.width() + x;
+				bits[bit / 8] |= 0x80 >> (bit % 8);
+			}
+		}
+	}
+	data.extend(bits);
+	Ok(data)
+}
+
+impl Font {
+	/// Write this font in GRUB's PF2 format.
+	///
+	/// The encodings are assumed to be Unicode code points. The font name is built
+	/// from the `FAMILY_NAME`, `WEIGHT_NAME` and `SLANT` properties and the pixel
+	/// size, which GRUB treats as the point size, like `grub-mkfont` does.
+	pub fn write_pf2<W: Write>(&self, mut writer: W) -> Result<(), Pf2Error> {
+		let glyphs: Vec<&Glyph> = self.glyphs().into_iter().collect();
+		let (ascent, descent) = self.ascent_descent();
+		let point_size = self.pixels_per_em();
+		let family = self
+			.string_property("FAMILY_NAME")
+			.unwrap_or(&self.name)
+			.to_owned();
+		let bold = self
+			.string_property("WEIGHT_NAME")
+			.is_some_and(|name| name.to_ascii_lowercase().contains("bold"));
+		let italic = matches!(self.string_property("SLANT"), Some("I" | "O"));
+		let style = match (bold, italic) {
+			(false, false) => "Regular",
+			(true, false) => "Bold",
+			(false, true) => "Italic",
+			(true, true) => "Bold Italic"
+		};
+		let number = |value: i32| clamp_u16(value).to_be_bytes();
+
+		let mut buf = MAGIC.to_vec();
+		section(
+			&mut buf,
+			b"NAME",
+			&string(&format!("{family} {style} {point_size}"))
+		);
+		section(&mut buf, b"FAMI", &string(&family));
+		section(
+			&mut buf,
+			b"WEIG",
+			&string(match bold {
+				true => "bold",
+				false => "normal"
+			})
+		);
+		section(
+			&mut buf,
+			b"SLAN",
+			&string(match italic {
+				true => "italic",
+				false => "normal"
+			})
+		);
+		section(&mut buf, b"PTSZ", &number(point_size));
+		let max = |f: fn(&&Glyph) -> u32| glyphs.iter().map(f).max().unwrap_or(0);
+		section(&mut buf, b"MAXW", &number(max(|g| g.bbox.width) as i32));
+		section(&mut buf, b"MAXH", &number(max(|g| g.bbox.height) as i32));
+		section(&mut buf, b"ASCE", &number(ascent));
+		section(&mut buf, b"DESC", &number(descent));
+
+		// the glyph data follows the character index and the header of the DATA section
+		let mut data = Vec::new();
+		let mut chix = Vec::with_capacity(glyphs.len() * CHIX_ENTRY_SIZE);
+		let data_offset = buf.len() + 8 + glyphs.len() * CHIX_ENTRY_SIZE + 8;
+		for glyph in &glyphs {
+			chix.extend(glyph.encoding.to_be_bytes());
+			chix.push(0); // uncompressed
+			chix.extend(((data_offset + data.len()) as u32).to_be_bytes());
+			data.extend(glyph_data(glyph)?);
+		}
+		section(&mut buf, b"CHIX", &chix);
+		buf.extend(b"DATA");
+		buf.extend(DATA_LENGTH.to_be_bytes());
+		buf.extend(data);
+		writer.write_all(&buf)?;
+		Ok(())
+	}
+}
@@ -0,0 +1,90 @@
+//! The PC Screen Font (PSF) format used by the Linux console.
+//!
+//! See <https://www.win.tue.nl/~aeb/linux/kbd/font-formats-1.html> for a description
+//! of both versions of the format.
+
+use std::io;
+use thiserror::Error;
+
+mod reader;
+mod writer;
+
+pub(crate) const PSF1_MAGIC: &[u8; 2] = b"\x36\x04";
+pub(crate) const PSF1_MODE512: u8 = 0x01;
+pub(crate) const PSF1_MODEHASTAB: u8 = 0x02;
+pub(crate) const PSF1_MODEHASSEQ: u8 = 0x04;
+pub(crate) const PSF1_SEPARATOR: u16 = 0xFFFF;
+pub(crate) const PSF1_STARTSEQ: u16 = 0xFFFE;
+
+pub(crate) const PSF2_MAGIC: &[u8; 4] = b"\x72\xb5\x4a\x86";
+pub(crate) const PSF2_HAS_UNICODE_TABLE: u32 = 0x01;
+pub(crate) const PSF2_HEADER_SIZE: u32 = 32;
+pub(crate) const PSF2_SEPARATOR: u8 = 0xFF;
+pub(crate) const PSF2_STARTSEQ: u8 = 0xFE;
+
+#[derive(Debug, Error)]
+pub enum PsfError {
+	#[error("I/O Error: {0}")]
+	IOError(#[from] io::Error),
+
+	#[error("Not a PSF file")]
+	InvalidMagic,
+
+	#[error("Unsupported PSF2 version {0}")]
+	UnsupportedVersion(u32),
+
+	#[error("Unexpected end of file")]
+	UnexpectedEof,
+
+	#[error("Invalid unicode table")]
+	InvalidUnicodeTable,
+
+	#[error("Glyph {0} has a different advance than the other glyphs")]
+	ProportionalFont(u32),
+
+	#[error("Glyph {0} exceeds the character cell")]
+	GlyphOutsideCell(u32),
+
+	#[error("PSF1 fonts must be 8 pixels wide and at most 255 pixels high, not {0}x{1}")]
+	UnsupportedSize(u32, u32),
+
+	#[error("Too many glyphs: {0}")]
+	TooManyGlyphs(usize)
+}
+
+/// The version of the PSF format.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum PsfVersion {
+	/// The original format, limited to 8 pixels wide fonts with 256 or 512 glyphs
+	/// and a unicode table restricted to the Basic Multilingual Plane.
+	Psf1,
+
+	/// The extended format supporting arbitrary sizes and glyph counts.
+	#[default]
+	Psf2
+}
+
+/// Options for [writing PSF fonts](crate::Font::write_psf).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PsfOptions {
+	pub version: PsfVersion,
+
+	/// Write a unicode table mapping characters to glyphs. Glyphs with identical
+	/// bitmaps are stored only once. Without a unicode table, the encoding of each
+	/// glyph is used as its position in the font.
+	pub unicode_table: bool,
+
+	/// Add the canonical decomposition of each precomposed character to the unicode
+	/// table, so that the console can display decomposed input.
+	pub sequences: bool
+}
+
+impl Default for PsfOptions {
+	fn default() -> Self {
+		Self {
+			version: PsfVersion::default(),
+			unicode_table: true,
+			sequences: true
+		}
+	}
+}
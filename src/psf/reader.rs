@@ -0,0 +1,233 @@
+use super::*;
+use crate::{
+	compress::decompress, font::GlyphWrapper, BoundingBox, Font, Glyph, Size, Value
+};
+use bit_vec::BitVec;
+use log::warn;
+use std::{
+	collections::{BTreeSet, HashMap},
+	io::{BufReader, Read}
+};
+use unicode_normalization::UnicodeNormalization;
+
+/// The characters of a single glyph as stored in the unicode table.
+#[derive(Default)]
+struct Entry {
+	chars: Vec<char>,
+	sequences: Vec<Vec<char>>
+}
+
+impl Entry {
+	/// Get the encodings of this entry. Sequences are mapped to the character they
+	/// compose to, and sequences that do not compose to a single character are
+	/// dropped since they cannot be represented as a glyph encoding.
+	fn encodings(self) -> Vec<u32> {
+		let mut encodings: Vec<u32> = self.chars.into_iter().map(u32::from).collect();
+		for seq in self.sequences {
+			let mut composed = seq.iter().copied().nfc();
+			match (composed.next(), composed.next()) {
+				(Some(ch), None) => encodings.push(ch.into()),
+				_ => warn!("Dropping unicode sequence {seq:?} without a composed form")
+			}
+		}
+		encodings
+	}
+}
+
+fn u32_at(buf: &[u8], pos: usize) -> Result<u32, PsfError> {
+	buf.get(pos .. pos + 4)
+		.map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+		.ok_or(PsfError::UnexpectedEof)
+}
+
+fn char_of(cp: u32) -> Result<char, PsfError> {
+	char::from_u32(cp).ok_or(PsfError::InvalidUnicodeTable)
+}
+
+/// Parse the PSF1 unicode table with 16 bit code points.
+fn read_psf1_table(data: &[u8], count: usize) -> Result<Vec<Entry>, PsfError> {
+	let mut values = data
+		.chunks_exact(2)
+		.map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]));
+	let mut entries = Vec::new();
+	for _ in 0 .. count {
+		let mut entry = Entry::default();
+		let mut seq: Option<Vec<char>> = None;
+		loop {
+			match values.next().ok_or(PsfError::UnexpectedEof)? {
+				PSF1_SEPARATOR => break,
+				PSF1_STARTSEQ => entry.sequences.extend(seq.replace(Vec::new())),
+				cp => match &mut seq {
+					Some(seq) => seq.push(char_of(cp.into())?),
+					None => entry.chars.push(char_of(cp.into())?)
+				}
+			}
+		}
+		entry.sequences.extend(seq);
+		entries.push(entry);
+	}
+	Ok(entries)
+}
+
+/// Parse the PSF2 unicode table with UTF-8 encoded code points.
+fn read_psf2_table(data: &[u8], count: usize) -> Result<Vec<Entry>, PsfError> {
+	let mut data = data;
+	let mut entries = Vec::new();
+	for _ in 0 .. count {
+		let end = data
+			.iter()
+			.position(|byte| *byte == PSF2_SEPARATOR)
+			.ok_or(PsfError::UnexpectedEof)?;
+		let mut parts = data[.. end]
+			.split(|byte| *byte == PSF2_STARTSEQ)
+			.map(|part| {
+				std::str::from_utf8(part).map_err(|_| PsfError::InvalidUnicodeTable)
+			});
+		entries.push(Entry {
+			chars: parts.next().unwrap()?.chars().collect(),
+			sequences: parts
+				.map(|part| Ok(part?.chars().collect()))
+				.collect::<Result<_, PsfError>>()?
+		});
+		data = &data[end + 1 ..];
+	}
+	Ok(entries)
+}
+
+impl Font {
+	/// Read a font in the PC Screen Font format, either version 1 or 2. With the
+	/// `gzip` feature enabled, gzip compressed input is decompressed on the fly.
+	///
+	/// PSF fonts have no baseline, so the bottom quarter of the character cell is
+	/// assumed to be below the baseline. Unicode sequences are mapped to the
+	/// character they compose to, and dropped if there is no such character. Fonts
+	/// without a unicode table use the glyph position as the encoding.
+	pub fn read_psf<R: Read>(reader: R) -> Result<Self, PsfError> {
+		let mut buf = Vec::new();
+		decompress(BufReader::new(reader))?.read_to_end(&mut buf)?;
+
+		let (width, height, count, offset, char_size, table) = if buf
+			.starts_with(PSF1_MAGIC)
+		{
+			let mode = *buf.get(2).ok_or(PsfError::UnexpectedEof)?;
+			let height = *buf.get(3).ok_or(PsfError::UnexpectedEof)? as u32;
+			let count = match mode & PSF1_MODE512 {
+				0 => 256,
+				_ => 512
+			};
+			let table = (mode & (PSF1_MODEHASTAB | PSF1_MODEHASSEQ) != 0).then(|| {
+				let start = 4 + count * height as usize;
+				read_psf1_table(buf.get(start ..).unwrap_or_default(), count)
+			});
+			(8, height, count, 4, height as usize, table)
+		} else if buf.starts_with(PSF2_MAGIC) {
+			let version = u32_at(&buf, 4)?;
+			if version != 0 {
+				return Err(PsfError::UnsupportedVersion(version));
+			}
+			let header_size = u32_at(&buf, 8)? as usize;
+			let flags = u32_at(&buf, 12)?;
+			let count = u32_at(&buf, 16)? as usize;
+			let char_size = u32_at(&buf, 20)? as usize;
+			let height = u32_at(&buf, 24)?;
+			let width = u32_at(&buf, 28)?;
+			if char_size < (width as usize).div_ceil(8) * height as usize {
+				return Err(PsfError::UnexpectedEof);
+			}
+			// don't trust the glyph count, and count empty glyphs as one byte so that the
+			// number of glyphs is bounded by the file size
+			let glyphs_end = count
+				.checked_mul(char_size.max(1))
+				.and_then(|size| size.checked_add(header_size))
+				.ok_or(PsfError::UnexpectedEof)?;
+			if glyphs_end > buf.len() {
+				return Err(PsfError::UnexpectedEof);
+			}
+			let table = (flags & PSF2_HAS_UNICODE_TABLE != 0).then(|| {
+				let start = header_size + count * char_size;
+				read_psf2_table(buf.get(start ..).unwrap_or_default(), count)
+			});
+			(width, height, count, header_size, char_size, table)
+		} else {
+			return Err(PsfError::InvalidMagic);
+		};
+		let entries = table.transpose()?;
+		let row_bytes = (width as usize).div_ceil(8);
+
+		let descent = height / 4;
+		let ascent = height - descent;
+		let bbox = BoundingBox {
+			width,
+			height,
+			offset_x: 0,
+			offset_y: -(descent as i32)
+		};
+		let swidth = (width as f64 * 1000.0 / height.max(1) as f64).round();
+
+		let mut glyphs = BTreeSet::new();
+		let mut entries = entries.map(Vec::into_iter);
+		for idx in 0 .. count {
+			let start = offset + idx * char_size;
+			let data = buf
+				.get(start .. start + row_bytes * height as usize)
+				.ok_or(PsfError::UnexpectedEof)?;
+			let bitmap: Vec<BitVec> = data
+				.chunks(row_bytes.max(1))
+				.map(|row| {
+					let mut row = BitVec::from_bytes(row);
+					for x in width as usize .. row.len() {
+						row.set(x, false);
+					}
+					row
+				})
+				.collect();
+
+			let (encodings, named) = match &mut entries {
+				Some(entries) => (entries.next().unwrap_or_default().encodings(), true),
+				None => (vec![idx as u32], false)
+			};
+			for encoding in encodings {
+				let glyph = Glyph {
+					name: match named {
+						true => format!("uni{encoding:04X}"),
+						false => format!("char{encoding}")
+					},
+					encoding,
+					swidth: Some((swidth, 0.0)),
+					dwidth: Some((width as f64, 0.0)),
+					bbox,
+					bitmap: bitmap.clone()
+				};
+				if !glyphs.insert(GlyphWrapper(glyph)) {
+					warn!(
+						"Ignoring duplicate mapping of U+{encoding:04X} to glyph {idx}"
+					);
+				}
+			}
+		}
+
+		let registry = match entries {
+			Some(_) => "iso10646-1",
+			None => "misc-fontspecific"
+		};
+		Ok(Self {
+			version: None,
+			name: format!(
+				"-misc-console-medium-r-normal--{height}-{}-72-72-c-{}-{registry}",
+				height * 10,
+				width * 10
+			),
+			bbox,
+			size: Size {
+				pt: height,
+				xres: 72,
+				yres: 72
+			},
+			properties: HashMap::from([
+				("FONT_ASCENT".into(), Value::Integer(ascent as i32)),
+				("FONT_DESCENT".into(), Value::Integer(descent as i32))
+			]),
+			glyphs
+		})
+	}
+}
@@ -0,0 +1,241 @@
+use super::*;
+use crate::{layout::advance, Font, Glyph};
+use log::warn;
+use std::{collections::HashMap, io::Write};
+use unicode_normalization::char::decompose_canonical;
+
+/// A glyph of the PSF font, together with the characters mapped to it.
+#[derive(Default)]
+struct Slot {
+	bitmap: Vec<u8>,
+	chars: Vec<u32>
+}
+
+impl Slot {
+	/// Get the unicode sequences of this slot, that is the canonical decompositions of
+	/// all precomposed characters, if sequences are enabled.
+	fn sequences(&self, enabled: bool) -> Vec<Vec<char>> {
+		if !enabled {
+			return Vec::new();
+		}
+		self.chars
+			.iter()
+			.filter_map(|cp| char::from_u32(*cp))
+			.filter_map(|ch| {
+				let mut seq = Vec::new();
+				decompose_canonical(ch, |part| seq.push(part));
+				(seq.len() > 1).then_some(seq)
+			})
+			.collect()
+	}
+}
+
+/// The character cell shared by all glyphs of a PSF font.
+struct Cell {
+	width: u32,
+	height: u32,
+	ascent: i32
+}
+
+impl Cell {
+	fn stride(&self) -> usize {
+		(self.width as usize).div_ceil(8)
+	}
+
+	/// Render a glyph into the cell, returning the row-major bitmap with each row
+	/// padded to full bytes.
+	fn render(&self, glyph: &Glyph) -> Result<Vec<u8>, PsfError> {
+		let stride = self.stride();
+		let mut data = vec![0; stride * self.height as usize];
+		let bitmap = glyph.bitmap();
+		let bbox = glyph.bbox;
+		let top = self.ascent - bbox.offset_y - bbox.height as i32;
+		for y in 0 .. bitmap.height() {
+			for x in 0 .. bitmap.width() {
+				if !bitmap.get(x, y).unwrap_or(false) {
+					continue;
+				}
+				let cx = bbox.offset_x + x as i32;
+				let cy = top + y as i32;
+				if cx < 0 || cx >= self.width as i32 || cy < 0 || cy >= self.height as i32
+				{
+					return Err(PsfError::GlyphOutsideCell(glyph.encoding));
+				}
+				let (cx, cy) = (cx as usize, cy as usize);
+				data[cy * stride + cx / 8] |= 0x80 >> (cx % 8);
+			}
+		}
+		Ok(data)
+	}
+}
+
+fn write_psf1_table<W: Write>(
+	writer: &mut W,
+	slots: &[Slot],
+	sequences: bool
+) -> Result<(), PsfError> {
+	let bmp = |cp: u32| match u16::try_from(cp) {
+		Ok(cp) if cp < PSF1_STARTSEQ => Some(cp),
+		_ => {
+			warn!("Omitting U+{cp:04X} from the PSF1 unicode table");
+			None
+		}
+	};
+	for slot in slots {
+		for cp in slot.chars.iter().filter_map(|cp| bmp(*cp)) {
+			writer.write_all(&cp.to_le_bytes())?;
+		}
+		for seq in slot.sequences(sequences) {
+			let Some(seq) = seq
+				.into_iter()
+				.map(|ch| bmp(ch.into()))
+				.collect::<Option<Vec<_>>>()
+			else {
+				continue;
+			};
+			writer.write_all(&PSF1_STARTSEQ.to_le_bytes())?;
+			for cp in seq {
+				writer.write_all(&cp.to_le_bytes())?;
+			}
+		}
+		writer.write_all(&PSF1_SEPARATOR.to_le_bytes())?;
+	}
+	Ok(())
+}
+
+fn write_psf2_table<W: Write>(
+	writer: &mut W,
+	slots: &[Slot],
+	sequences: bool
+) -> Result<(), PsfError> {
+	for slot in slots {
+		let chars: String = slot
+			.chars
+			.iter()
+			.filter_map(|cp| char::from_u32(*cp))
+			.collect();
+		writer.write_all(chars.as_bytes())?;
+		for seq in slot.sequences(sequences) {
+			writer.write_all(&[PSF2_STARTSEQ])?;
+			writer.write_all(seq.into_iter().collect::<String>().as_bytes())?;
+		}
+		writer.write_all(&[PSF2_SEPARATOR])?;
+	}
+	Ok(())
+}
+
+impl Font {
+	/// Write this font in the PC Screen Font format. PSF requires all glyphs to share
+	/// a character cell, whose width is the common advance of all glyphs and whose
+	/// height is given by `FONT_ASCENT` and `FONT_DESCENT`.
+	pub fn write_psf<W: Write>(
+		&self,
+		mut writer: W,
+		options: &PsfOptions
+	) -> Result<(), PsfError> {
+		let width = match self.glyphs().into_iter().next() {
+			Some(glyph) => advance(glyph),
+			None => self.bbox.width as i32
+		};
+		if let Some(glyph) = self.glyphs().into_iter().find(|g| advance(g) != width) {
+			return Err(PsfError::ProportionalFont(glyph.encoding));
+		}
+		let (ascent, descent) = self.ascent_descent();
+		let cell = Cell {
+			width: width.max(0) as u32,
+			height: (ascent + descent).max(0) as u32,
+			ascent
+		};
+		if options.version == PsfVersion::Psf1 && (cell.width != 8 || cell.height > 0xFF)
+		{
+			return Err(PsfError::UnsupportedSize(cell.width, cell.height));
+		}
+
+		let mut slots: Vec<Slot> = Vec::new();
+		if options.unicode_table {
+			let mut indices: HashMap<Vec<u8>, usize> = HashMap::new();
+			for glyph in self.glyphs() {
+				let bitmap = cell.render(glyph)?;
+				let idx = *indices.entry(bitmap.clone()).or_insert_with(|| {
+					slots.push(Slot {
+						bitmap,
+						chars: Vec::new()
+					});
+					slots.len() - 1
+				});
+				slots[idx].chars.push(glyph.encoding);
+			}
+		} else {
+			for glyph in self.glyphs() {
+				let idx = glyph.encoding as usize;
+				if idx >= 0x10000 {
+					return Err(PsfError::TooManyGlyphs(idx + 1));
+				}
+				slots.resize_with(slots.len().max(idx + 1), Slot::default);
+				slots[idx].bitmap = cell.render(glyph)?;
+			}
+		}
+
+		let count = match options.version {
+			PsfVersion::Psf1 if slots.len() <= 256 => 256,
+			PsfVersion::Psf1 if slots.len() <= 512 => 512,
+			PsfVersion::Psf1 => return Err(PsfError::TooManyGlyphs(slots.len())),
+			PsfVersion::Psf2 => slots.len()
+		};
+		slots.resize_with(count, Slot::default);
+		let char_size = cell.stride() * cell.height as usize;
+		for slot in &mut slots {
+			slot.bitmap.resize(char_size, 0);
+		}
+
+		match options.version {
+			PsfVersion::Psf1 => {
+				let mut mode = 0;
+				if count == 512 {
+					mode |= PSF1_MODE512;
+				}
+				if options.unicode_table {
+					mode |= PSF1_MODEHASTAB;
+					if options.sequences {
+						mode |= PSF1_MODEHASSEQ;
+					}
+				}
+				writer.write_all(PSF1_MAGIC)?;
+				writer.write_all(&[mode, cell.height as u8])?;
+			},
+			PsfVersion::Psf2 => {
+				let flags = match options.unicode_table {
+					true => PSF2_HAS_UNICODE_TABLE,
+					false => 0
+				};
+				writer.write_all(PSF2_MAGIC)?;
+				for value in [
+					0,
+					PSF2_HEADER_SIZE,
+					flags,
+					count as u32,
+					char_size as u32,
+					cell.height,
+					cell.width
+				] {
+					writer.write_all(&value.to_le_bytes())?;
+				}
+			}
+		}
+		for slot in &slots {
+			writer.write_all(&slot.bitmap)?;
+		}
+
+		if options.unicode_table {
+			match options.version {
+				PsfVersion::Psf1 => {
+					write_psf1_table(&mut writer, &slots, options.sequences)?
+				},
+				PsfVersion::Psf2 => {
+					write_psf2_table(&mut writer, &slots, options.sequences)?
+				},
+			}
+		}
+		Ok(())
+	}
+}
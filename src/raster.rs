@@ -0,0 +1,133 @@
+//! Rendering of bitmaps to colored images, behind the `png` and `image` features.
+
+use crate::Bitmap;
+#[cfg(feature = "png")]
+use crate::Layout;
+#[cfg(feature = "image")]
+use image::{GrayImage, Luma, RgbaImage};
+#[cfg(feature = "png")]
+use std::io::{self, Write};
+#[cfg(feature = "png")]
+use thiserror::Error;
+
+#[cfg(feature = "png")]
+#[derive(Debug, Error)]
+pub enum PngError {
+	#[error("I/O Error: {0}")]
+	IOError(#[from] io::Error),
+
+	#[error("PNG Encoding Error: {0}")]
+	EncodingError(#[from] png::EncodingError),
+
+	#[error("The scaled image exceeds the maximum size")]
+	ImageTooLarge
+}
+
+/// Options for rendering bitmaps to colored images.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RenderOptions {
+	/// The RGBA color of set pixels.
+	pub foreground: [u8; 4],
+
+	/// The RGBA color of unset pixels.
+	pub background: [u8; 4],
+
+	/// The integer factor each pixel is scaled by. A scale of 0 is treated as 1.
+	pub scale: u32
+}
+
+impl Default for RenderOptions {
+	fn default() -> Self {
+		Self {
+			foreground: [0, 0, 0, 0xFF],
+			background: [0xFF, 0xFF, 0xFF, 0xFF],
+			scale: 1
+		}
+	}
+}
+
+impl Bitmap<'_> {
+	/// Get the size of the rendered image and its RGBA pixels, stored row by row from
+	/// the top left corner. Returns `None` if the size exceeds `u32`.
+	fn rgba(self, options: &RenderOptions) -> Option<(u32, u32, Vec<u8>)> {
+		let scale = options.scale.max(1) as usize;
+		let width = self.width().checked_mul(scale)?;
+		let height = self.height().checked_mul(scale)?;
+		let mut buf = Vec::with_capacity(width.checked_mul(height)?.checked_mul(4)?);
+		for y in 0 .. height {
+			for x in 0 .. width {
+				buf.extend(match self.get(x / scale, y / scale).unwrap_or(false) {
+					true => options.foreground,
+					false => options.background
+				});
+			}
+		}
+		Some((width.try_into().ok()?, height.try_into().ok()?, buf))
+	}
+
+	/// Write this bitmap as an 8-bit RGBA PNG image.
+	#[cfg(feature = "png")]
+	pub fn write_png<W: Write>(
+		self,
+		writer: W,
+		options: &RenderOptions
+	) -> Result<(), PngError> {
+		let (width, height, data) = self.rgba(options).ok_or(PngError::ImageTooLarge)?;
+		let mut encoder = png::Encoder::new(writer, width, height);
+		encoder.set_color(png::ColorType::Rgba);
+		encoder.set_depth(png::BitDepth::Eight);
+		let mut writer = encoder.write_header()?;
+		writer.write_image_data(&data)?;
+		writer.finish()?;
+		Ok(())
+	}
+
+	/// Convert this bitmap into a coverage mask, with set pixels in white (255) on a
+	/// black (0) background.
+	#[cfg(feature = "image")]
+	pub fn to_gray_image(self) -> GrayImage {
+		GrayImage::from_fn(self.width() as u32, self.height() as u32, |x, y| {
+			let set = self.get(x as usize, y as usize).unwrap_or(false);
+			Luma([u8::from(set) * 0xFF])
+		})
+	}
+
+	/// Convert this bitmap into an RGBA image using the colors and scale of `options`.
+	///
+	/// # Panics
+	///
+	/// Panics if the scaled image exceeds the maximum size.
+	#[cfg(feature = "image")]
+	pub fn to_rgba_image(self, options: &RenderOptions) -> RgbaImage {
+		let (width, height, data) = self
+			.rgba(options)
+			.expect("the scaled image exceeds the maximum size");
+		RgbaImage::from_raw(width, height, data).unwrap()
+	}
+}
+
+#[cfg(feature = "png")]
+impl Layout<'_> {
+	/// Render this layout and write it as an 8-bit RGBA PNG image.
+	pub fn write_png<W: Write>(
+		&self,
+		writer: W,
+		options: &RenderOptions
+	) -> Result<(), PngError> {
+		self.render().as_bitmap().write_png(writer, options)
+	}
+}
+
+#[cfg(feature = "image")]
+impl From<Bitmap<'_>> for GrayImage {
+	fn from(bitmap: Bitmap<'_>) -> Self {
+		bitmap.to_gray_image()
+	}
+}
+
+#[cfg(feature = "image")]
+impl From<Bitmap<'_>> for RgbaImage {
+	fn from(bitmap: Bitmap<'_>) -> Self {
+		bitmap.to_rgba_image(&RenderOptions::default())
+	}
+}
@@ -1,4 +1,5 @@
 use crate::{
+	compress::decompress,
 	tokens::{Token, WritingDirection},
 	BoundingBox, Error, Font, Glyph, Size, Value
 };
@@ -81,7 +82,14 @@ impl State {
 }
 
 impl Font {
+	/// Read a font in the Glyph Bitmap Distribution Format (BDF). With the `gzip`
+	/// feature enabled, gzip compressed input is decompressed on the fly.
 	pub fn read<R: BufRead>(reader: R) -> Result<Self, Error> {
+		Self::read_decompressed(decompress(reader)?)
+	}
+
+	/// Read a font in the BDF format from input that is already decompressed.
+	pub(crate) fn read_decompressed<R: BufRead>(reader: R) -> Result<Self, Error> {
 		let mut font_version = None;
 		let mut font_name = None;
 		let mut font_size = None;
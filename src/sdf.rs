@@ -0,0 +1,250 @@
+//! Signed distance fields of glyphs, for smooth scaling on the GPU.
+
+use crate::{layout::advance, BoundingBox, Glyph};
+
+/// Options for generating a [`DistanceField`].
+#[derive(Clone, Copy, Debug)]
+pub struct SdfOptions {
+	/// The largest distance that can be encoded, in pixels of the distance field. The
+	/// field is padded by this many pixels on every side.
+	pub spread: u32,
+
+	/// The number of distance field pixels per glyph pixel.
+	pub upsampling: u32,
+
+	/// Whether to generate a multi-channel (MSDF) field with three channels, which
+	/// keeps the corners of the pixels sharp. The edges of each contour cycle through
+	/// the colors cyan, magenta and yellow, and the glyph is reconstructed from the
+	/// median of the channels.
+	pub multi_channel: bool
+}
+
+impl Default for SdfOptions {
+	fn default() -> Self {
+		Self {
+			spread: 4,
+			upsampling: 4,
+			multi_channel: false
+		}
+	}
+}
+
+/// An 8-bit distance field of a glyph. A value of 128 is on the outline, larger values
+/// are inside the glyph and smaller ones outside, with 0 and 255 at the spread.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DistanceField {
+	bbox: BoundingBox,
+	advance: i32,
+	channels: usize,
+	data: Vec<u8>
+}
+
+impl DistanceField {
+	/// Get the width of the distance field in pixels.
+	pub fn width(&self) -> u32 {
+		self.bbox.width
+	}
+
+	/// Get the height of the distance field in pixels.
+	pub fn height(&self) -> u32 {
+		self.bbox.height
+	}
+
+	/// Get the bounding box of the distance field relative to the glyph origin, in
+	/// pixels of the distance field. This is the bounding box of the glyph scaled by
+	/// the upsampling and grown by the padding.
+	pub fn bounding_box(&self) -> BoundingBox {
+		self.bbox
+	}
+
+	/// Get the horizontal distance the pen moves after drawing the glyph, in pixels of
+	/// the distance field.
+	pub fn advance(&self) -> i32 {
+		self.advance
+	}
+
+	/// Get the number of channels per pixel, 1 for single-channel and 3 (red, green,
+	/// blue) for multi-channel fields.
+	pub fn channels(&self) -> usize {
+		self.channels
+	}
+
+	/// Get the distances, stored row by row from the top left corner with the channels
+	/// of each pixel next to each other.
+	pub fn data(&self) -> &[u8] {
+		&self.data
+	}
+
+	/// Get the channels of the pixel in column `x` and row `y`, counted from the top
+	/// left corner.
+	pub fn get(&self, x: usize, y: usize) -> Option<&[u8]> {
+		if x >= self.width() as usize || y >= self.height() as usize {
+			return None;
+		}
+		let idx = (y * self.width() as usize + x) * self.channels;
+		Some(&self.data[idx .. idx + self.channels])
+	}
+}
+
+/// The channels of the multi-channel colors cyan, magenta and yellow.
+const COLORS: [u8; 3] = [0b110, 0b101, 0b011];
+
+/// A straight edge of an outline, in glyph pixels relative to the origin.
+struct Edge {
+	a: (f64, f64),
+	b: (f64, f64),
+	color: u8
+}
+
+/// The distance from a point to an edge. Distances are positive on the right side of
+/// the edge, where the outlines are filled.
+#[derive(Clone, Copy)]
+struct Distance {
+	/// The signed distance to the closest point of the edge.
+	distance: f64,
+
+	/// How much the point is off to the side when closest to an end point, which
+	/// prefers the edge facing the point if two edges share the end point.
+	dot: f64,
+
+	/// The signed distance to the line through the edge, if the point is beyond one of
+	/// the end points and that distance is smaller.
+	pseudo: f64
+}
+
+impl Distance {
+	fn is_closer(&self, other: &Self) -> bool {
+		let (d, other_d) = (self.distance.abs(), other.distance.abs());
+		d < other_d || (d == other_d && self.dot < other.dot)
+	}
+}
+
+impl Edge {
+	fn distance(&self, (x, y): (f64, f64)) -> Distance {
+		let (dx, dy) = (self.b.0 - self.a.0, self.b.1 - self.a.1);
+		let len = dx.hypot(dy);
+		let (ax, ay) = (x - self.a.0, y - self.a.1);
+		let t = (ax * dx + ay * dy) / (len * len);
+		let perpendicular = (ax * dy - ay * dx) / len;
+		let (nx, ny) = match t {
+			t if t < 0.0 => self.a,
+			t if t > 1.0 => self.b,
+			t => (self.a.0 + t * dx, self.a.1 + t * dy)
+		};
+		let (qx, qy) = (x - nx, y - ny);
+		let distance = qx.hypot(qy);
+		let dot = match (0.0 ..= 1.0).contains(&t) || distance == 0.0 {
+			true => 0.0,
+			false => ((qx * dx + qy * dy) / (distance * len)).abs()
+		};
+		let distance = distance.copysign(perpendicular);
+		let pseudo = match perpendicular.abs() <= distance.abs() {
+			true => perpendicular,
+			false => distance
+		};
+		Distance {
+			distance,
+			dot,
+			pseudo
+		}
+	}
+}
+
+/// Convert a signed distance in pixels of the distance field to an 8-bit value.
+fn encode(distance: f64, spread: u32) -> u8 {
+	let value = 0.5 + distance / (2 * spread) as f64;
+	(value * 255.0).round().clamp(0.0, 255.0) as u8
+}
+
+impl Glyph {
+	/// Generate a signed distance field of this glyph from the outlines of its pixels.
+	/// The field covers the bounding box of the glyph, scaled by the upsampling and
+	/// padded by the spread, and is sampled at the pixel centers.
+	pub fn distance_field(&self, options: &SdfOptions) -> DistanceField {
+		let (spread, scale) = (options.spread.max(1), options.upsampling.max(1));
+		let bitmap = self.bitmap();
+		let bbox = self.bbox;
+		let top = bbox.offset_y + bbox.height as i32;
+		let field_bbox = BoundingBox {
+			width: bbox.width * scale + 2 * spread,
+			height: bbox.height * scale + 2 * spread,
+			offset_x: bbox.offset_x * scale as i32 - spread as i32,
+			offset_y: bbox.offset_y * scale as i32 - spread as i32
+		};
+
+		let mut edges = Vec::new();
+		for contour in bitmap.contours() {
+			let n = contour.len();
+			for i in 0 .. n {
+				let (a, b) = (contour[i], contour[(i + 1) % n]);
+				// neighbouring edges, including the last and first one, share one channel
+				let color = match i == n - 1 && n % 3 == 1 {
+					true => COLORS[1],
+					false => COLORS[i % 3]
+				};
+				edges.push(Edge {
+					a: (a.0 as f64, a.1 as f64),
+					b: (b.0 as f64, b.1 as f64),
+					color
+				});
+			}
+		}
+
+		let channels = match options.multi_channel {
+			true => 3,
+			false => 1
+		};
+		let (width, height) = (field_bbox.width as usize, field_bbox.height as usize);
+		let field_top = field_bbox.offset_y + height as i32;
+		let mut data = Vec::with_capacity(width * height * channels);
+		for row in 0 .. height {
+			for col in 0 .. width {
+				let x = (field_bbox.offset_x as f64 + col as f64 + 0.5) / scale as f64;
+				let y = (field_top as f64 - row as f64 - 0.5) / scale as f64;
+				let distances: Vec<(u8, Distance)> = edges
+					.iter()
+					.map(|edge| (edge.color, edge.distance((x, y))))
+					.collect();
+
+				if options.multi_channel {
+					for channel in [0b001, 0b010, 0b100] {
+						let distance = distances
+							.iter()
+							.filter(|(color, _)| color & channel != 0)
+							.map(|(_, distance)| *distance)
+							.reduce(|a, b| match b.is_closer(&a) {
+								true => b,
+								false => a
+							})
+							.map_or(f64::NEG_INFINITY, |distance| distance.pseudo);
+						data.push(encode(distance * scale as f64, spread));
+					}
+					continue;
+				}
+
+				let distance = distances
+					.iter()
+					.map(|(_, distance)| distance.distance.abs())
+					.fold(f64::INFINITY, f64::min);
+				let (px, py) =
+					(x.floor() as i32 - bbox.offset_x, top - 1 - y.floor() as i32);
+				// rows keep the padding bits beyond the width, which are not inside
+				let inside = (0 .. bitmap.width() as i32).contains(&px)
+					&& (0 .. bitmap.height() as i32).contains(&py)
+					&& bitmap.get(px as usize, py as usize).unwrap_or(false);
+				let distance = match inside {
+					true => distance,
+					false => -distance
+				};
+				data.push(encode(distance * scale as f64, spread));
+			}
+		}
+
+		DistanceField {
+			bbox: field_bbox,
+			advance: advance(self) * scale as i32,
+			channels,
+			data
+		}
+	}
+}
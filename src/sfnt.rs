@@ -0,0 +1,559 @@
+//! Fonts in the SFNT container used by OpenType and TrueType.
+//!
+//! See <https://learn.microsoft.com/en-us/typography/opentype/spec/> for a
+//! description of the container and its tables.
+
+use crate::{layout::advance, BoundingBox, Font, Glyph};
+use std::io::{self, Write};
+use thiserror::Error;
+
+mod otb;
+mod reader;
+mod ttf;
+
+/// The largest number of segments that fit into a format 4 `cmap` subtable, whose
+/// length of 16 + 8 * segments bytes is stored in 16 bits.
+const MAX_FORMAT4_SEGMENTS: usize = (0xFFFF - 16) / 8;
+
+#[derive(Debug, Error)]
+pub enum SfntError {
+	#[error("I/O Error: {0}")]
+	IOError(#[from] io::Error),
+
+	#[error("At least one strike is required")]
+	NoStrikes,
+
+	#[error("Too many glyphs: {0}")]
+	TooManyGlyphs(usize),
+
+	#[error("Metrics of glyph {0} exceed the range supported by the format")]
+	MetricsOutOfRange(u32),
+
+	#[error("Not an SFNT font")]
+	InvalidMagic,
+
+	#[error("Unexpected end of file")]
+	UnexpectedEof,
+
+	#[error("Missing table {0:?}")]
+	MissingTable(String),
+
+	#[error("Unsupported {0} format {1}")]
+	UnsupportedFormat(&'static str, u16)
+}
+
+/// Append big endian values to a buffer.
+pub(crate) trait Put {
+	fn put_u8(&mut self, value: u8);
+	fn put_i8(&mut self, value: i8);
+	fn put_u16(&mut self, value: u16);
+	fn put_i16(&mut self, value: i16);
+	fn put_u32(&mut self, value: u32);
+	fn put_i64(&mut self, value: i64);
+}
+
+impl Put for Vec<u8> {
+	fn put_u8(&mut self, value: u8) {
+		self.push(value);
+	}
+
+	fn put_i8(&mut self, value: i8) {
+		self.push(value as u8);
+	}
+
+	fn put_u16(&mut self, value: u16) {
+		self.extend(value.to_be_bytes());
+	}
+
+	fn put_i16(&mut self, value: i16) {
+		self.extend(value.to_be_bytes());
+	}
+
+	fn put_u32(&mut self, value: u32) {
+		self.extend(value.to_be_bytes());
+	}
+
+	fn put_i64(&mut self, value: i64) {
+		self.extend(value.to_be_bytes());
+	}
+}
+
+/// Clamp a value into the range of an `i16`.
+pub(crate) fn clamp_i16(value: i32) -> i16 {
+	value.clamp(i16::MIN.into(), i16::MAX.into()) as i16
+}
+
+/// Clamp a value into the range of an `u16`.
+pub(crate) fn clamp_u16(value: i32) -> u16 {
+	value.clamp(0, u16::MAX.into()) as u16
+}
+
+/// Get the checksum of a table, padded to a multiple of 4 bytes.
+fn checksum(data: &[u8]) -> u32 {
+	data.chunks(4).fold(0u32, |sum, chunk| {
+		let mut word = [0; 4];
+		word[.. chunk.len()].copy_from_slice(chunk);
+		sum.wrapping_add(u32::from_be_bytes(word))
+	})
+}
+
+/// Write tables into an SFNT container. The `head` table must be present and will
+/// have its checksum adjustment updated.
+pub(crate) fn write_sfnt<W: Write>(
+	mut writer: W,
+	mut tables: Vec<(&[u8; 4], Vec<u8>)>
+) -> Result<(), SfntError> {
+	tables.sort_by_key(|(tag, _)| **tag);
+	let num_tables = tables.len() as u16;
+	let entry_selector = 15 - num_tables.max(1).leading_zeros() as u16;
+	let search_range = 16 << entry_selector;
+
+	let mut header = Vec::new();
+	header.put_u32(0x0001_0000);
+	header.put_u16(num_tables);
+	header.put_u16(search_range);
+	header.put_u16(entry_selector);
+	header.put_u16(num_tables * 16 - search_range);
+
+	let mut offset = 12 + 16 * tables.len();
+	let mut body = Vec::new();
+	let mut head_offset = None;
+	for (tag, data) in &tables {
+		if *tag == b"head" {
+			head_offset = Some(offset);
+		}
+		header.extend(*tag);
+		header.put_u32(checksum(data));
+		header.put_u32(offset as u32);
+		header.put_u32(data.len() as u32);
+		body.extend(data);
+		body.resize(body.len().div_ceil(4) * 4, 0);
+		offset = 12 + 16 * tables.len() + body.len();
+	}
+
+	let mut file = header;
+	file.extend(body);
+	if let Some(pos) = head_offset {
+		let adjustment = 0xB1B0_AFBAu32.wrapping_sub(checksum(&file));
+		file[pos + 8 .. pos + 12].copy_from_slice(&adjustment.to_be_bytes());
+	}
+	writer.write_all(&file)?;
+	Ok(())
+}
+
+/// The horizontal metrics and bounding box of a glyph in font units.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct GlyphMetrics {
+	pub(crate) advance: u16,
+	pub(crate) bbox: BoundingBox
+}
+
+/// Font-wide information shared by the tables of an SFNT font. All values are in
+/// font units unless noted otherwise.
+pub(crate) struct Info<'a> {
+	pub(crate) font: &'a Font,
+	pub(crate) units_per_em: u16,
+
+	/// The number of font units per pixel of `font`.
+	pub(crate) units_per_pixel: i32,
+
+	pub(crate) ascent: i32,
+	pub(crate) descent: i32,
+	pub(crate) bold: bool,
+	pub(crate) italic: bool,
+
+	/// The metrics of all glyphs, indexed by glyph id.
+	pub(crate) metrics: Vec<GlyphMetrics>,
+
+	/// The mapping from character codes to glyph ids, ordered by character code.
+	pub(crate) cmap: Vec<(u32, u16)>
+}
+
+impl<'a> Info<'a> {
+	/// Collect the information of the primary font. Each pixel is represented by an
+	/// integer number of font units, so that outlines and metrics are exact at the
+	/// font's native size.
+	pub(crate) fn new(font: &'a Font) -> Self {
+		let ppem = font.pixels_per_em();
+		let units_per_pixel = (1024 / ppem).max(1);
+		let (ascent, descent) = font.ascent_descent();
+		let bold = font
+			.string_property("WEIGHT_NAME")
+			.is_some_and(|name| name.to_ascii_lowercase().contains("bold"));
+		let italic = matches!(font.string_property("SLANT"), Some("I" | "O"));
+		Self {
+			font,
+			units_per_em: (ppem * units_per_pixel).clamp(16, 16384) as u16,
+			units_per_pixel,
+			ascent: ascent * units_per_pixel,
+			descent: descent * units_per_pixel,
+			bold,
+			italic,
+			metrics: Vec::new(),
+			cmap: Vec::new()
+		}
+	}
+
+	/// Get the metrics of a glyph of a font with the given number of font units per
+	/// pixel.
+	pub(crate) fn glyph_metrics(glyph: &Glyph, units_per_pixel: i32) -> GlyphMetrics {
+		let bbox = glyph.bbox;
+		GlyphMetrics {
+			advance: clamp_u16(advance(glyph) * units_per_pixel),
+			bbox: BoundingBox {
+				width: bbox.width * units_per_pixel as u32,
+				height: bbox.height * units_per_pixel as u32,
+				offset_x: bbox.offset_x * units_per_pixel,
+				offset_y: bbox.offset_y * units_per_pixel
+			}
+		}
+	}
+
+	fn family(&self) -> String {
+		self.font
+			.string_property("FAMILY_NAME")
+			.unwrap_or(&self.font.name)
+			.to_owned()
+	}
+
+	fn subfamily(&self) -> &'static str {
+		match (self.bold, self.italic) {
+			(false, false) => "Regular",
+			(true, false) => "Bold",
+			(false, true) => "Italic",
+			(true, true) => "Bold Italic"
+		}
+	}
+
+	fn bounding_box(&self) -> BoundingBox {
+		self.metrics
+			.iter()
+			.fold(BoundingBox::default(), |bbox, m| bbox.union(m.bbox))
+	}
+
+	fn is_fixed_pitch(&self) -> bool {
+		let mut advances = self
+			.metrics
+			.iter()
+			.map(|m| m.advance)
+			.filter(|adv| *adv > 0);
+		let first = advances.next();
+		advances.all(|adv| Some(adv) == first)
+	}
+
+	fn pixel_property(&self, key: &str) -> Option<i32> {
+		Some(self.font.integer_property(key)? * self.units_per_pixel)
+	}
+
+	pub(crate) fn head(&self, index_to_loc_format: i16) -> Vec<u8> {
+		let bbox = self.bounding_box();
+		let mut buf = Vec::new();
+		buf.put_u32(0x0001_0000);
+		buf.put_u32(0x0001_0000);
+		buf.put_u32(0); // checkSumAdjustment
+		buf.put_u32(0x5F0F_3CF5);
+		// baseline at y=0, left sidebearing at x=0, integer scaling
+		buf.put_u16(0x000B);
+		buf.put_u16(self.units_per_em);
+		buf.put_i64(0); // created
+		buf.put_i64(0); // modified
+		buf.put_i16(clamp_i16(bbox.offset_x));
+		buf.put_i16(clamp_i16(bbox.offset_y));
+		buf.put_i16(clamp_i16(bbox.offset_x + bbox.width as i32));
+		buf.put_i16(clamp_i16(bbox.offset_y + bbox.height as i32));
+		buf.put_u16(self.bold as u16 | (self.italic as u16) << 1);
+		buf.put_u16(clamp_u16(self.font.pixels_per_em()));
+		buf.put_i16(2);
+		buf.put_i16(index_to_loc_format);
+		buf.put_i16(0);
+		buf
+	}
+
+	pub(crate) fn hhea(&self) -> Vec<u8> {
+		let inked = || self.metrics.iter().filter(|m| !m.bbox.is_empty());
+		let mut buf = Vec::new();
+		buf.put_u32(0x0001_0000);
+		buf.put_i16(clamp_i16(self.ascent));
+		buf.put_i16(clamp_i16(-self.descent));
+		buf.put_i16(0);
+		buf.put_u16(self.metrics.iter().map(|m| m.advance).max().unwrap_or(0));
+		buf.put_i16(clamp_i16(
+			inked().map(|m| m.bbox.offset_x).min().unwrap_or(0)
+		));
+		buf.put_i16(clamp_i16(
+			inked()
+				.map(|m| i32::from(m.advance) - m.bbox.offset_x - m.bbox.width as i32)
+				.min()
+				.unwrap_or(0)
+		));
+		buf.put_i16(clamp_i16(
+			inked()
+				.map(|m| m.bbox.offset_x + m.bbox.width as i32)
+				.max()
+				.unwrap_or(0)
+		));
+		buf.put_i16(1); // caretSlopeRise
+		buf.put_i16(0); // caretSlopeRun
+		buf.put_i16(0); // caretOffset
+		buf.extend([0; 8]);
+		buf.put_i16(0); // metricDataFormat
+		buf.put_u16(self.metrics.len() as u16);
+		buf
+	}
+
+	pub(crate) fn hmtx(&self) -> Vec<u8> {
+		let mut buf = Vec::new();
+		for m in &self.metrics {
+			buf.put_u16(m.advance);
+			buf.put_i16(clamp_i16(m.bbox.offset_x));
+		}
+		buf
+	}
+
+	/// Get the `maxp` table version 0.5, used by fonts without TrueType outlines.
+	pub(crate) fn maxp(&self) -> Vec<u8> {
+		let mut buf = Vec::new();
+		buf.put_u32(0x0000_5000);
+		buf.put_u16(self.metrics.len() as u16);
+		buf
+	}
+
+	/// Get the `maxp` table version 1.0, used by fonts with TrueType outlines that
+	/// have no instructions or composite glyphs.
+	pub(crate) fn maxp_truetype(&self, max_points: u16, max_contours: u16) -> Vec<u8> {
+		let mut buf = Vec::new();
+		buf.put_u32(0x0001_0000);
+		buf.put_u16(self.metrics.len() as u16);
+		buf.put_u16(max_points);
+		buf.put_u16(max_contours);
+		buf.put_u16(0); // maxCompositePoints
+		buf.put_u16(0); // maxCompositeContours
+		buf.put_u16(2); // maxZones
+		buf.extend([0; 16]);
+		buf
+	}
+
+	pub(crate) fn cmap(&self) -> Vec<u8> {
+		// consecutive character codes mapped to consecutive glyph ids
+		let mut runs: Vec<(u32, u32, u16)> = Vec::new();
+		for &(code, gid) in &self.cmap {
+			let extend = runs.last().is_some_and(|(start, end, start_gid)| {
+				code == end + 1 && u32::from(gid) == u32::from(*start_gid) + code - start
+			});
+			match extend {
+				true => runs.last_mut().unwrap().1 = code,
+				false => runs.push((code, code, gid))
+			}
+		}
+
+		// format 4 for the basic multilingual plane, unless it has too many segments
+		// for the 16 bit length of the subtable, leaving 0xFFFF for the final segment
+		let mut segments: Vec<(u16, u16, u16)> = runs
+			.iter()
+			.filter(|(start, ..)| *start < 0xFFFF)
+			.map(|&(start, end, gid)| {
+				let end = end.min(0xFFFE) as u16;
+				(start as u16, end, gid.wrapping_sub(start as u16))
+			})
+			.collect();
+		segments.push((0xFFFF, 0xFFFF, 1));
+		let format4 = (segments.len() <= MAX_FORMAT4_SEGMENTS).then(|| {
+			let seg_count = segments.len() as u16;
+			let entry_selector = 15 - seg_count.leading_zeros() as u16;
+			let search_range = 2 << entry_selector;
+			let mut buf = Vec::new();
+			buf.put_u16(4);
+			buf.put_u16(16 + 8 * seg_count);
+			buf.put_u16(0);
+			buf.put_u16(seg_count * 2);
+			buf.put_u16(search_range);
+			buf.put_u16(entry_selector);
+			buf.put_u16(seg_count * 2 - search_range);
+			for (_, end, _) in &segments {
+				buf.put_u16(*end);
+			}
+			buf.put_u16(0);
+			for (start, ..) in &segments {
+				buf.put_u16(*start);
+			}
+			for (.., delta) in &segments {
+				buf.put_u16(*delta);
+			}
+			for _ in &segments {
+				buf.put_u16(0);
+			}
+			buf
+		});
+
+		// format 12 if there are characters outside of the basic multilingual plane or
+		// at 0xFFFF, or format 4 does not fit
+		let needs_format12 = runs.last().is_some_and(|(_, end, _)| *end >= 0xFFFF);
+		let format12 = (needs_format12 || format4.is_none()).then(|| {
+			let mut buf = Vec::new();
+			buf.put_u16(12);
+			buf.put_u16(0);
+			buf.put_u32(16 + 12 * runs.len() as u32);
+			buf.put_u32(0);
+			buf.put_u32(runs.len() as u32);
+			for &(start, end, gid) in &runs {
+				buf.put_u32(start);
+				buf.put_u32(end);
+				buf.put_u32(gid.into());
+			}
+			buf
+		});
+
+		let mut subtables: Vec<(u16, u16, Vec<u8>)> = Vec::new();
+		subtables.extend(format4.map(|table| (3, 1, table)));
+		subtables.extend(format12.map(|table| (3, 10, table)));
+		let mut buf = Vec::new();
+		buf.put_u16(0);
+		buf.put_u16(subtables.len() as u16);
+		let mut offset = 4 + 8 * subtables.len();
+		for (platform, encoding, table) in &subtables {
+			buf.put_u16(*platform);
+			buf.put_u16(*encoding);
+			buf.put_u32(offset as u32);
+			offset += table.len();
+		}
+		for (.., table) in subtables {
+			buf.extend(table);
+		}
+		buf
+	}
+
+	pub(crate) fn name(&self) -> Vec<u8> {
+		let family = self.family();
+		let subfamily = self.subfamily();
+		let full_name = match subfamily {
+			"Regular" => family.clone(),
+			_ => format!("{family} {subfamily}")
+		};
+		let ps_name: String = format!("{family}-{subfamily}")
+			.chars()
+			.filter(|ch| ch.is_ascii_graphic() && !"[](){}<>/%".contains(*ch))
+			.collect();
+		let mut records = Vec::new();
+		if let Some(copyright) = self.font.string_property("COPYRIGHT") {
+			records.push((0u16, copyright.to_owned()));
+		}
+		records.push((1, family));
+		records.push((2, subfamily.to_owned()));
+		records.push((3, self.font.name.clone()));
+		records.push((4, full_name));
+		records.push((5, "Version 1.0".to_owned()));
+		records.push((6, ps_name));
+
+		let mut strings = Vec::new();
+		let mut buf = Vec::new();
+		buf.put_u16(0);
+		buf.put_u16(records.len() as u16);
+		buf.put_u16(6 + 12 * records.len() as u16);
+		for (name_id, value) in records {
+			let offset = strings.len();
+			for unit in value.encode_utf16() {
+				strings.put_u16(unit);
+			}
+			buf.put_u16(3); // Windows
+			buf.put_u16(1); // Unicode BMP
+			buf.put_u16(0x409); // English (United States)
+			buf.put_u16(name_id);
+			buf.put_u16((strings.len() - offset) as u16);
+			buf.put_u16(offset as u16);
+		}
+		buf.extend(strings);
+		buf
+	}
+
+	pub(crate) fn os2(&self) -> Vec<u8> {
+		let upem = i32::from(self.units_per_em);
+		let bbox = self.bounding_box();
+		let advances: Vec<i32> = self
+			.metrics
+			.iter()
+			.map(|m| i32::from(m.advance))
+			.filter(|adv| *adv > 0)
+			.collect();
+		let avg_width = match advances.len() {
+			0 => 0,
+			len => advances.iter().sum::<i32>() / len as i32
+		};
+		let weight: u16 = match self.bold {
+			true => 700,
+			false => 400
+		};
+		let mut selection = 0u16;
+		if self.italic {
+			selection |= 0x01;
+		}
+		if self.bold {
+			selection |= 0x20;
+		}
+		if selection == 0 {
+			selection = 0x40;
+		}
+		let first_char = self.cmap.first().map(|(code, _)| *code).unwrap_or(0);
+		let last_char = self.cmap.last().map(|(code, _)| *code).unwrap_or(0);
+
+		let mut buf = Vec::new();
+		buf.put_u16(4);
+		buf.put_i16(clamp_i16(avg_width));
+		buf.put_u16(weight);
+		buf.put_u16(5); // usWidthClass: medium
+		buf.put_u16(0); // fsType: installable embedding
+		for (x_size, y_size, x_offset, y_offset) in [
+			(upem * 65 / 100, upem * 70 / 100, 0, upem * 14 / 100),
+			(upem * 65 / 100, upem * 70 / 100, 0, upem * 48 / 100)
+		] {
+			buf.put_i16(clamp_i16(x_size));
+			buf.put_i16(clamp_i16(y_size));
+			buf.put_i16(clamp_i16(x_offset));
+			buf.put_i16(clamp_i16(y_offset));
+		}
+		buf.put_i16(clamp_i16(self.units_per_pixel));
+		buf.put_i16(clamp_i16(
+			self.pixel_property("X_HEIGHT").unwrap_or(self.ascent) / 2
+		));
+		buf.put_i16(0); // sFamilyClass
+		buf.extend([0; 10]); // panose
+		buf.extend([0; 16]); // ulUnicodeRange
+		buf.extend(*b"    ");
+		buf.put_u16(selection);
+		buf.put_u16(first_char.min(0xFFFF) as u16);
+		buf.put_u16(last_char.min(0xFFFF) as u16);
+		buf.put_i16(clamp_i16(self.ascent));
+		buf.put_i16(clamp_i16(-self.descent));
+		buf.put_i16(0); // sTypoLineGap
+		buf.put_u16(clamp_u16(
+			self.ascent.max(bbox.offset_y + bbox.height as i32)
+		));
+		buf.put_u16(clamp_u16(self.descent.max(-bbox.offset_y)));
+		buf.put_u32(1); // ulCodePageRange1: Latin 1
+		buf.put_u32(0);
+		buf.put_i16(clamp_i16(self.pixel_property("X_HEIGHT").unwrap_or(0)));
+		buf.put_i16(clamp_i16(self.pixel_property("CAP_HEIGHT").unwrap_or(0)));
+		buf.put_u16(0); // usDefaultChar
+		buf.put_u16(0x20); // usBreakChar
+		buf.put_u16(1); // usMaxContext
+		buf
+	}
+
+	/// Get the `post` table version 3, which contains no glyph names.
+	pub(crate) fn post(&self) -> Vec<u8> {
+		let mut buf = Vec::new();
+		buf.put_u32(0x0003_0000);
+		buf.put_u32(0); // italicAngle
+		buf.put_i16(clamp_i16(
+			-self
+				.pixel_property("UNDERLINE_POSITION")
+				.unwrap_or(self.units_per_pixel)
+		));
+		buf.put_i16(clamp_i16(
+			self.pixel_property("UNDERLINE_THICKNESS")
+				.unwrap_or(self.units_per_pixel)
+		));
+		buf.put_u32(self.is_fixed_pitch() as u32);
+		buf.extend([0; 16]);
+		buf
+	}
+}
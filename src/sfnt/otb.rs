@@ -0,0 +1,204 @@
+//! Bitmap-only OpenType fonts with embedded bitmap strikes in the `EBDT` and `EBLC`
+//! tables.
+
+use super::*;
+use crate::{Font, Glyph};
+use std::collections::BTreeSet;
+
+/// The glyphs of a single strike, indexed by glyph id.
+struct Strike<'a> {
+	font: &'a Font,
+	glyphs: Vec<Option<&'a Glyph>>
+}
+
+/// The `SbitLineMetrics` record of a strike.
+fn line_metrics(buf: &mut Vec<u8>, strike: &Strike<'_>) {
+	let (ascent, descent) = strike.font.ascent_descent();
+	let glyphs = || strike.glyphs.iter().flatten();
+	let inked = || glyphs().filter(|g| !g.bbox.is_empty());
+	let to_i8 = |v: i32| v.clamp(i8::MIN.into(), i8::MAX.into()) as i8;
+	buf.put_i8(to_i8(ascent));
+	buf.put_i8(to_i8(-descent));
+	buf.put_u8(
+		glyphs()
+			.map(|g| advance(g))
+			.max()
+			.unwrap_or(0)
+			.clamp(0, 0xFF) as u8
+	);
+	buf.put_i8(1); // caretSlopeNumerator
+	buf.put_i8(0); // caretSlopeDenominator
+	buf.put_i8(0); // caretOffset
+	buf.put_i8(to_i8(inked().map(|g| g.bbox.offset_x).min().unwrap_or(0)));
+	buf.put_i8(to_i8(
+		inked()
+			.map(|g| advance(g) - g.bbox.offset_x - g.bbox.width as i32)
+			.min()
+			.unwrap_or(0)
+	));
+	buf.put_i8(to_i8(
+		inked()
+			.map(|g| g.bbox.offset_y + g.bbox.height as i32)
+			.max()
+			.unwrap_or(0)
+	));
+	buf.put_i8(to_i8(inked().map(|g| g.bbox.offset_y).min().unwrap_or(0)));
+	buf.put_i8(0);
+	buf.put_i8(0);
+}
+
+/// Append a glyph to the `EBDT` table using image format 1, that is small metrics
+/// followed by byte-aligned bitmap data.
+fn glyph_image(ebdt: &mut Vec<u8>, glyph: &Glyph) -> Result<(), SfntError> {
+	let bbox = glyph.bbox;
+	let out_of_range = || SfntError::MetricsOutOfRange(glyph.encoding);
+	let height = u8::try_from(bbox.height).map_err(|_| out_of_range())?;
+	let width = u8::try_from(bbox.width).map_err(|_| out_of_range())?;
+	let bearing_x = i8::try_from(bbox.offset_x).map_err(|_| out_of_range())?;
+	let bearing_y =
+		i8::try_from(bbox.offset_y + bbox.height as i32).map_err(|_| out_of_range())?;
+	let advance = u8::try_from(advance(glyph)).map_err(|_| out_of_range())?;
+	ebdt.extend([height, width, bearing_x as u8, bearing_y as u8, advance]);
+
+	let bitmap = glyph.bitmap();
+	let stride = bitmap.width().div_ceil(8);
+	for y in 0 .. bitmap.height() {
+		let mut row = vec![0u8; stride];
+		for x in 0 .. bitmap.width() {
+			if bitmap.get(x, y).unwrap_or(false) {
+				row[x / 8] |= 0x80 >> (x % 8);
+			}
+		}
+		ebdt.extend(row);
+	}
+	Ok(())
+}
+
+impl Font {
+	/// Write this font as a bitmap-only OpenType font (OTB) with a single strike.
+	pub fn write_otb<W: Write>(&self, writer: W) -> Result<(), SfntError> {
+		Self::write_otb_strikes(&[self], writer)
+	}
+
+	/// Write a bitmap-only OpenType font (OTB) with one strike per font, e.g. the
+	/// same typeface at different pixel sizes.
+	///
+	/// The font contains the union of the glyphs of all strikes, and the encodings
+	/// are assumed to be Unicode code points. The horizontal metrics, as well as the
+	/// `OS/2` and `name` tables, are derived from the first strike and its XLFD
+	/// properties.
+	pub fn write_otb_strikes<W: Write>(
+		strikes: &[&Font],
+		writer: W
+	) -> Result<(), SfntError> {
+		let primary = *strikes.first().ok_or(SfntError::NoStrikes)?;
+		let encodings: BTreeSet<u32> = strikes
+			.iter()
+			.flat_map(|font| font.glyphs.iter().map(|gw| gw.0.encoding))
+			.collect();
+		if encodings.len() >= 0xFFFF {
+			return Err(SfntError::TooManyGlyphs(encodings.len() + 1));
+		}
+
+		// glyph id 0 is the .notdef glyph, which uses the default glyph if there is one
+		let strikes: Vec<Strike<'_>> = strikes
+			.iter()
+			.map(|font| Strike {
+				font,
+				glyphs: [font.default_glyph()]
+					.into_iter()
+					.chain(encodings.iter().map(|enc| font.glyph(*enc)))
+					.collect()
+			})
+			.collect();
+
+		let mut info = Info::new(primary);
+		let upem = i32::from(info.units_per_em);
+		info.metrics = (0 .. encodings.len() + 1)
+			.map(|gid| {
+				strikes
+					.iter()
+					.find_map(|strike| {
+						let glyph = strike.glyphs[gid]?;
+						let units_per_pixel = (upem / strike.font.pixels_per_em()).max(1);
+						Some(Info::glyph_metrics(glyph, units_per_pixel))
+					})
+					.unwrap_or_default()
+			})
+			.collect();
+		info.cmap = encodings
+			.iter()
+			.zip(1 ..)
+			.map(|(enc, gid)| (*enc, gid))
+			.collect();
+
+		let mut ebdt = Vec::new();
+		ebdt.put_u32(0x0002_0000);
+		let mut eblc = Vec::new();
+		eblc.put_u32(0x0002_0000);
+		eblc.put_u32(strikes.len() as u32);
+		let mut index_tables = Vec::new();
+		for strike in &strikes {
+			let present = || {
+				strike
+					.glyphs
+					.iter()
+					.enumerate()
+					.filter(|(_, glyph)| glyph.is_some())
+					.map(|(gid, _)| gid as u16)
+			};
+			let first_gid = present().min().unwrap_or(0);
+			let last_gid = present().max().unwrap_or(0);
+
+			let image_data_offset = ebdt.len();
+			let mut offsets = Vec::new();
+			for glyph in &strike.glyphs[first_gid.into() ..= last_gid.into()] {
+				offsets.push((ebdt.len() - image_data_offset) as u32);
+				if let Some(glyph) = glyph {
+					glyph_image(&mut ebdt, glyph)?;
+				}
+			}
+			offsets.push((ebdt.len() - image_data_offset) as u32);
+
+			// the index subtable array with a single entry, followed by the subtable
+			let array_offset = 8 + 48 * strikes.len() + index_tables.len();
+			let mut array = Vec::new();
+			array.put_u16(first_gid);
+			array.put_u16(last_gid);
+			array.put_u32(8);
+			array.put_u16(1); // indexFormat
+			array.put_u16(1); // imageFormat
+			array.put_u32(image_data_offset as u32);
+			for offset in offsets {
+				array.put_u32(offset);
+			}
+
+			let ppem = strike.font.pixels_per_em().clamp(0, 0xFF) as u8;
+			eblc.put_u32(array_offset as u32);
+			eblc.put_u32(array.len() as u32);
+			eblc.put_u32(1); // numberOfIndexSubTables
+			eblc.put_u32(0); // colorRef
+			line_metrics(&mut eblc, strike);
+			line_metrics(&mut eblc, strike);
+			eblc.put_u16(first_gid);
+			eblc.put_u16(last_gid);
+			eblc.extend([ppem, ppem, 1]);
+			eblc.put_i8(1); // horizontal metrics
+			index_tables.extend(array);
+		}
+		eblc.extend(index_tables);
+
+		write_sfnt(writer, vec![
+			(b"EBDT", ebdt),
+			(b"EBLC", eblc),
+			(b"OS/2", info.os2()),
+			(b"cmap", info.cmap()),
+			(b"head", info.head(0)),
+			(b"hhea", info.hhea()),
+			(b"hmtx", info.hmtx()),
+			(b"maxp", info.maxp()),
+			(b"name", info.name()),
+			(b"post", info.post()),
+		])
+	}
+}
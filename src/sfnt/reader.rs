@@ -0,0 +1,686 @@
+//! Import of embedded bitmap strikes from the `EBDT` and `EBLC` tables.
+
+use super::*;
+use crate::{compress::decompress, font::GlyphWrapper, Size, Value};
+use bit_vec::BitVec;
+use log::warn;
+use std::{
+	cell::{Cell, RefCell},
+	collections::{BTreeMap, BTreeSet, HashMap},
+	io::{BufReader, Read},
+	rc::Rc
+};
+
+/// The maximum nesting depth of composite glyph images.
+const MAX_COMPONENT_DEPTH: u32 = 8;
+
+/// The maximum number of components painted into the composite glyphs of a strike.
+const MAX_STRIKE_COMPONENTS: usize = 1 << 16;
+
+/// A big endian cursor over the data of an SFNT font or one of its tables.
+#[derive(Clone, Copy)]
+struct Data<'a>(&'a [u8]);
+
+impl<'a> Data<'a> {
+	fn bytes(&self, offset: usize, len: usize) -> Result<&'a [u8], SfntError> {
+		self.0
+			.get(offset .. offset.saturating_add(len))
+			.ok_or(SfntError::UnexpectedEof)
+	}
+
+	fn sub(&self, offset: usize) -> Result<Data<'a>, SfntError> {
+		self.0
+			.get(offset ..)
+			.map(Data)
+			.ok_or(SfntError::UnexpectedEof)
+	}
+
+	fn u8(&self, offset: usize) -> Result<u8, SfntError> {
+		Ok(self.bytes(offset, 1)?[0])
+	}
+
+	fn i8(&self, offset: usize) -> Result<i8, SfntError> {
+		Ok(self.u8(offset)? as i8)
+	}
+
+	fn u16(&self, offset: usize) -> Result<u16, SfntError> {
+		let bytes = self.bytes(offset, 2)?;
+		Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+	}
+
+	fn u32(&self, offset: usize) -> Result<u32, SfntError> {
+		let bytes = self.bytes(offset, 4)?;
+		Ok(u32::from_be_bytes(bytes.try_into().unwrap()))
+	}
+}
+
+/// The tables of an SFNT font.
+struct Tables<'a>(HashMap<[u8; 4], Data<'a>>);
+
+impl<'a> Tables<'a> {
+	fn parse(data: &'a [u8]) -> Result<Self, SfntError> {
+		let data = Data(data);
+		match data.bytes(0, 4)? {
+			[0x00, 0x01, 0x00, 0x00] | b"true" | b"OTTO" => {},
+			_ => return Err(SfntError::InvalidMagic)
+		}
+		let num_tables = usize::from(data.u16(4)?);
+		let mut tables = HashMap::new();
+		for i in 0 .. num_tables {
+			let record = 12 + 16 * i;
+			let tag: [u8; 4] = data.bytes(record, 4)?.try_into().unwrap();
+			let offset = data.u32(record + 8)? as usize;
+			let len = data.u32(record + 12)? as usize;
+			tables.insert(tag, Data(data.bytes(offset, len)?));
+		}
+		Ok(Self(tables))
+	}
+
+	fn get(&self, tag: &[u8; 4]) -> Option<Data<'a>> {
+		self.0.get(tag).copied()
+	}
+
+	fn require(&self, tag: &[u8; 4]) -> Result<Data<'a>, SfntError> {
+		self.get(tag).ok_or_else(|| {
+			SfntError::MissingTable(String::from_utf8_lossy(tag).into_owned())
+		})
+	}
+}
+
+/// Parse the `cmap` table into a mapping from glyph ids to character codes. The
+/// Unicode subtable with the largest coverage is used.
+fn read_cmap(cmap: Data<'_>) -> Result<BTreeMap<u16, Vec<u32>>, SfntError> {
+	let num_subtables = usize::from(cmap.u16(2)?);
+	let mut best: Option<(u8, Data<'_>)> = None;
+	for i in 0 .. num_subtables {
+		let record = 4 + 8 * i;
+		let platform = cmap.u16(record)?;
+		let encoding = cmap.u16(record + 2)?;
+		let subtable = cmap.sub(cmap.u32(record + 4)? as usize)?;
+		let format = subtable.u16(0)?;
+		let rank = match (platform, encoding, format) {
+			(3, 10, 12) | (0, 4 | 6, 12) => 3,
+			(3, 1, 4) | (0, 0 ..= 3, 4) => 2,
+			(3, 1 | 10, _) | (0, ..) => 1,
+			_ => continue
+		};
+		if !matches!(best, Some((best_rank, _)) if best_rank >= rank) {
+			best = Some((rank, subtable));
+		}
+	}
+
+	let mut mapping: BTreeMap<u16, Vec<u32>> = BTreeMap::new();
+	let Some((_, subtable)) = best else {
+		return Ok(mapping);
+	};
+	let mut map = |code: u32, gid: u16| {
+		if gid != 0 {
+			mapping.entry(gid).or_default().push(code);
+		}
+	};
+	match subtable.u16(0)? {
+		0 => {
+			for code in 0 .. 256 {
+				map(code, subtable.u8(6 + code as usize)?.into());
+			}
+		},
+		4 => {
+			let seg_count = usize::from(subtable.u16(6)? / 2);
+			let ends = 14;
+			let starts = ends + 2 * seg_count + 2;
+			let deltas = starts + 2 * seg_count;
+			let range_offsets = deltas + 2 * seg_count;
+			for i in 0 .. seg_count {
+				let end = subtable.u16(ends + 2 * i)?;
+				let start = subtable.u16(starts + 2 * i)?;
+				let delta = subtable.u16(deltas + 2 * i)?;
+				let range_offset = usize::from(subtable.u16(range_offsets + 2 * i)?);
+				for code in start ..= end {
+					if code == 0xFFFF {
+						break;
+					}
+					let gid = match range_offset {
+						0 => code.wrapping_add(delta),
+						_ => {
+							let pos =
+								range_offsets
+									+ 2 * i + range_offset + 2 * usize::from(code - start);
+							match subtable.u16(pos)? {
+								0 => 0,
+								gid => gid.wrapping_add(delta)
+							}
+						}
+					};
+					map(code.into(), gid);
+				}
+			}
+		},
+		6 => {
+			let first = u32::from(subtable.u16(6)?);
+			let count = subtable.u16(8)?;
+			for i in 0 .. count {
+				map(first + u32::from(i), subtable.u16(10 + 2 * usize::from(i))?);
+			}
+		},
+		12 => {
+			let num_groups = subtable.u32(12)? as usize;
+			for i in 0 .. num_groups {
+				let group = 16 + 12 * i;
+				let start = subtable.u32(group)?;
+				let end = subtable.u32(group + 4)?.min(char::MAX as u32);
+				let start_gid = subtable.u32(group + 8)?;
+				if start_gid > 0xFFFF {
+					continue;
+				}
+				// only the codes mapping to 16 bit glyph ids are of interest
+				let end = end.min(start.saturating_add(0xFFFF - start_gid));
+				for code in start ..= end {
+					map(code, (start_gid + (code - start)) as u16);
+				}
+			}
+		},
+		format => return Err(SfntError::UnsupportedFormat("cmap", format))
+	}
+	Ok(mapping)
+}
+
+/// Get a string from the `name` table, preferring English Windows names.
+fn read_name(name: Data<'_>, name_id: u16) -> Result<Option<String>, SfntError> {
+	let count = usize::from(name.u16(2)?);
+	let strings = usize::from(name.u16(4)?);
+	let mut best: Option<(u8, String)> = None;
+	for i in 0 .. count {
+		let record = 6 + 12 * i;
+		if name.u16(record + 6)? != name_id {
+			continue;
+		}
+		let platform = name.u16(record)?;
+		let encoding = name.u16(record + 2)?;
+		let language = name.u16(record + 4)?;
+		let len = usize::from(name.u16(record + 8)?);
+		let bytes = name.bytes(strings + usize::from(name.u16(record + 10)?), len)?;
+		let (rank, value) = match (platform, encoding) {
+			(0, _) | (3, 0 | 1 | 10) => {
+				let units: Vec<u16> = bytes
+					.chunks_exact(2)
+					.map(|unit| u16::from_be_bytes([unit[0], unit[1]]))
+					.collect();
+				let rank = match (platform, language) {
+					(3, 0x409) => 3,
+					_ => 2
+				};
+				(rank, String::from_utf16_lossy(&units))
+			},
+			(1, 0) if bytes.is_ascii() => {
+				(1, String::from_utf8_lossy(bytes).into_owned())
+			},
+			_ => continue
+		};
+		if !matches!(&best, Some((best_rank, _)) if *best_rank >= rank) {
+			best = Some((rank, value));
+		}
+	}
+	Ok(best.map(|(_, value)| value))
+}
+
+/// The metrics of a glyph image in pixels.
+#[derive(Clone, Copy)]
+struct ImageMetrics {
+	height: u8,
+	width: u8,
+	bearing_x: i8,
+	bearing_y: i8,
+	advance: u8
+}
+
+impl ImageMetrics {
+	/// Read `SmallGlyphMetrics`, or the horizontal part of `BigGlyphMetrics`.
+	fn read(data: Data<'_>, offset: usize) -> Result<Self, SfntError> {
+		Ok(Self {
+			height: data.u8(offset)?,
+			width: data.u8(offset + 1)?,
+			bearing_x: data.i8(offset + 2)?,
+			bearing_y: data.i8(offset + 3)?,
+			advance: data.u8(offset + 4)?
+		})
+	}
+}
+
+/// The location of a glyph image in the `EBDT` table.
+struct Location {
+	image_format: u16,
+	offset: usize,
+	len: usize,
+
+	/// The metrics of all glyphs in an index subtable with constant metrics.
+	metrics: Option<ImageMetrics>
+}
+
+/// A decoded glyph image.
+struct Image {
+	metrics: ImageMetrics,
+
+	/// The rows of the bitmap, each `width` bits long.
+	rows: Vec<BitVec>
+}
+
+/// Parse the index subtables of a strike into the locations of its glyph images.
+fn read_index(
+	eblc: Data<'_>,
+	size: Data<'_>
+) -> Result<BTreeMap<u16, Location>, SfntError> {
+	let array = eblc.sub(size.u32(0)? as usize)?;
+	let count = size.u32(8)? as usize;
+	let mut locations = BTreeMap::new();
+	for i in 0 .. count {
+		let first = array.u16(8 * i)?;
+		let last = array.u16(8 * i + 2)?;
+		let subtable = array.sub(array.u32(8 * i + 4)? as usize)?;
+		let index_format = subtable.u16(0)?;
+		let image_format = subtable.u16(2)?;
+		let image_data_offset = subtable.u32(4)? as usize;
+		let mut insert = |gid: u16, start: usize, end: usize, metrics| {
+			if end > start {
+				locations.insert(gid, Location {
+					image_format,
+					offset: image_data_offset + start,
+					len: end - start,
+					metrics
+				});
+			}
+		};
+		match index_format {
+			1 | 3 => {
+				let offset = |i: usize| -> Result<usize, SfntError> {
+					Ok(match index_format {
+						1 => subtable.u32(8 + 4 * i)? as usize,
+						_ => subtable.u16(8 + 2 * i)?.into()
+					})
+				};
+				for (i, gid) in (first ..= last).enumerate() {
+					insert(gid, offset(i)?, offset(i + 1)?, None);
+				}
+			},
+			2 => {
+				let image_size = subtable.u32(8)? as usize;
+				let metrics = ImageMetrics::read(subtable, 12)?;
+				for (i, gid) in (first ..= last).enumerate() {
+					insert(gid, i * image_size, (i + 1) * image_size, Some(metrics));
+				}
+			},
+			4 => {
+				let num_glyphs = subtable.u32(8)? as usize;
+				for i in 0 .. num_glyphs {
+					let pair = 12 + 4 * i;
+					let gid = subtable.u16(pair)?;
+					let start = subtable.u16(pair + 2)?.into();
+					let end = subtable.u16(pair + 6)?.into();
+					insert(gid, start, end, None);
+				}
+			},
+			5 => {
+				let image_size = subtable.u32(8)? as usize;
+				let metrics = ImageMetrics::read(subtable, 12)?;
+				let num_glyphs = subtable.u32(20)? as usize;
+				for i in 0 .. num_glyphs {
+					let gid = subtable.u16(24 + 2 * i)?;
+					insert(gid, i * image_size, (i + 1) * image_size, Some(metrics));
+				}
+			},
+			format => return Err(SfntError::UnsupportedFormat("EBLC index", format))
+		}
+	}
+	Ok(locations)
+}
+
+/// The glyph images of a single strike.
+struct StrikeImages<'a> {
+	ebdt: Data<'a>,
+	locations: BTreeMap<u16, Location>,
+	bit_depth: u8,
+
+	/// The images decoded so far, so that shared components are only decoded once.
+	decoded: RefCell<HashMap<u16, Option<Rc<Image>>>>,
+
+	/// The number of components painted so far.
+	painted: Cell<usize>
+}
+
+impl StrikeImages<'_> {
+	/// Read a bitmap of the given size, with rows either padded to bytes or packed.
+	/// Pixels with more than one bit are set if they are at least half opaque.
+	fn bitmap(
+		&self,
+		data: Data<'_>,
+		offset: usize,
+		metrics: ImageMetrics,
+		byte_aligned: bool
+	) -> Result<Vec<BitVec>, SfntError> {
+		let depth = usize::from(self.bit_depth);
+		let (width, height) = (usize::from(metrics.width), usize::from(metrics.height));
+		let stride = match byte_aligned {
+			true => (width * depth).div_ceil(8) * 8,
+			false => width * depth
+		};
+		let bytes = data.bytes(offset, (stride * height).div_ceil(8))?;
+		Ok((0 .. height)
+			.map(|y| {
+				(0 .. width)
+					.map(|x| {
+						// the most significant bit of a pixel decides if it is set
+						let bit = y * stride + x * depth;
+						bytes[bit / 8] & (0x80 >> (bit % 8)) != 0
+					})
+					.collect()
+			})
+			.collect())
+	}
+
+	/// Get the image of a glyph, decoding it if it wasn't decoded before.
+	fn image(&self, gid: u16, nesting: u32) -> Result<Option<Rc<Image>>, SfntError> {
+		if let Some(image) = self.decoded.borrow().get(&gid) {
+			return Ok(image.clone());
+		}
+		let image = self.decode(gid, nesting)?.map(Rc::new);
+		self.decoded.borrow_mut().insert(gid, image.clone());
+		Ok(image)
+	}
+
+	/// Decode the image of a glyph, painting the components of composite glyphs.
+	fn decode(&self, gid: u16, nesting: u32) -> Result<Option<Image>, SfntError> {
+		let Some(location) = self.locations.get(&gid) else {
+			return Ok(None);
+		};
+		let data = Data(self.ebdt.bytes(location.offset, location.len)?);
+		let constant = || location.metrics.ok_or(SfntError::UnexpectedEof);
+		let (metrics, rows) = match location.image_format {
+			1 | 2 => {
+				let metrics = ImageMetrics::read(data, 0)?;
+				let byte_aligned = location.image_format == 1;
+				(metrics, self.bitmap(data, 5, metrics, byte_aligned)?)
+			},
+			5 => (constant()?, self.bitmap(data, 0, constant()?, false)?),
+			6 | 7 => {
+				let metrics = ImageMetrics::read(data, 0)?;
+				let byte_aligned = location.image_format == 6;
+				(metrics, self.bitmap(data, 8, metrics, byte_aligned)?)
+			},
+			8 | 9 if nesting >= MAX_COMPONENT_DEPTH => {
+				warn!("Skipping glyph {gid} with too deeply nested components");
+				return Ok(None);
+			},
+			8 | 9 => {
+				// small metrics and a pad byte, or big metrics
+				let metrics = ImageMetrics::read(data, 0)?;
+				let components = match location.image_format {
+					8 => 6,
+					_ => 8
+				};
+				let (width, height) =
+					(i32::from(metrics.width), i32::from(metrics.height));
+				let mut rows = vec![
+					BitVec::from_elem(metrics.width.into(), false);
+					metrics.height.into()
+				];
+				for i in 0 .. usize::from(data.u16(components)?) {
+					if self.painted.get() >= MAX_STRIKE_COMPONENTS {
+						warn!(
+							"Skipping components of glyph {gid} beyond the strike limit"
+						);
+						break;
+					}
+					self.painted.set(self.painted.get() + 1);
+					let component = components + 2 + 4 * i;
+					let x_offset = i32::from(data.i8(component + 2)?);
+					let y_offset = i32::from(data.i8(component + 3)?);
+					let Some(image) = self.image(data.u16(component)?, nesting + 1)?
+					else {
+						continue;
+					};
+					for (y, row) in image.rows.iter().enumerate() {
+						for (x, bit) in row.iter().enumerate() {
+							let (px, py) = (x_offset + x as i32, y_offset + y as i32);
+							if bit
+								&& (0 .. width).contains(&px)
+								&& (0 .. height).contains(&py)
+							{
+								rows[py as usize].set(px as usize, true);
+							}
+						}
+					}
+				}
+				(metrics, rows)
+			},
+			format => return Err(SfntError::UnsupportedFormat("EBDT image", format))
+		};
+		Ok(Some(Image { metrics, rows }))
+	}
+}
+
+/// Convert an image into the bitmap of a glyph, with rows padded to whole bytes.
+fn glyph_bitmap(rows: Vec<BitVec>) -> Vec<BitVec> {
+	rows.into_iter()
+		.map(|mut row| {
+			row.grow(row.len().div_ceil(8) * 8 - row.len(), false);
+			row
+		})
+		.collect()
+}
+
+/// Font-wide information used by all strikes.
+struct FontInfo {
+	family: String,
+	copyright: Option<String>,
+	bold: bool,
+	italic: bool,
+	fixed_pitch: bool,
+	units_per_em: u16,
+
+	/// The advance width of each glyph in font units.
+	advances: Vec<u16>,
+
+	/// The character codes mapped to each glyph.
+	cmap: BTreeMap<u16, Vec<u32>>
+}
+
+impl FontInfo {
+	fn read(tables: &Tables<'_>) -> Result<Self, SfntError> {
+		let head = tables.require(b"head")?;
+		let mac_style = head.u16(44)?;
+		let units_per_em = head.u16(18)?;
+
+		let (mut bold, mut italic) = (mac_style & 1 != 0, mac_style & 2 != 0);
+		if let Some(os2) = tables.get(b"OS/2") {
+			let selection = os2.u16(62)?;
+			bold |= os2.u16(4)? >= 600 || selection & 0x20 != 0;
+			italic |= selection & 0x01 != 0;
+		}
+		let fixed_pitch = match tables.get(b"post") {
+			Some(post) => post.u32(12)? != 0,
+			None => false
+		};
+
+		let (family, copyright) = match tables.get(b"name") {
+			Some(name) => (
+				match read_name(name, 16)? {
+					Some(family) => Some(family),
+					None => read_name(name, 1)?
+				},
+				read_name(name, 0)?
+			),
+			None => (None, None)
+		};
+
+		let mut advances = Vec::new();
+		if let (Some(hhea), Some(hmtx)) = (tables.get(b"hhea"), tables.get(b"hmtx")) {
+			let num_metrics = usize::from(hhea.u16(34)?);
+			for i in 0 .. num_metrics {
+				advances.push(hmtx.u16(4 * i)?);
+			}
+		}
+
+		Ok(Self {
+			family: family.unwrap_or_else(|| "Unknown".to_owned()),
+			copyright,
+			bold,
+			italic,
+			fixed_pitch,
+			units_per_em,
+			advances,
+			cmap: read_cmap(tables.require(b"cmap")?)?
+		})
+	}
+
+	/// Get the advance width of a glyph in font units. Glyphs past the end of the
+	/// `hmtx` metrics use the last advance.
+	fn advance(&self, gid: u16) -> Option<u16> {
+		self.advances
+			.get(usize::from(gid))
+			.or(self.advances.last())
+			.copied()
+	}
+}
+
+/// Read a single strike, described by its `BitmapSize` record.
+fn read_strike(
+	info: &FontInfo,
+	ebdt: Data<'_>,
+	eblc: Data<'_>,
+	size: Data<'_>
+) -> Result<Font, SfntError> {
+	let ascent = i32::from(size.i8(16)?);
+	let descent = -i32::from(size.i8(17)?);
+	let ppem = u32::from(size.u8(45)?);
+	let images = StrikeImages {
+		ebdt,
+		locations: read_index(eblc, size)?,
+		bit_depth: size.u8(46)?,
+		decoded: RefCell::default(),
+		painted: Cell::default()
+	};
+
+	let mut glyphs = BTreeSet::new();
+	for (gid, codes) in &info.cmap {
+		let Some(image) = images.image(*gid, 0)? else {
+			continue;
+		};
+		let metrics = image.metrics;
+		let dwidth = f64::from(metrics.advance);
+		let swidth = match info.advance(*gid) {
+			Some(advance) if info.units_per_em > 0 => {
+				(f64::from(advance) * 1000.0 / f64::from(info.units_per_em)).round()
+			},
+			_ => (dwidth * 1000.0 / f64::from(ppem.max(1))).round()
+		};
+		let bbox = BoundingBox {
+			width: metrics.width.into(),
+			height: metrics.height.into(),
+			offset_x: metrics.bearing_x.into(),
+			offset_y: i32::from(metrics.bearing_y) - i32::from(metrics.height)
+		};
+		let bitmap = glyph_bitmap(image.rows.clone());
+		for &encoding in codes {
+			glyphs.insert(GlyphWrapper(Glyph {
+				name: format!("uni{encoding:04X}"),
+				encoding,
+				swidth: Some((swidth, 0.0)),
+				dwidth: Some((dwidth, 0.0)),
+				bbox,
+				bitmap: bitmap.clone()
+			}));
+		}
+	}
+
+	let weight_name = match info.bold {
+		true => "Bold",
+		false => "Medium"
+	};
+	let slant = match info.italic {
+		true => "I",
+		false => "R"
+	};
+	let spacing = match info.fixed_pitch {
+		true => "M",
+		false => "P"
+	};
+	let advances: Vec<i32> = glyphs.iter().map(|gw| advance(&gw.0)).collect();
+	let average_width = match advances.len() {
+		0 => 0,
+		len => advances.iter().sum::<i32>() * 10 / len as i32
+	};
+	let family = &info.family;
+	let name = format!(
+		"-misc-{family}-{weight_name}-{slant}-Normal--{ppem}-{}-72-72-{spacing}-\
+		 {average_width}-iso10646-1",
+		ppem * 10
+	);
+
+	let mut properties: HashMap<String, Value> = HashMap::from([
+		("FAMILY_NAME".into(), Value::String(family.clone())),
+		("WEIGHT_NAME".into(), Value::String(weight_name.into())),
+		("SLANT".into(), Value::String(slant.into())),
+		("PIXEL_SIZE".into(), Value::Integer(ppem as i32)),
+		("POINT_SIZE".into(), Value::Integer(ppem as i32 * 10)),
+		("RESOLUTION_X".into(), Value::Integer(72)),
+		("RESOLUTION_Y".into(), Value::Integer(72)),
+		("SPACING".into(), Value::String(spacing.into())),
+		("AVERAGE_WIDTH".into(), Value::Integer(average_width)),
+		("CHARSET_REGISTRY".into(), Value::String("iso10646".into())),
+		("CHARSET_ENCODING".into(), Value::String("1".into())),
+		("FONT_ASCENT".into(), Value::Integer(ascent)),
+		("FONT_DESCENT".into(), Value::Integer(descent))
+	]);
+	if let Some(copyright) = &info.copyright {
+		properties.insert("COPYRIGHT".into(), Value::String(copyright.clone()));
+	}
+
+	let bbox = glyphs
+		.iter()
+		.fold(BoundingBox::default(), |bbox, gw| bbox.union(gw.0.bbox));
+	Ok(Font {
+		version: None,
+		name,
+		bbox,
+		size: Size {
+			pt: ppem,
+			xres: 72,
+			yres: 72
+		},
+		properties,
+		glyphs
+	})
+}
+
+impl Font {
+	/// Read the embedded bitmap strikes of an OpenType or TrueType font, returning
+	/// one font per strike in the order they are stored.
+	///
+	/// The bitmaps are taken from the `EBDT` and `EBLC` tables, or their Apple
+	/// counterparts `bdat` and `bloc`, and the glyph encodings from the Unicode
+	/// subtable of `cmap`. Glyphs without a character code are skipped. Grayscale
+	/// strikes are converted to monochrome by setting all pixels that are at least
+	/// half opaque. The `DWIDTH` of each glyph is its advance in the strike, while
+	/// the `SWIDTH` is derived from `hmtx`.
+	pub fn read_otb<R: Read>(reader: R) -> Result<Vec<Self>, SfntError> {
+		let mut buf = Vec::new();
+		decompress(BufReader::new(reader))?.read_to_end(&mut buf)?;
+		let tables = Tables::parse(&buf)?;
+		let (ebdt, eblc) = match (tables.get(b"bdat"), tables.get(b"bloc")) {
+			(Some(bdat), Some(bloc)) => (bdat, bloc),
+			_ => (tables.require(b"EBDT")?, tables.require(b"EBLC")?)
+		};
+		let info = FontInfo::read(&tables)?;
+
+		let num_sizes = eblc.u32(4)? as usize;
+		let mut fonts = Vec::new();
+		for i in 0 .. num_sizes {
+			let size = Data(eblc.bytes(8 + 48 * i, 48)?);
+			let bit_depth = size.u8(46)?;
+			if !matches!(bit_depth, 1 | 2 | 4 | 8) {
+				return Err(SfntError::UnsupportedFormat("bit depth", bit_depth.into()));
+			}
+			fonts.push(read_strike(&info, ebdt, eblc, size)?);
+		}
+		Ok(fonts)
+	}
+}
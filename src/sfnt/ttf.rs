@@ -0,0 +1,193 @@
+//! Scalable TrueType fonts with outlines in the `glyf` table.
+
+use super::*;
+use crate::{outline::Polygon, Font, Glyph};
+use std::iter;
+
+const ON_CURVE_POINT: u8 = 0x01;
+const X_SHORT_VECTOR: u8 = 0x02;
+const Y_SHORT_VECTOR: u8 = 0x04;
+const X_IS_SAME_OR_POSITIVE: u8 = 0x10;
+const Y_IS_SAME_OR_POSITIVE: u8 = 0x20;
+
+/// Get the flag and the encoded coordinate delta of a point, or `None` if the delta
+/// exceeds 16 bits.
+fn coordinate(delta: i32, short: u8, same_or_positive: u8) -> Option<(u8, Vec<u8>)> {
+	Some(match delta {
+		0 => (same_or_positive, Vec::new()),
+		1 ..= 0xFF => (short | same_or_positive, vec![delta as u8]),
+		-0xFF ..= -1 => (short, vec![(-delta) as u8]),
+		_ => (0, i16::try_from(delta).ok()?.to_be_bytes().to_vec())
+	})
+}
+
+/// An encoded simple glyph of the `glyf` table.
+struct SimpleGlyph {
+	data: Vec<u8>,
+	metrics: GlyphMetrics,
+	points: usize,
+	contours: usize
+}
+
+impl SimpleGlyph {
+	/// Get a glyph without outline, which takes up no space in the `glyf` table.
+	fn empty(advance: u16) -> Self {
+		Self {
+			data: Vec::new(),
+			metrics: GlyphMetrics {
+				advance,
+				bbox: BoundingBox::default()
+			},
+			points: 0,
+			contours: 0
+		}
+	}
+}
+
+/// Convert the outline of a glyph to font units and encode it as a simple glyph
+/// without instructions.
+fn simple_glyph(glyph: &Glyph, units_per_pixel: i32) -> Result<SimpleGlyph, SfntError> {
+	let contours: Vec<Polygon> = glyph
+		.bitmap()
+		.contours()
+		.into_iter()
+		.map(|contour| {
+			contour
+				.into_iter()
+				.map(|(x, y)| (x * units_per_pixel, y * units_per_pixel))
+				.collect()
+		})
+		.collect();
+	let points: Vec<(i32, i32)> = contours.iter().flatten().copied().collect();
+	let advance = clamp_u16(advance(glyph) * units_per_pixel);
+
+	let out_of_range = || SfntError::MetricsOutOfRange(glyph.encoding);
+	let (Some(x_min), Some(x_max), Some(y_min), Some(y_max)) = (
+		points.iter().map(|p| p.0).min(),
+		points.iter().map(|p| p.0).max(),
+		points.iter().map(|p| p.1).min(),
+		points.iter().map(|p| p.1).max()
+	) else {
+		return Ok(SimpleGlyph::empty(advance));
+	};
+	let bounds = [x_min, y_min, x_max, y_max]
+		.map(|v| i16::try_from(v).map_err(|_| out_of_range()));
+	let contour_count = i16::try_from(contours.len()).map_err(|_| out_of_range())?;
+
+	let mut data = Vec::new();
+	data.put_i16(contour_count);
+	for bound in bounds {
+		data.put_i16(bound?);
+	}
+	let mut end = 0;
+	for contour in &contours {
+		end += contour.len();
+		data.put_u16(u16::try_from(end - 1).map_err(|_| out_of_range())?);
+	}
+	data.put_u16(0); // instructionLength
+
+	let mut flags = Vec::with_capacity(points.len());
+	let mut xs = Vec::new();
+	let mut ys = Vec::new();
+	for (prev, point) in iter::once((0, 0))
+		.chain(points.iter().copied())
+		.zip(&points)
+	{
+		let (x_flag, x) =
+			coordinate(point.0 - prev.0, X_SHORT_VECTOR, X_IS_SAME_OR_POSITIVE)
+				.ok_or_else(out_of_range)?;
+		let (y_flag, y) =
+			coordinate(point.1 - prev.1, Y_SHORT_VECTOR, Y_IS_SAME_OR_POSITIVE)
+				.ok_or_else(out_of_range)?;
+		flags.push(ON_CURVE_POINT | x_flag | y_flag);
+		xs.extend(x);
+		ys.extend(y);
+	}
+	data.extend(flags);
+	data.extend(xs);
+	data.extend(ys);
+	data.resize(data.len().div_ceil(4) * 4, 0);
+
+	Ok(SimpleGlyph {
+		data,
+		metrics: GlyphMetrics {
+			advance,
+			bbox: BoundingBox {
+				width: (x_max - x_min) as u32,
+				height: (y_max - y_min) as u32,
+				offset_x: x_min,
+				offset_y: y_min
+			}
+		},
+		points: points.len(),
+		contours: contours.len()
+	})
+}
+
+impl Font {
+	/// Write this font as a scalable TrueType font.
+	///
+	/// The set pixels of every glyph are merged into rectilinear outlines, and each
+	/// pixel spans an integer number of font units. Rendered at its native pixel
+	/// size, which is the `PIXEL_SIZE` property or the sum of ascent and descent,
+	/// the font therefore matches the bitmap font exactly. The encodings are assumed
+	/// to be Unicode code points, and the default glyph, if any, becomes `.notdef`.
+	pub fn write_ttf<W: Write>(&self, writer: W) -> Result<(), SfntError> {
+		let glyphs: Vec<&Glyph> = self.glyphs().into_iter().collect();
+		if glyphs.len() >= 0xFFFF {
+			return Err(SfntError::TooManyGlyphs(glyphs.len() + 1));
+		}
+
+		let mut info = Info::new(self);
+		let mut glyf = Vec::new();
+		let mut offsets = vec![0];
+		let (mut max_points, mut max_contours) = (0, 0);
+		// glyph id 0 is the .notdef glyph, which uses the default glyph if there is one
+		let notdef = self.default_glyph();
+		for glyph in iter::once(notdef).chain(glyphs.iter().copied().map(Some)) {
+			let glyph = match glyph {
+				Some(glyph) => simple_glyph(glyph, info.units_per_pixel)?,
+				None => SimpleGlyph::empty(0)
+			};
+			max_points = max_points.max(glyph.points);
+			max_contours = max_contours.max(glyph.contours);
+			info.metrics.push(glyph.metrics);
+			glyf.extend(glyph.data);
+			offsets.push(glyf.len());
+		}
+		info.cmap = glyphs
+			.iter()
+			.zip(1 ..)
+			.map(|(glyph, gid)| (glyph.encoding, gid))
+			.collect();
+
+		// short offsets store half of the actual offset
+		let short = glyf.len() <= 2 * 0xFFFF;
+		let mut loca = Vec::new();
+		for offset in offsets {
+			match short {
+				true => loca.put_u16((offset / 2) as u16),
+				false => loca.put_u32(offset as u32)
+			}
+		}
+
+		write_sfnt(writer, vec![
+			(b"OS/2", info.os2()),
+			(b"cmap", info.cmap()),
+			(b"glyf", glyf),
+			(b"head", info.head(i16::from(!short))),
+			(b"hhea", info.hhea()),
+			(b"hmtx", info.hmtx()),
+			(b"loca", loca),
+			(
+				b"maxp",
+				info.maxp_truetype(
+					clamp_u16(max_points as i32),
+					clamp_u16(max_contours as i32)
+				)
+			),
+			(b"name", info.name()),
+			(b"post", info.post()),
+		])
+	}
+}
@@ -0,0 +1,209 @@
+//! Specimen sheets showing all glyphs of a font.
+
+use crate::{Bitmap, BitmapBuf, BoundingBox, Font, Glyph};
+use std::ops::RangeInclusive;
+
+/// The width of a character of the label font.
+const LABEL_WIDTH: u32 = 3;
+
+/// The height of a character of the label font.
+const LABEL_HEIGHT: u32 = 5;
+
+/// Get the rows of a character of the built-in label font, with the leftmost pixel in
+/// the highest of three bits. Lowercase letters use the uppercase shapes, unsupported
+/// characters are blank.
+fn label_char(ch: char) -> [u8; 5] {
+	match ch.to_ascii_uppercase() {
+		'0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+		'1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+		'2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+		'3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+		'4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+		'5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+		'6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+		'7' => [0b111, 0b001, 0b001, 0b010, 0b010],
+		'8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+		'9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+		'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+		'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+		'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+		'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+		'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+		'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+		'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+		'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+		'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+		'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+		'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+		'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+		'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+		'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+		'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+		'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+		'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+		'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+		'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+		'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+		'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+		'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+		'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+		'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+		'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+		'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+		'+' => [0b000, 0b010, 0b111, 0b010, 0b000],
+		'-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+		'.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+		'_' => [0b000, 0b000, 0b000, 0b000, 0b111],
+		_ => [0; 5]
+	}
+}
+
+/// Get the width of a label in pixels, with one empty column between characters.
+fn label_width(label: &str) -> u32 {
+	(label.chars().count() as u32 * (LABEL_WIDTH + 1)).saturating_sub(1)
+}
+
+/// Options for rendering a [specimen sheet](Font::specimen).
+#[derive(Clone, Debug)]
+pub struct SpecimenOptions {
+	/// The number of glyphs per row.
+	pub columns: usize,
+
+	/// Only include glyphs with an encoding in this range, e.g. a Unicode block.
+	pub range: Option<RangeInclusive<u32>>,
+
+	/// Whether to label each glyph with its name, in addition to its encoding.
+	pub names: bool,
+
+	/// A sample text rendered with the font above the glyphs. An empty text omits the
+	/// header.
+	pub sample_text: String,
+
+	/// The number of empty pixels between the cells of the grid and around the sheet.
+	pub spacing: u32
+}
+
+impl Default for SpecimenOptions {
+	fn default() -> Self {
+		Self {
+			columns: 16,
+			range: None,
+			names: false,
+			sample_text: "The quick brown fox jumps over the lazy dog".to_owned(),
+			spacing: 4
+		}
+	}
+}
+
+/// A bitmap whose pixels are addressed from the top left corner.
+struct Sheet(BitmapBuf);
+
+impl Sheet {
+	fn new(width: u32, height: u32) -> Self {
+		Self(BitmapBuf::new(BoundingBox {
+			width,
+			height,
+			offset_x: 0,
+			offset_y: -(height as i32)
+		}))
+	}
+
+	/// Draw a bitmap with the top left corner of `bbox`, relative to the origin of the
+	/// bitmap, placed at (`left`, `top`).
+	fn draw(&mut self, bitmap: Bitmap<'_>, bbox: BoundingBox, left: u32, top: u32) {
+		let x = left as i32 - bbox.offset_x;
+		let y = -(top as i32) - bbox.height as i32 - bbox.offset_y;
+		self.0.draw(bitmap, x, y);
+	}
+
+	fn label(&mut self, label: &str, left: u32, top: u32) {
+		for (idx, ch) in label.chars().enumerate() {
+			let x = left + idx as u32 * (LABEL_WIDTH + 1);
+			for (y, row) in label_char(ch).into_iter().enumerate() {
+				for dx in 0 .. LABEL_WIDTH {
+					if row & (1 << (LABEL_WIDTH - 1 - dx)) != 0 {
+						let _ = self.0.set((x + dx) as usize, top as usize + y, true);
+					}
+				}
+			}
+		}
+	}
+}
+
+impl Font {
+	/// Render a specimen sheet of this font: a header with a sample text, followed by
+	/// a grid of all glyphs ordered by their encoding. Each glyph is labelled with its
+	/// encoding in hexadecimal and optionally its name, using a small built-in font.
+	///
+	/// The returned bitmap can be saved with any of the image exporters, e.g.
+	/// [`Bitmap::write_pbm`](crate::Bitmap::write_pbm).
+	pub fn specimen(&self, options: &SpecimenOptions) -> BitmapBuf {
+		let spacing = options.spacing;
+		let columns = options.columns.max(1);
+		let glyphs: Vec<&Glyph> = self
+			.glyphs()
+			.into_iter()
+			.filter(|glyph| match &options.range {
+				Some(range) => range.contains(&glyph.encoding),
+				None => true
+			})
+			.collect();
+		let labels: Vec<(String, &str)> = glyphs
+			.iter()
+			.map(|glyph| (format!("{:04X}", glyph.encoding), glyph.name.as_str()))
+			.collect();
+
+		let header = self.layout(&options.sample_text).render();
+		let header_bbox = header.bounding_box();
+		let header_height = match header_bbox.is_empty() {
+			true => 0,
+			false => header_bbox.height + spacing
+		};
+
+		// every cell has room for the font bounding box and the labels below it
+		let bbox = self.bbox;
+		let cell_width = labels
+			.iter()
+			.map(|(code, name)| {
+				let name_width = match options.names {
+					true => label_width(name),
+					false => 0
+				};
+				label_width(code).max(name_width)
+			})
+			.fold(bbox.width, u32::max);
+		let label_lines = 1 + u32::from(options.names);
+		let cell_height = bbox.height + label_lines * (LABEL_HEIGHT + 1);
+		let rows = glyphs.len().div_ceil(columns) as u32;
+		let grid_columns = (glyphs.len().min(columns)) as u32;
+		let grid_width = (grid_columns * (cell_width + spacing)).saturating_sub(spacing);
+		let grid_height = (rows * (cell_height + spacing)).saturating_sub(spacing);
+
+		let width = grid_width.max(header_bbox.width) + 2 * spacing;
+		let height = header_height + grid_height + 2 * spacing;
+		let mut sheet = Sheet::new(width, height);
+		sheet.draw(header.as_bitmap(), header_bbox, spacing, spacing);
+
+		let grid_top = spacing + header_height;
+		for (idx, (glyph, (code, name))) in glyphs.iter().zip(&labels).enumerate() {
+			let col = (idx % columns) as u32;
+			let row = (idx / columns) as u32;
+			let left = spacing + col * (cell_width + spacing);
+			let top = grid_top + row * (cell_height + spacing);
+			sheet.draw(
+				glyph.bitmap(),
+				bbox,
+				left + (cell_width - bbox.width) / 2,
+				top
+			);
+
+			let mut label_top = top + bbox.height + 1;
+			sheet.label(code, left + (cell_width - label_width(code)) / 2, label_top);
+			if options.names {
+				label_top += LABEL_HEIGHT + 1;
+				sheet.label(name, left + (cell_width - label_width(name)) / 2, label_top);
+			}
+		}
+		sheet.0
+	}
+}
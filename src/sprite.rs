@@ -0,0 +1,163 @@
+//! Sprite sheets: glyphs arranged in a grid of fixed-size cells in an image, described
+//! by a JSON sidecar, so that they can be edited in an image editor.
+
+use crate::tga::{Image, TgaError};
+use std::io;
+use thiserror::Error;
+
+mod json;
+mod reader;
+mod writer;
+
+/// The color of ink pixels in exported images.
+const INK: [u8; 4] = [0, 0, 0, 0xFF];
+
+/// The color of empty pixels in exported images.
+const BACKGROUND: [u8; 4] = [0, 0, 0, 0];
+
+#[derive(Debug, Error)]
+pub enum SpriteSheetError {
+	#[error("I/O Error: {0}")]
+	IOError(#[from] io::Error),
+
+	#[error("Syntax error in line {0} of the sidecar")]
+	SyntaxError(usize),
+
+	#[error("Missing or invalid {0} in the sidecar")]
+	Missing(&'static str),
+
+	#[error("The {0} in the sidecar is out of range")]
+	InvalidValue(&'static str),
+
+	#[error("Unexpected end of file")]
+	UnexpectedEof,
+
+	#[error("Unsupported image: {0}")]
+	UnsupportedImage(&'static str),
+
+	#[error("The image exceeds the maximum size of its format")]
+	ImageTooLarge,
+
+	#[error("The cell of glyph {0} is outside of the image")]
+	CellOutsideImage(u32),
+
+	#[cfg(feature = "png")]
+	#[error("PNG Decoding Error: {0}")]
+	PngDecodingError(#[from] png::DecodingError),
+
+	#[cfg(feature = "png")]
+	#[error("PNG Encoding Error: {0}")]
+	PngEncodingError(#[from] png::EncodingError)
+}
+
+impl From<TgaError> for SpriteSheetError {
+	fn from(err: TgaError) -> Self {
+		match err {
+			TgaError::UnexpectedEof => Self::UnexpectedEof,
+			TgaError::Unsupported(what) => Self::UnsupportedImage(what)
+		}
+	}
+}
+
+/// The image format of a sprite sheet.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SpriteSheetImage {
+	/// Uncompressed 32 bit TGA, which needs no additional dependencies.
+	#[default]
+	Tga,
+
+	/// 8-bit RGBA PNG, with the `png` feature.
+	#[cfg(feature = "png")]
+	Png
+}
+
+impl SpriteSheetImage {
+	fn extension(self) -> &'static str {
+		match self {
+			Self::Tga => "tga",
+			#[cfg(feature = "png")]
+			Self::Png => "png"
+		}
+	}
+}
+
+/// Options for writing sprite sheets.
+#[derive(Clone, Copy, Debug)]
+pub struct SpriteSheetOptions {
+	/// The number of cells per row.
+	pub columns: usize,
+
+	/// The number of empty pixels around the glyphs in each cell, which leaves room
+	/// for editing glyphs beyond the font bounding box.
+	pub padding: u32,
+
+	/// The number of pixels between cells.
+	pub spacing: u32,
+
+	/// The image format.
+	pub image: SpriteSheetImage
+}
+
+impl Default for SpriteSheetOptions {
+	fn default() -> Self {
+		Self {
+			columns: 16,
+			padding: 0,
+			spacing: 0,
+			image: SpriteSheetImage::Tga
+		}
+	}
+}
+
+/// Check whether a pixel is ink: dark and, for images with transparency, opaque.
+fn is_ink(image: &Image, [r, g, b, a]: [u8; 4]) -> bool {
+	let luma = (u32::from(r) * 299 + u32::from(g) * 587 + u32::from(b) * 114) / 1000;
+	luma < 0x80 && (image.gray || !image.has_alpha || a >= 0x80)
+}
+
+#[cfg(feature = "png")]
+fn decode_png(data: &[u8]) -> Result<Image, SpriteSheetError> {
+	use png::{ColorType, Transformations};
+
+	let mut decoder = png::Decoder::new(data);
+	decoder.set_transformations(Transformations::EXPAND | Transformations::STRIP_16);
+	let mut reader = decoder.read_info()?;
+	let mut buf = vec![0; reader.output_buffer_size()];
+	let info = reader.next_frame(&mut buf)?;
+	let pixels = buf[.. info.buffer_size()]
+		.chunks(info.color_type.samples())
+		.map(|px| match *px {
+			[v] => [v, v, v, v],
+			[v, a] => [v, v, v, a],
+			[r, g, b] => [r, g, b, 0xFF],
+			[r, g, b, a] => [r, g, b, a],
+			_ => unreachable!()
+		})
+		.collect();
+	Ok(Image {
+		width: info.width as usize,
+		height: info.height as usize,
+		pixels,
+		has_alpha: matches!(
+			info.color_type,
+			ColorType::Grayscale | ColorType::GrayscaleAlpha | ColorType::Rgba
+		),
+		gray: info.color_type == ColorType::Grayscale
+	})
+}
+
+#[cfg(feature = "png")]
+fn encode_png(
+	width: u32,
+	height: u32,
+	pixels: &[[u8; 4]]
+) -> Result<Vec<u8>, SpriteSheetError> {
+	let mut buf = Vec::new();
+	let mut encoder = png::Encoder::new(&mut buf, width, height);
+	encoder.set_color(png::ColorType::Rgba);
+	encoder.set_depth(png::BitDepth::Eight);
+	let mut writer = encoder.write_header()?;
+	writer.write_image_data(pixels.as_flattened())?;
+	writer.finish()?;
+	Ok(buf)
+}
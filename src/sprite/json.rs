@@ -0,0 +1,323 @@
+//! A minimal JSON reader and writer for sprite sheet sidecars.
+//!
+//! Sidecars only need a small subset of JSON, which doesn't justify pulling serde into
+//! the dependencies of a font library. This module is only built with the
+//! `sprite-sheet` feature.
+
+use super::SpriteSheetError;
+use std::{fmt::Write as _, str::CharIndices};
+
+/// The deepest nesting of arrays and objects that is parsed. Sidecars need far less,
+/// and the limit keeps deeply nested input from overflowing the stack.
+const MAX_DEPTH: usize = 32;
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Json {
+	Null,
+	Bool(bool),
+	Number(f64),
+	String(String),
+	Array(Vec<Json>),
+
+	/// An object with its members in the order they appeared.
+	Object(Vec<(String, Json)>)
+}
+
+impl Json {
+	/// Get a member of an object.
+	pub(crate) fn get(&self, key: &str) -> Option<&Json> {
+		match self {
+			Self::Object(members) => {
+				members.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+			},
+			_ => None
+		}
+	}
+
+	pub(crate) fn as_f64(&self) -> Option<f64> {
+		match self {
+			Self::Number(value) => Some(*value),
+			_ => None
+		}
+	}
+
+	pub(crate) fn as_i64(&self) -> Option<i64> {
+		self.as_f64()
+			.filter(|value| value.fract() == 0.0)
+			.map(|value| value as i64)
+	}
+
+	pub(crate) fn as_str(&self) -> Option<&str> {
+		match self {
+			Self::String(value) => Some(value),
+			_ => None
+		}
+	}
+
+	pub(crate) fn as_array(&self) -> Option<&[Json]> {
+		match self {
+			Self::Array(values) => Some(values),
+			_ => None
+		}
+	}
+
+	/// Check whether this value is written on a single line.
+	fn is_inline(&self) -> bool {
+		match self {
+			Self::Array(values) => values
+				.iter()
+				.all(|value| !matches!(value, Self::Array(_) | Self::Object(_))),
+			Self::Object(members) => members.is_empty(),
+			_ => true
+		}
+	}
+
+	/// Write this value, indenting nested arrays and objects by one tab per level.
+	pub(crate) fn write(&self, buf: &mut String, indent: usize) {
+		match self {
+			Self::Null => *buf += "null",
+			Self::Bool(value) => write!(buf, "{value}").unwrap(),
+			Self::Number(value) => write!(buf, "{value}").unwrap(),
+			Self::String(value) => write_string(buf, value),
+			Self::Array(values) if self.is_inline() => {
+				*buf += "[";
+				for (idx, value) in values.iter().enumerate() {
+					if idx > 0 {
+						*buf += ", ";
+					}
+					value.write(buf, indent);
+				}
+				*buf += "]";
+			},
+			Self::Array(values) => {
+				*buf += "[\n";
+				for (idx, value) in values.iter().enumerate() {
+					*buf += &"\t".repeat(indent + 1);
+					value.write(buf, indent + 1);
+					if idx + 1 < values.len() {
+						*buf += ",";
+					}
+					*buf += "\n";
+				}
+				*buf += &"\t".repeat(indent);
+				*buf += "]";
+			},
+			Self::Object(members) if members.is_empty() => *buf += "{}",
+			Self::Object(members) => {
+				*buf += "{\n";
+				for (idx, (key, value)) in members.iter().enumerate() {
+					*buf += &"\t".repeat(indent + 1);
+					write_string(buf, key);
+					*buf += ": ";
+					value.write(buf, indent + 1);
+					if idx + 1 < members.len() {
+						*buf += ",";
+					}
+					*buf += "\n";
+				}
+				*buf += &"\t".repeat(indent);
+				*buf += "}";
+			}
+		}
+	}
+}
+
+fn write_string(buf: &mut String, value: &str) {
+	*buf += "\"";
+	for ch in value.chars() {
+		match ch {
+			'"' => *buf += "\\\"",
+			'\\' => *buf += "\\\\",
+			'\n' => *buf += "\\n",
+			'\r' => *buf += "\\r",
+			'\t' => *buf += "\\t",
+			ch if ch < ' ' => write!(buf, "\\u{:04x}", ch as u32).unwrap(),
+			ch => buf.push(ch)
+		}
+	}
+	*buf += "\"";
+}
+
+/// Parse a JSON document.
+pub(crate) fn parse(input: &str) -> Result<Json, SpriteSheetError> {
+	let mut parser = Parser {
+		input,
+		pos: 0,
+		depth: 0
+	};
+	let value = parser.value()?;
+	parser.skip_whitespace();
+	match parser.pos == input.len() {
+		true => Ok(value),
+		false => Err(parser.error())
+	}
+}
+
+/// Read the four hexadecimal digits of a UTF-16 code unit.
+fn hex_unit(chars: &mut CharIndices<'_>) -> Option<u32> {
+	let hex: String = chars.take(4).map(|(_, ch)| ch).collect();
+	u32::from_str_radix(&hex, 16).ok()
+}
+
+struct Parser<'a> {
+	input: &'a str,
+	pos: usize,
+
+	/// The number of arrays and objects around the current position.
+	depth: usize
+}
+
+impl Parser<'_> {
+	/// Get an error pointing to the line of the current position.
+	fn error(&self) -> SpriteSheetError {
+		let line = self.input[.. self.pos].matches('\n').count() + 1;
+		SpriteSheetError::SyntaxError(line)
+	}
+
+	fn rest(&self) -> &str {
+		&self.input[self.pos ..]
+	}
+
+	fn skip_whitespace(&mut self) {
+		let rest = self.rest();
+		self.pos += rest.len() - rest.trim_start_matches([' ', '\t', '\n', '\r']).len();
+	}
+
+	fn expect(&mut self, token: &str) -> Result<(), SpriteSheetError> {
+		self.skip_whitespace();
+		match self.rest().starts_with(token) {
+			true => {
+				self.pos += token.len();
+				Ok(())
+			},
+			false => Err(self.error())
+		}
+	}
+
+	/// Consume `token` if it is next, ignoring whitespace.
+	fn eat(&mut self, token: char) -> bool {
+		self.skip_whitespace();
+		let found = self.rest().starts_with(token);
+		if found {
+			self.pos += token.len_utf8();
+		}
+		found
+	}
+
+	fn value(&mut self) -> Result<Json, SpriteSheetError> {
+		self.skip_whitespace();
+		match self.rest().chars().next() {
+			Some(ch @ ('{' | '[')) => {
+				if self.depth == MAX_DEPTH {
+					return Err(self.error());
+				}
+				self.depth += 1;
+				let value = match ch {
+					'{' => self.object(),
+					_ => self.array()
+				};
+				self.depth -= 1;
+				value
+			},
+			Some('"') => Ok(Json::String(self.string()?)),
+			Some('t') => self.expect("true").map(|_| Json::Bool(true)),
+			Some('f') => self.expect("false").map(|_| Json::Bool(false)),
+			Some('n') => self.expect("null").map(|_| Json::Null),
+			Some('-' | '0' ..= '9') => self.number(),
+			_ => Err(self.error())
+		}
+	}
+
+	fn object(&mut self) -> Result<Json, SpriteSheetError> {
+		self.expect("{")?;
+		let mut members = Vec::new();
+		if self.eat('}') {
+			return Ok(Json::Object(members));
+		}
+		loop {
+			self.skip_whitespace();
+			let key = self.string()?;
+			self.expect(":")?;
+			members.push((key, self.value()?));
+			if self.eat('}') {
+				return Ok(Json::Object(members));
+			}
+			self.expect(",")?;
+		}
+	}
+
+	fn array(&mut self) -> Result<Json, SpriteSheetError> {
+		self.expect("[")?;
+		let mut values = Vec::new();
+		if self.eat(']') {
+			return Ok(Json::Array(values));
+		}
+		loop {
+			values.push(self.value()?);
+			if self.eat(']') {
+				return Ok(Json::Array(values));
+			}
+			self.expect(",")?;
+		}
+	}
+
+	fn string(&mut self) -> Result<String, SpriteSheetError> {
+		self.expect("\"")?;
+		let mut value = String::new();
+		let mut chars = self.rest().char_indices();
+		while let Some((idx, ch)) = chars.next() {
+			match ch {
+				'"' => {
+					self.pos += idx + 1;
+					return Ok(value);
+				},
+				'\\' => {
+					let escaped = match chars.next().map(|(_, ch)| ch) {
+						Some('"') => '"',
+						Some('\\') => '\\',
+						Some('/') => '/',
+						Some('b') => '\u{8}',
+						Some('f') => '\u{c}',
+						Some('n') => '\n',
+						Some('r') => '\r',
+						Some('t') => '\t',
+						Some('u') => {
+							let high = hex_unit(&mut chars);
+							let code = match high {
+								Some(high @ 0xD800 .. 0xDC00) => {
+									if chars.next().map(|(_, ch)| ch) != Some('\\')
+										|| chars.next().map(|(_, ch)| ch) != Some('u')
+									{
+										return Err(self.error());
+									}
+									hex_unit(&mut chars)
+										.filter(|low| (0xDC00 .. 0xE000).contains(low))
+										.map(|low| {
+											0x10000
+												+ ((high - 0xD800) << 10) + (low - 0xDC00)
+										})
+								},
+								code => code
+							};
+							code.and_then(char::from_u32).ok_or_else(|| self.error())?
+						},
+						_ => return Err(self.error())
+					};
+					value.push(escaped);
+				},
+				ch => value.push(ch)
+			}
+		}
+		Err(self.error())
+	}
+
+	fn number(&mut self) -> Result<Json, SpriteSheetError> {
+		let rest = self.rest();
+		let len = rest
+			.find(|ch: char| !matches!(ch, '-' | '+' | '.' | 'e' | 'E' | '0' ..= '9'))
+			.unwrap_or(rest.len());
+		let value = rest[.. len].parse().map_err(|_| self.error())?;
+		self.pos += len;
+		Ok(Json::Number(value))
+	}
+}
@@ -0,0 +1,194 @@
+use super::{json::Json, *};
+use crate::{font::GlyphWrapper, tga, BoundingBox, Font, Glyph, Size, Value};
+use bit_vec::BitVec;
+use std::{
+	collections::{BTreeSet, HashMap},
+	ffi::OsStr,
+	fs,
+	path::Path
+};
+
+/// The magic bytes of PNG images.
+#[cfg(feature = "png")]
+const PNG_MAGIC: &[u8; 8] = b"\x89PNG\r\n\x1a\n";
+
+fn integer<T: TryFrom<i64>>(
+	json: &Json,
+	key: &'static str
+) -> Result<T, SpriteSheetError> {
+	json.get(key)
+		.and_then(Json::as_i64)
+		.and_then(|value| value.try_into().ok())
+		.ok_or(SpriteSheetError::Missing(key))
+}
+
+fn pair(json: &Json, key: &'static str) -> Result<Option<(f64, f64)>, SpriteSheetError> {
+	json.get(key)
+		.map(|value| match value.as_array() {
+			Some([x, y]) => x.as_f64().zip(y.as_f64()),
+			_ => None
+		})
+		.map(|value| value.ok_or(SpriteSheetError::Missing(key)))
+		.transpose()
+}
+
+impl Font {
+	/// Read a sprite sheet written by [`Font::write_sprite_sheet`], possibly edited in
+	/// an image editor, from its JSON sidecar at `path`.
+	///
+	/// Dark pixels are ink, unless they are transparent. The bounding box of each
+	/// glyph is recomputed tightly around its ink, while the encoding, name and
+	/// metrics are taken from the sidecar.
+	pub fn read_sprite_sheet<P: AsRef<Path>>(path: P) -> Result<Self, SpriteSheetError> {
+		let path = path.as_ref();
+		let sidecar = json::parse(&fs::read_to_string(path)?)?;
+		let file = sidecar
+			.get("image")
+			.and_then(Json::as_str)
+			.ok_or(SpriteSheetError::Missing("image"))?;
+		// the image has to be next to the sidecar
+		if Path::new(file).file_name() != Some(OsStr::new(file)) {
+			return Err(SpriteSheetError::InvalidValue("image"));
+		}
+		let data = fs::read(path.parent().unwrap_or(Path::new("")).join(file))?;
+		#[cfg(feature = "png")]
+		let image = match data.starts_with(PNG_MAGIC) {
+			true => decode_png(&data)?,
+			false => tga::decode(&data)?
+		};
+		#[cfg(not(feature = "png"))]
+		let image = tga::decode(&data)?;
+
+		let columns: usize = integer(&sidecar, "columns")?;
+		let cell_width: usize = integer(&sidecar, "cell_width")?;
+		let cell_height: usize = integer(&sidecar, "cell_height")?;
+		let spacing: usize = integer(&sidecar, "spacing")?;
+		let origin_x: i32 = integer(&sidecar, "origin_x")?;
+		let origin_y: i32 = integer(&sidecar, "origin_y")?;
+		if columns == 0 {
+			return Err(SpriteSheetError::Missing("columns"));
+		}
+
+		let mut glyphs = BTreeSet::new();
+		let entries = sidecar
+			.get("glyphs")
+			.and_then(Json::as_array)
+			.ok_or(SpriteSheetError::Missing("glyphs"))?;
+		for entry in entries {
+			let encoding: u32 = integer(entry, "encoding")?;
+			let cell: usize = integer(entry, "cell")?;
+			// the start of the cell along one axis, if the whole cell is inside the image
+			let cell_start = |idx: usize, len: usize, image_len: usize| {
+				let start = idx.checked_mul(len.checked_add(spacing)?)?;
+				(start.checked_add(len)? <= image_len).then_some(start)
+			};
+			let (Some(left), Some(top)) = (
+				cell_start(cell % columns, cell_width, image.width),
+				cell_start(cell / columns, cell_height, image.height)
+			) else {
+				return Err(SpriteSheetError::CellOutsideImage(encoding));
+			};
+
+			let ink = |x: usize, y: usize| {
+				is_ink(&image, image.pixels[(top + y) * image.width + left + x])
+			};
+			let (mut min_x, mut min_y, mut max_x, mut max_y) =
+				(usize::MAX, usize::MAX, 0, 0);
+			for y in 0 .. cell_height {
+				for x in 0 .. cell_width {
+					if ink(x, y) {
+						(min_x, min_y) = (min_x.min(x), min_y.min(y));
+						(max_x, max_y) = (max_x.max(x), max_y.max(y));
+					}
+				}
+			}
+			let (bbox, bitmap) = match min_x {
+				usize::MAX => (BoundingBox::default(), Vec::new()),
+				_ => {
+					let (width, height) = (max_x - min_x + 1, max_y - min_y + 1);
+					let bitmap = (min_y ..= max_y)
+						.map(|y| {
+							let mut row = BitVec::from_elem(width.div_ceil(8) * 8, false);
+							for x in min_x ..= max_x {
+								row.set(x - min_x, ink(x, y));
+							}
+							row
+						})
+						.collect();
+					let offset_x = (min_x as i32)
+						.checked_sub(origin_x)
+						.ok_or(SpriteSheetError::InvalidValue("origin_x"))?;
+					let offset_y = origin_y
+						.checked_sub(1 + max_y as i32)
+						.ok_or(SpriteSheetError::InvalidValue("origin_y"))?;
+					let bbox = BoundingBox {
+						width: width as u32,
+						height: height as u32,
+						offset_x,
+						offset_y
+					};
+					(bbox, bitmap)
+				}
+			};
+
+			glyphs.insert(GlyphWrapper(Glyph {
+				name: entry
+					.get("name")
+					.and_then(Json::as_str)
+					.map(str::to_owned)
+					.unwrap_or_else(|| format!("uni{encoding:04X}")),
+				encoding,
+				swidth: pair(entry, "swidth")?,
+				dwidth: pair(entry, "dwidth")?,
+				bbox,
+				bitmap
+			}));
+		}
+
+		let font = sidecar
+			.get("font")
+			.ok_or(SpriteSheetError::Missing("font"))?;
+		let name = font
+			.get("name")
+			.and_then(Json::as_str)
+			.ok_or(SpriteSheetError::Missing("font name"))?;
+		let size = match font.get("size").and_then(Json::as_array) {
+			Some([pt, xres, yres]) => [pt, xres, yres]
+				.map(|value| value.as_i64().and_then(|value| u32::try_from(value).ok())),
+			_ => [None; 3]
+		};
+		let [Some(pt), Some(xres), Some(yres)] = size else {
+			return Err(SpriteSheetError::Missing("font size"));
+		};
+		let properties = match font.get("properties") {
+			Some(Json::Object(members)) => members
+				.iter()
+				.map(|(key, value)| {
+					let value = match value {
+						Json::String(value) => Value::String(value.clone()),
+						value => value
+							.as_i64()
+							.and_then(|value| i32::try_from(value).ok())
+							.map(Value::Integer)
+							.ok_or(SpriteSheetError::Missing("property value"))?
+					};
+					Ok((key.clone(), value))
+				})
+				.collect::<Result<HashMap<_, _>, SpriteSheetError>>()?,
+			Some(_) => return Err(SpriteSheetError::Missing("font properties")),
+			None => HashMap::new()
+		};
+
+		let bbox = glyphs
+			.iter()
+			.fold(BoundingBox::default(), |bbox, gw| bbox.union(gw.0.bbox));
+		Ok(Self {
+			version: None,
+			name: name.to_owned(),
+			bbox,
+			size: Size { pt, xres, yres },
+			properties,
+			glyphs
+		})
+	}
+}
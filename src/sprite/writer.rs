@@ -0,0 +1,152 @@
+use super::{json::Json, *};
+use crate::{tga, BoundingBox, Font, Glyph, Value};
+use std::{fs, path::Path};
+
+fn number<T: Into<f64>>(value: T) -> Json {
+	Json::Number(value.into())
+}
+
+fn pair((x, y): (f64, f64)) -> Json {
+	Json::Array(vec![number(x), number(y)])
+}
+
+impl Font {
+	/// Write the glyphs of this font as a sprite sheet: an image with the glyphs in a
+	/// grid of fixed-size cells, and a JSON sidecar at `path` describing the grid,
+	/// the font and the encoding and metrics of each glyph. The image is named after
+	/// the sidecar, e.g. `font.json` has the image `font.tga`.
+	///
+	/// Ink is black on a transparent background. Every cell fits the bounding boxes
+	/// of all glyphs plus the padding, and the glyph origin is at the same position
+	/// in every cell.
+	pub fn write_sprite_sheet<P: AsRef<Path>>(
+		&self,
+		path: P,
+		options: &SpriteSheetOptions
+	) -> Result<(), SpriteSheetError> {
+		let path = path.as_ref();
+		let glyphs: Vec<&Glyph> = self.glyphs().into_iter().collect();
+		let bbox = glyphs
+			.iter()
+			.fold(self.bbox, |bbox, glyph| bbox.union(glyph.bbox));
+		let padding = options.padding;
+		let columns = options.columns.max(1).min(glyphs.len().max(1));
+		let rows = glyphs.len().div_ceil(columns);
+		// the size of a row or column of cells that fit `len` pixels and the padding
+		let size = |cells: usize, len: u32| -> Option<u32> {
+			let cells = u32::try_from(cells).ok()?;
+			let cell = padding.checked_mul(2)?.checked_add(len)?;
+			let spacing = (cells.max(1) - 1).checked_mul(options.spacing)?;
+			cells.checked_mul(cell)?.checked_add(spacing)
+		};
+		let too_large = || SpriteSheetError::ImageTooLarge;
+		let cell_width = size(1, bbox.width).ok_or_else(too_large)?;
+		let cell_height = size(1, bbox.height).ok_or_else(too_large)?;
+		let width = size(columns, bbox.width).ok_or_else(too_large)?;
+		let height = size(rows, bbox.height).ok_or_else(too_large)?;
+		let padding = i32::try_from(padding).map_err(|_| too_large())?;
+		let origin_x = padding.checked_sub(bbox.offset_x).ok_or_else(too_large)?;
+		let origin_y = padding
+			.checked_add(bbox.height as i32)
+			.and_then(|y| y.checked_add(bbox.offset_y))
+			.ok_or_else(too_large)?;
+
+		let mut pixels = vec![BACKGROUND; width as usize * height as usize];
+		let mut entries = Vec::with_capacity(glyphs.len());
+		for (idx, glyph) in glyphs.iter().enumerate() {
+			// both products are within the image, even for the first row or column
+			let (column, row) = ((idx % columns) as u32, (idx / columns) as u32);
+			let left = column * cell_width + column * options.spacing;
+			let top = row * cell_height + row * options.spacing;
+			let BoundingBox {
+				width: glyph_width,
+				height: glyph_height,
+				offset_x,
+				offset_y
+			} = glyph.bbox;
+			let glyph_left = left as i32 + origin_x + offset_x;
+			let glyph_top = top as i32 + origin_y - offset_y - glyph_height as i32;
+			let bitmap = glyph.bitmap();
+			for y in 0 .. glyph_height as usize {
+				for x in 0 .. glyph_width as usize {
+					if bitmap.get(x, y).unwrap_or(false) {
+						let px = (glyph_left + x as i32) as usize;
+						let py = (glyph_top + y as i32) as usize;
+						pixels[py * width as usize + px] = INK;
+					}
+				}
+			}
+
+			let mut entry = vec![
+				("cell".to_owned(), number(idx as u32)),
+				("encoding".to_owned(), number(glyph.encoding)),
+				("name".to_owned(), Json::String(glyph.name.clone())),
+			];
+			if let Some(swidth) = glyph.swidth {
+				entry.push(("swidth".to_owned(), pair(swidth)));
+			}
+			if let Some(dwidth) = glyph.dwidth {
+				entry.push(("dwidth".to_owned(), pair(dwidth)));
+			}
+			entries.push(Json::Object(entry));
+		}
+
+		let stem = path
+			.file_stem()
+			.map(|stem| stem.to_string_lossy().into_owned())
+			.unwrap_or_default();
+		let file = format!("{stem}.{}", options.image.extension());
+		let data = match options.image {
+			SpriteSheetImage::Tga => {
+				let width =
+					u16::try_from(width).map_err(|_| SpriteSheetError::ImageTooLarge)?;
+				let height =
+					u16::try_from(height).map_err(|_| SpriteSheetError::ImageTooLarge)?;
+				tga::encode(width, height, &pixels)
+			},
+			#[cfg(feature = "png")]
+			SpriteSheetImage::Png => encode_png(width, height, &pixels)?
+		};
+		fs::write(path.parent().unwrap_or(Path::new("")).join(&file), data)?;
+
+		let mut properties: Vec<(String, Json)> = self
+			.properties()
+			.map(|(key, value)| {
+				let value = match value {
+					Value::Integer(value) => number(*value),
+					Value::String(value) => Json::String(value.clone())
+				};
+				(key.to_owned(), value)
+			})
+			.collect();
+		properties.sort_by(|a, b| a.0.cmp(&b.0));
+		let font = Json::Object(vec![
+			("name".to_owned(), Json::String(self.name.clone())),
+			(
+				"size".to_owned(),
+				Json::Array(vec![
+					number(self.size.pt),
+					number(self.size.xres),
+					number(self.size.yres),
+				])
+			),
+			("properties".to_owned(), Json::Object(properties)),
+		]);
+		let sidecar = Json::Object(vec![
+			("image".to_owned(), Json::String(file)),
+			("columns".to_owned(), number(columns as u32)),
+			("cell_width".to_owned(), number(cell_width)),
+			("cell_height".to_owned(), number(cell_height)),
+			("spacing".to_owned(), number(options.spacing)),
+			("origin_x".to_owned(), number(origin_x)),
+			("origin_y".to_owned(), number(origin_y)),
+			("font".to_owned(), font),
+			("glyphs".to_owned(), Json::Array(entries)),
+		]);
+		let mut buf = String::new();
+		sidecar.write(&mut buf, 0);
+		buf += "\n";
+		fs::write(path, buf)?;
+		Ok(())
+	}
+}
@@ -0,0 +1,149 @@
+use crate::{
+	coverage::is_assigned,
+	font::{GlyphIdx, GlyphWrapper},
+	BoundingBox, Font, Glyph, Value
+};
+use std::{
+	collections::{BTreeSet, HashSet},
+	ops::RangeInclusive
+};
+use unicode_normalization::char::decompose_canonical;
+
+/// A set of characters, identified by their encoding or glyph name, used to select the
+/// glyphs of a [font subset](Font::subset).
+#[derive(Clone, Debug, Default)]
+pub struct CharSet {
+	encodings: BTreeSet<u32>,
+	ranges: Vec<RangeInclusive<u32>>,
+	names: HashSet<String>
+}
+
+impl CharSet {
+	/// Create an empty set.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Create a set containing all characters of a sample text.
+	pub fn from_text(text: &str) -> Self {
+		let mut set = Self::new();
+		set.insert_text(text);
+		set
+	}
+
+	/// Add a single character or encoding to the set.
+	pub fn insert<I: GlyphIdx>(&mut self, ch: I) -> &mut Self {
+		self.encodings.insert(ch.encoding());
+		self
+	}
+
+	/// Add a range of encodings, for example a Unicode block, to the set.
+	pub fn insert_range(&mut self, range: RangeInclusive<u32>) -> &mut Self {
+		self.ranges.push(range);
+		self
+	}
+
+	/// Add a glyph name to the set.
+	pub fn insert_name(&mut self, name: &str) -> &mut Self {
+		self.names.insert(name.to_owned());
+		self
+	}
+
+	/// Add all characters of a sample text to the set.
+	pub fn insert_text(&mut self, text: &str) -> &mut Self {
+		self.encodings.extend(text.chars().map(u32::from));
+		self
+	}
+
+	/// Iterate over the encodings of this set in ascending order. Ranges only contribute
+	/// assigned characters, and glyph names are not included.
+	pub(crate) fn iter_encodings(&self) -> impl Iterator<Item = u32> {
+		let mut encodings = self.encodings.clone();
+		for range in &self.ranges {
+			encodings.extend(
+				range
+					.clone()
+					.filter(|enc| char::from_u32(*enc).is_some_and(is_assigned))
+			);
+		}
+		encodings.into_iter()
+	}
+
+	/// Check whether a glyph is selected by this set.
+	pub fn contains(&self, glyph: &Glyph) -> bool {
+		self.encodings.contains(&glyph.encoding)
+			|| self
+				.ranges
+				.iter()
+				.any(|range| range.contains(&glyph.encoding))
+			|| self.names.contains(&glyph.name)
+	}
+}
+
+impl<I: GlyphIdx> FromIterator<I> for CharSet {
+	fn from_iter<T: IntoIterator<Item = I>>(iter: T) -> Self {
+		Self {
+			encodings: iter.into_iter().map(GlyphIdx::encoding).collect(),
+			..Self::default()
+		}
+	}
+}
+
+impl Font {
+	/// Create a new font containing only the glyphs selected by `chars` and the
+	/// [default glyph](Self::default_glyph).
+	///
+	/// Selected characters that are missing from the font but can be synthesized from
+	/// their canonical decomposition keep the glyphs of the decomposition. The font
+	/// bounding box is recomputed from the remaining glyphs, and the `DEFAULT_CHAR`
+	/// property is removed if it does not reference a glyph of the subset.
+	pub fn subset(&self, chars: &CharSet) -> Font {
+		let mut encodings: BTreeSet<u32> = self
+			.glyphs()
+			.into_iter()
+			.filter(|glyph| chars.contains(glyph))
+			.map(|glyph| glyph.encoding)
+			.collect();
+		for enc in &chars.encodings {
+			if self.glyph(*enc).is_some() {
+				continue;
+			}
+			if let Some(ch) = char::from_u32(*enc) {
+				decompose_canonical(ch, |part| {
+					if self.glyph(part).is_some() {
+						encodings.insert(part.into());
+					}
+				});
+			}
+		}
+		if let Some(glyph) = self.default_glyph() {
+			encodings.insert(glyph.encoding);
+		}
+
+		let glyphs: BTreeSet<GlyphWrapper> = encodings
+			.into_iter()
+			.filter_map(|enc| self.glyph(enc))
+			.map(|glyph| glyph.clone().into())
+			.collect();
+		let bbox = glyphs
+			.iter()
+			.fold(BoundingBox::default(), |bbox, gw| bbox.union(gw.0.bbox));
+
+		let mut properties = self.properties.clone();
+		if let Some(Value::Integer(enc)) = properties.get("DEFAULT_CHAR") {
+			let contained = u32::try_from(*enc).is_ok_and(|enc| glyphs.contains(&enc));
+			if !contained {
+				properties.remove("DEFAULT_CHAR");
+			}
+		}
+
+		Font {
+			version: self.version,
+			name: self.name.clone(),
+			bbox,
+			size: self.size,
+			properties,
+			glyphs
+		}
+	}
+}
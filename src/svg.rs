@@ -0,0 +1,162 @@
+//! SVG output of bitmaps, with the pixels merged into rectilinear paths.
+
+use crate::{Bitmap, BoundingBox, Layout};
+use std::{
+	fmt::Write as _,
+	io::{self, Write}
+};
+
+/// Options for writing SVG images.
+#[derive(Clone, Debug)]
+pub struct SvgOptions {
+	/// The size of a pixel in the `width` and `height` of the image. The paths are in
+	/// pixel units, so the image can be scaled freely.
+	pub scale: u32,
+
+	/// The fill color of set pixels, e.g. `#000` or `currentColor`.
+	pub foreground: String,
+
+	/// The fill color of the background, or `None` for a transparent background.
+	pub background: Option<String>,
+
+	/// Whether to draw lines between all pixels.
+	pub grid: bool,
+
+	/// Whether to draw the baseline.
+	pub baseline: bool,
+
+	/// Whether to draw the bounding boxes of the glyphs.
+	pub bounding_box: bool
+}
+
+impl Default for SvgOptions {
+	fn default() -> Self {
+		Self {
+			scale: 8,
+			foreground: "#000".to_owned(),
+			background: None,
+			grid: false,
+			baseline: false,
+			bounding_box: false
+		}
+	}
+}
+
+/// The stroke attributes of overlays, which are one screen pixel wide at any scale.
+fn stroke(color: &str) -> String {
+	format!(
+		"fill=\"none\" stroke=\"{color}\" stroke-width=\"1\" \
+		 vector-effect=\"non-scaling-stroke\""
+	)
+}
+
+/// Escape a string for use in an attribute value.
+fn escape(value: &str) -> String {
+	value
+		.replace('&', "&amp;")
+		.replace('"', "&quot;")
+		.replace('<', "&lt;")
+}
+
+/// Write a bitmap as SVG, with `boxes` drawn as bounding box overlays. The SVG
+/// coordinates are pixels with the y axis pointing down, so that the origin of the
+/// bitmap is at (0, 0).
+fn write_svg<W: Write>(
+	mut writer: W,
+	bitmap: Bitmap<'_>,
+	boxes: &[BoundingBox],
+	options: &SvgOptions
+) -> io::Result<()> {
+	let bbox = bitmap.bbox;
+	let (left, top) = (bbox.offset_x, -(bbox.offset_y + bbox.height as i32));
+	let (right, bottom) = (left + bbox.width as i32, -bbox.offset_y);
+	writeln!(
+		writer,
+		"<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+		 viewBox=\"{left} {top} {} {}\">",
+		bbox.width * options.scale,
+		bbox.height * options.scale,
+		bbox.width,
+		bbox.height
+	)?;
+	if let Some(background) = &options.background {
+		writeln!(
+			writer,
+			"<rect x=\"{left}\" y=\"{top}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+			bbox.width,
+			bbox.height,
+			escape(background)
+		)?;
+	}
+
+	// the corners alternate between horizontal and vertical moves
+	let mut path = String::new();
+	for contour in bitmap.contours() {
+		let Some(&(x, y)) = contour.first() else {
+			continue;
+		};
+		write!(path, "M{x} {}", -y).unwrap();
+		for window in contour.windows(2) {
+			let ((x0, _), (x1, y1)) = (window[0], window[1]);
+			match x0 == x1 {
+				true => write!(path, "V{}", -y1).unwrap(),
+				false => write!(path, "H{x1}").unwrap()
+			}
+		}
+		path += "Z";
+	}
+	if !path.is_empty() {
+		writeln!(
+			writer,
+			"<path d=\"{path}\" fill=\"{}\"/>",
+			escape(&options.foreground)
+		)?;
+	}
+
+	if options.grid {
+		let mut grid = String::new();
+		for x in left ..= right {
+			write!(grid, "M{x} {top}V{bottom}").unwrap();
+		}
+		for y in top ..= bottom {
+			write!(grid, "M{left} {y}H{right}").unwrap();
+		}
+		writeln!(writer, "<path d=\"{grid}\" {}/>", stroke("#ccc"))?;
+	}
+	if options.baseline && top <= 0 && bottom >= 0 {
+		writeln!(writer, "<path d=\"M{left} 0H{right}\" {}/>", stroke("#f00"))?;
+	}
+	if options.bounding_box {
+		for bbox in boxes.iter().filter(|bbox| !bbox.is_empty()) {
+			writeln!(
+				writer,
+				"<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" {}/>",
+				bbox.offset_x,
+				-(bbox.offset_y + bbox.height as i32),
+				bbox.width,
+				bbox.height,
+				stroke("#00f")
+			)?;
+		}
+	}
+	writeln!(writer, "</svg>")
+}
+
+impl Bitmap<'_> {
+	/// Write this bitmap as an SVG image. Adjacent set pixels are merged into paths
+	/// along their outlines, and the overlays are clipped to the bounding box of the
+	/// bitmap.
+	pub fn write_svg<W: Write>(self, writer: W, options: &SvgOptions) -> io::Result<()> {
+		write_svg(writer, self, &[self.bbox], options)
+	}
+}
+
+impl Layout<'_> {
+	/// Render this layout and write it as an SVG image, like [`Bitmap::write_svg`].
+	/// The bounding box overlay shows the bounding box of each glyph.
+	pub fn write_svg<W: Write>(&self, writer: W, options: &SvgOptions) -> io::Result<()> {
+		let boxes: Vec<BoundingBox> =
+			self.glyphs().iter().map(|pg| pg.bounding_box()).collect();
+		write_svg(writer, self.render().as_bitmap(), &boxes, options)
+	}
+}
@@ -0,0 +1,118 @@
+//! Minimal support for Truevision TGA images.
+
+const TGA_HEADER_SIZE: usize = 18;
+
+/// The image descriptor flag for images stored top to bottom.
+const TOP_TO_BOTTOM: u8 = 0x20;
+
+#[derive(Debug)]
+pub(crate) enum TgaError {
+	UnexpectedEof,
+	Unsupported(&'static str)
+}
+
+/// An image with RGBA pixels, stored row by row from the top left corner.
+pub(crate) struct Image {
+	pub(crate) width: usize,
+	pub(crate) height: usize,
+	pub(crate) pixels: Vec<[u8; 4]>,
+
+	/// Whether the image has an alpha channel, otherwise all pixels are opaque.
+	pub(crate) has_alpha: bool,
+
+	/// Whether the image is grayscale. The value of each pixel is stored in all
+	/// channels, including alpha.
+	#[cfg_attr(not(feature = "sprite-sheet"), allow(dead_code))]
+	pub(crate) gray: bool
+}
+
+/// Encode RGBA pixels, stored row by row from the top left corner, as an uncompressed
+/// 32 bit TGA image.
+pub(crate) fn encode(width: u16, height: u16, pixels: &[[u8; 4]]) -> Vec<u8> {
+	let mut buf = Vec::with_capacity(TGA_HEADER_SIZE + pixels.len() * 4);
+	buf.extend([0, 0, 2]); // no id, no color map, uncompressed true color
+	buf.extend([0; 9]);
+	buf.extend(width.to_le_bytes());
+	buf.extend(height.to_le_bytes());
+	buf.extend([32, TOP_TO_BOTTOM | 8]);
+	for [r, g, b, a] in pixels {
+		buf.extend([*b, *g, *r, *a]);
+	}
+	buf
+}
+
+/// Decode an uncompressed or run-length encoded grayscale or true color image.
+pub(crate) fn decode(data: &[u8]) -> Result<Image, TgaError> {
+	let header = data
+		.get(.. TGA_HEADER_SIZE)
+		.ok_or(TgaError::UnexpectedEof)?;
+	let id_len = usize::from(header[0]);
+	if header[1] != 0 {
+		return Err(TgaError::Unsupported("color mapped images"));
+	}
+	let (gray, rle) = match header[2] {
+		2 => (false, false),
+		3 => (true, false),
+		10 => (false, true),
+		11 => (true, true),
+		_ => return Err(TgaError::Unsupported("unknown image type"))
+	};
+	let width = usize::from(u16::from_le_bytes([header[12], header[13]]));
+	let height = usize::from(u16::from_le_bytes([header[14], header[15]]));
+	let depth = usize::from(header[16]);
+	let pixel_size = match (gray, depth) {
+		(true, 8) => 1,
+		(false, 24) => 3,
+		(false, 32) => 4,
+		_ => return Err(TgaError::Unsupported("unsupported pixel depth"))
+	};
+	let pixel = |bytes: &[u8]| match bytes {
+		[v] => [*v, *v, *v, *v],
+		[b, g, r] => [*r, *g, *b, 0xFF],
+		[b, g, r, a] => [*r, *g, *b, *a],
+		_ => unreachable!()
+	};
+
+	let mut data = data
+		.get(TGA_HEADER_SIZE + id_len ..)
+		.ok_or(TgaError::UnexpectedEof)?;
+	let mut take = |len: usize| {
+		let bytes = data.get(.. len).ok_or(TgaError::UnexpectedEof)?;
+		data = &data[len ..];
+		Ok::<_, TgaError>(bytes)
+	};
+	let count = width * height;
+	let mut pixels = Vec::new();
+	while pixels.len() < count {
+		match rle {
+			false => pixels.push(pixel(take(pixel_size)?)),
+			true => {
+				let packet = take(1)?[0];
+				let len = usize::from(packet & 0x7F) + 1;
+				match packet & 0x80 != 0 {
+					true => {
+						let value = pixel(take(pixel_size)?);
+						pixels.extend((0 .. len).map(|_| value));
+					},
+					false => {
+						for _ in 0 .. len {
+							pixels.push(pixel(take(pixel_size)?));
+						}
+					},
+				}
+			}
+		}
+	}
+	pixels.truncate(count);
+
+	if header[17] & TOP_TO_BOTTOM == 0 && width > 0 {
+		pixels = pixels.chunks(width).rev().flatten().copied().collect();
+	}
+	Ok(Image {
+		width,
+		height,
+		pixels,
+		has_alpha: gray || depth == 32,
+		gray
+	})
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use bdf_reader::{AtlasError, AtlasOptions, CharSet};
+use common::sample_font;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn atlas() {
+	let font = sample_font();
+	let atlas = font.atlas(&AtlasOptions::default()).unwrap();
+	assert_eq!((atlas.width(), atlas.height()), (256, 256));
+	assert_eq!(atlas.glyphs().len(), 9);
+
+	let a = atlas.glyph('a').unwrap();
+	assert_eq!((a.width, a.height), (5, 4));
+	assert_eq!((a.bearing_x, a.bearing_y, a.advance), (1, 4, 8));
+	assert_eq!(a.uv.u_min, a.x as f32 / 256.0);
+	assert_eq!(a.uv.v_max, (a.y + 4) as f32 / 256.0);
+
+	// every glyph bitmap can be read back from the rendered atlas
+	let alpha = atlas.render();
+	assert_eq!(alpha.len(), 256 * 256);
+	for ag in atlas.glyphs() {
+		let bitmap = ag.glyph.bitmap();
+		for y in 0 .. bitmap.height() {
+			for x in 0 .. bitmap.width() {
+				let idx = (ag.y as usize + y) * 256 + ag.x as usize + x;
+				assert_eq!(alpha[idx] == 0xFF, bitmap.get(x, y).unwrap());
+			}
+		}
+	}
+	let set = font
+		.glyphs()
+		.into_iter()
+		.map(|glyph| glyph.bitmap())
+		.map(|bitmap| {
+			(0 .. bitmap.height())
+				.flat_map(|y| (0 .. bitmap.width()).map(move |x| (x, y)))
+				.filter(|(x, y)| bitmap.get(*x, *y).unwrap())
+				.count()
+		})
+		.sum::<usize>();
+	assert_eq!(alpha.iter().filter(|a| **a == 0xFF).count(), set);
+}
+
+#[test]
+fn atlas_subset() {
+	let font = sample_font();
+	let atlas = font
+		.atlas_subset(&CharSet::from_text("ai"), &AtlasOptions::default())
+		.unwrap();
+	let encodings: Vec<u32> = atlas
+		.glyphs()
+		.iter()
+		.map(|ag| ag.glyph.encoding())
+		.collect();
+	assert_eq!(encodings, vec![97, 105]);
+	assert!(atlas.glyph('A').is_none());
+}
+
+#[test]
+fn atlas_grow() {
+	let font = sample_font();
+	let options = AtlasOptions {
+		width: 16,
+		height: 16,
+		..Default::default()
+	};
+	let result = font.atlas(&options);
+	assert!(matches!(result, Err(AtlasError::AtlasFull(16, 16))));
+
+	let atlas = font
+		.atlas(&AtlasOptions {
+			grow: true,
+			..options
+		})
+		.unwrap();
+	assert_eq!((atlas.width(), atlas.height()), (32, 32));
+	let glyphs = atlas.glyphs();
+	for (i, a) in glyphs.iter().enumerate() {
+		assert!(a.x + a.width < atlas.width() && a.y + a.height < atlas.height());
+		for b in &glyphs[i + 1 ..] {
+			let overlaps = a.x < b.x + b.width
+				&& b.x < a.x + a.width
+				&& a.y < b.y + b.height
+				&& b.y < a.y + a.height;
+			assert!(!overlaps);
+		}
+	}
+
+	let result = font.atlas(&AtlasOptions {
+		grow: true,
+		padding: 3,
+		max_size: 32,
+		..options
+	});
+	assert!(matches!(result, Err(AtlasError::AtlasFull(32, 32))));
+	let result = font.atlas(&AtlasOptions {
+		width: 8,
+		..options
+	});
+	assert!(matches!(result, Err(AtlasError::GlyphTooLarge(32))));
+}
@@ -0,0 +1,174 @@
+mod common;
+
+use bdf_reader::{BmFontError, BmFontFormat, BmFontOptions, Font, Value};
+use common::sample_font;
+use pretty_assertions::assert_eq;
+use std::{fs, path::PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+	let dir =
+		std::env::temp_dir().join(format!("bdf-reader-{name}-{}", std::process::id()));
+	fs::create_dir_all(&dir).unwrap();
+	dir
+}
+
+fn assert_same_glyphs(read: &Font, font: &Font) {
+	let glyphs: Vec<_> = font.glyphs().into_iter().collect();
+	let read_glyphs: Vec<_> = read.glyphs().into_iter().collect();
+	assert_eq!(read_glyphs.len(), glyphs.len());
+	for (read, glyph) in read_glyphs.into_iter().zip(glyphs) {
+		assert_eq!(read.encoding(), glyph.encoding());
+		assert_eq!(read.dwidth(), glyph.dwidth());
+		assert_eq!(read.bounding_box(), glyph.bounding_box());
+		assert_eq!(read.bitmap().ascii_art(), glyph.bitmap().ascii_art());
+	}
+}
+
+#[test]
+fn bmfont_round_trip() {
+	let font = sample_font();
+	let dir = temp_dir("bmfont");
+	for format in [BmFontFormat::Text, BmFontFormat::Xml, BmFontFormat::Binary] {
+		let path = dir.join("sample.fnt");
+		let options = BmFontOptions {
+			format,
+			..Default::default()
+		};
+		font.write_bmfont(&path, &options).unwrap();
+		assert!(dir.join("sample_0.tga").exists());
+
+		let read = Font::read_bmfont(&path).unwrap();
+		assert_eq!(
+			read.name(),
+			"-misc-Sample-Medium-R-Normal--8-80-72-72-P-53-iso10646-1",
+			"{format:?}"
+		);
+		for (key, value) in [
+			("FAMILY_NAME", Value::String("Sample".into())),
+			("PIXEL_SIZE", Value::Integer(8)),
+			("FONT_ASCENT", Value::Integer(6)),
+			("FONT_DESCENT", Value::Integer(2))
+		] {
+			assert_eq!(read.property(key), Some(&value), "{format:?} {key}");
+		}
+		assert_same_glyphs(&read, &font);
+	}
+
+	let path = dir.join("sample.fnt");
+	assert!(fs::read(&path).unwrap().starts_with(b"BMF\x03"));
+	font.write_bmfont(&path, &BmFontOptions::default()).unwrap();
+	let text = fs::read_to_string(&path).unwrap();
+	let lines: Vec<&str> = text.lines().take(4).collect();
+	assert_eq!(lines, vec![
+		"info face=\"Sample\" size=8 bold=0 italic=0 charset=\"\" unicode=1 stretchH=100 \
+		 smooth=0 aa=1 padding=0,0,0,0 spacing=1,1 outline=0",
+		"common lineHeight=8 base=6 scaleW=256 scaleH=256 pages=1 packed=0 alphaChnl=0 \
+		 redChnl=4 greenChnl=4 blueChnl=4",
+		"page id=0 file=\"sample_0.tga\"",
+		"chars count=9"
+	]);
+	assert!(text
+		.contains("char id=97 x=57 y=1 width=5 height=4 xoffset=1 yoffset=2 xadvance=8"));
+	fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn bmfont_multiple_pages() {
+	let font = sample_font();
+	let dir = temp_dir("bmfont-pages");
+	let path = dir.join("small.fnt");
+	let options = BmFontOptions {
+		page_width: 20,
+		page_height: 12,
+		padding: 2,
+		..Default::default()
+	};
+	font.write_bmfont(&path, &options).unwrap();
+	let text = fs::read_to_string(&path).unwrap();
+	assert!(text.contains(" pages=7 "));
+	assert!(dir.join("small_6.tga").exists());
+	assert_same_glyphs(&Font::read_bmfont(&path).unwrap(), &font);
+
+	let options = BmFontOptions {
+		page_width: 9,
+		..options
+	};
+	let result = font.write_bmfont(&path, &options);
+	assert!(matches!(result, Err(BmFontError::GlyphTooLarge(32))));
+	fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn bmfont_quoted_face() {
+	let bdf = fs::read_to_string("tests/sample.bdf").unwrap().replace(
+		"FAMILY_NAME \"Sample\"",
+		"FAMILY_NAME \"Bob's ''Mono'' Sans\""
+	);
+	let font = Font::read(bdf.as_bytes()).unwrap();
+	let dir = temp_dir("bmfont-quoted");
+	let path = dir.join("quoted.fnt");
+
+	// the text format cannot represent the quotes
+	let result = font.write_bmfont(&path, &BmFontOptions::default());
+	assert!(matches!(result, Err(BmFontError::ValueOutOfRange("face"))));
+
+	let options = BmFontOptions {
+		format: BmFontFormat::Xml,
+		..Default::default()
+	};
+	font.write_bmfont(&path, &options).unwrap();
+	let read = Font::read_bmfont(&path).unwrap();
+	assert_eq!(
+		read.property("FAMILY_NAME"),
+		Some(&Value::String("Bob's \"Mono\" Sans".into()))
+	);
+	fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn bmfont_out_of_range() {
+	let font = sample_font();
+	let dir = temp_dir("bmfont-range");
+	let path = dir.join("range.fnt");
+	let options = BmFontOptions {
+		format: BmFontFormat::Binary,
+		page_width: 1024,
+		page_height: 1024,
+		padding: 256
+	};
+	let result = font.write_bmfont(&path, &options);
+	assert!(matches!(
+		result,
+		Err(BmFontError::ValueOutOfRange("spacing"))
+	));
+
+	fs::write(
+		&path,
+		"info face=\"Huge\" size=-2147483648\ncommon base=6\n"
+	)
+	.unwrap();
+	let result = Font::read_bmfont(&path);
+	assert!(matches!(
+		result,
+		Err(BmFontError::ValueOutOfRange("font size"))
+	));
+	fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn read_bmfont_truncated_page() {
+	let font = sample_font();
+	let dir = temp_dir("bmfont-truncated");
+	let path = dir.join("sample.fnt");
+	font.write_bmfont(&path, &BmFontOptions::default()).unwrap();
+
+	// a page header claiming the largest possible image, without any pixels
+	let mut tga = vec![
+		0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF, 32, 8,
+	];
+	tga.extend_from_slice(&[0, 0]);
+	fs::write(dir.join("sample_0.tga"), tga).unwrap();
+	let result = Font::read_bmfont(&path);
+	assert!(matches!(result, Err(BmFontError::UnexpectedEof)));
+	fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,8 @@
+use bdf_reader::Font;
+use std::{fs::File, io::BufReader};
+
+/// Read the small hand-written font used by most tests.
+pub fn sample_font() -> Font {
+	let reader = BufReader::new(File::open("tests/sample.bdf").unwrap());
+	Font::read(reader).unwrap()
+}
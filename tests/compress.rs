@@ -0,0 +1,90 @@
+#![cfg(feature = "gzip")]
+
+mod common;
+
+use bdf_reader::{Font, PcfOptions};
+use common::sample_font;
+use flate2::{write::GzEncoder, Compression};
+use std::{
+	fs,
+	io::{BufReader, Write}
+};
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+	let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+	encoder.write_all(data).unwrap();
+	encoder.finish().unwrap()
+}
+
+/// Summarize a font so that two fonts can be compared.
+fn summary(font: &Font) -> (String, Vec<(u32, String)>) {
+	let glyphs = font
+		.glyphs()
+		.into_iter()
+		.map(|glyph| (glyph.encoding(), glyph.bitmap().ascii_art()))
+		.collect();
+	(font.name().to_owned(), glyphs)
+}
+
+#[test]
+fn read_gzip() {
+	let bdf = fs::read("tests/sample.bdf").unwrap();
+	let font = Font::read(gzip(&bdf).as_slice()).unwrap();
+	assert_eq!(summary(&font), summary(&sample_font()));
+
+	let mut pcf = Vec::new();
+	font.write_pcf(&mut pcf, &PcfOptions::default()).unwrap();
+	let read = Font::read_pcf(gzip(&pcf).as_slice()).unwrap();
+	assert_eq!(summary(&read), summary(&font));
+}
+
+#[test]
+fn read_gzip_short_reads() {
+	// a reader that only returns a single byte at a time
+	let bdf = fs::read("tests/sample.bdf").unwrap();
+	let gz = gzip(&bdf);
+	let font = Font::read(BufReader::with_capacity(1, gz.as_slice())).unwrap();
+	assert_eq!(summary(&font), summary(&sample_font()));
+}
+
+#[test]
+fn open_detects_format() {
+	let font = sample_font();
+	let mut pcf = Vec::new();
+	font.write_pcf(&mut pcf, &PcfOptions::default()).unwrap();
+	let expected = summary(&font);
+
+	let dir = std::env::temp_dir().join(format!("bdf-reader-{}", std::process::id()));
+	fs::create_dir_all(&dir).unwrap();
+	let bdf = fs::read("tests/sample.bdf").unwrap();
+	fs::write(dir.join("sample.bdf.gz"), gzip(&bdf)).unwrap();
+	fs::write(dir.join("sample.pcf"), &pcf).unwrap();
+	fs::write(dir.join("sample.pcf.gz"), gzip(&pcf)).unwrap();
+	let mut pf2 = Vec::new();
+	font.write_pf2(&mut pf2).unwrap();
+	fs::write(dir.join("sample.pf2"), &pf2).unwrap();
+
+	assert_eq!(summary(&Font::open("tests/sample.bdf").unwrap()), expected);
+	assert_eq!(
+		summary(&Font::open(dir.join("sample.bdf.gz")).unwrap()),
+		expected
+	);
+	assert_eq!(
+		summary(&Font::open(dir.join("sample.pcf")).unwrap()),
+		expected
+	);
+	assert_eq!(
+		summary(&Font::open(dir.join("sample.pcf.gz")).unwrap()),
+		expected
+	);
+	// PF2 fonts do not store an XLFD name
+	assert_eq!(
+		summary(&Font::open(dir.join("sample.pf2")).unwrap()).1,
+		expected.1
+	);
+
+	// the input is only decompressed once
+	fs::write(dir.join("sample.bdf.gz.gz"), gzip(&gzip(&bdf))).unwrap();
+	assert!(Font::open(dir.join("sample.bdf.gz.gz")).is_err());
+	fs::remove_dir_all(dir).unwrap();
+}
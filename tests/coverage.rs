@@ -0,0 +1,50 @@
+mod common;
+
+use bdf_reader::CharSet;
+use common::sample_font;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn coverage_by_block_and_script() {
+	let coverage = sample_font().coverage();
+
+	let blocks: Vec<_> = coverage
+		.blocks
+		.iter()
+		.map(|block| (block.name, block.assigned, block.covered))
+		.collect();
+	assert_eq!(blocks, [
+		("Basic Latin", 128, 4),
+		("Combining Diacritical Marks", 112, 3),
+		("General Punctuation", 111, 1),
+		("Specials", 5, 1)
+	]);
+
+	let scripts: Vec<_> = coverage
+		.scripts
+		.iter()
+		.map(|script| (script.name, script.covered))
+		.collect();
+	assert_eq!(scripts, [("Common", 3), ("Inherited", 3), ("Latin", 3)]);
+	assert!(coverage.unassigned.is_empty());
+}
+
+#[test]
+fn missing_chars() {
+	let font = sample_font();
+	let mut repertoire = CharSet::from_text("Ai!");
+	repertoire.insert_range(0x300 ..= 0x302);
+	assert_eq!(font.missing_chars(&repertoire), [0x21, 0x302]);
+}
+
+#[test]
+fn compare_coverage() {
+	let font = sample_font();
+	let subset = font.subset(&CharSet::from_text("Ab"));
+	let cmp = subset.compare_coverage(&font);
+	assert_eq!(cmp.common, [0x41, 0xFFFD]);
+	assert!(cmp.only_first.is_empty());
+	assert_eq!(cmp.only_second, [
+		0x20, 0x61, 0x69, 0x300, 0x301, 0x308, 0x200B
+	]);
+}
@@ -0,0 +1,202 @@
+mod common;
+
+use bdf_reader::{BoundingBox, FntError, FntVersion, Font, Value};
+use common::sample_font;
+use indoc::indoc;
+use pretty_assertions::assert_eq;
+
+const FONT: &str = indoc! {r#"
+	STARTFONT 2.1
+	FONT -Test-Raster
+	SIZE 6 96 96
+	FONTBOUNDINGBOX 5 8 0 -2
+	STARTPROPERTIES 8
+	FAMILY_NAME "Raster"
+	WEIGHT_NAME "Bold"
+	PIXEL_SIZE 7
+	CHARSET_REGISTRY "microsoft"
+	CHARSET_ENCODING "cp1251"
+	FONT_ASCENT 6
+	FONT_DESCENT 2
+	DEFAULT_CHAR 65
+	ENDPROPERTIES
+	CHARS 3
+	STARTCHAR space
+	ENCODING 32
+	SWIDTH 500 0
+	DWIDTH 4 0
+	BBX 0 0 0 0
+	BITMAP
+	ENDCHAR
+	STARTCHAR A
+	ENCODING 65
+	SWIDTH 625 0
+	DWIDTH 5 0
+	BBX 5 6 0 0
+	BITMAP
+	20
+	50
+	88
+	F8
+	88
+	88
+	ENDCHAR
+	STARTCHAR g
+	ENCODING 103
+	SWIDTH 500 0
+	DWIDTH 4 0
+	BBX 3 5 0 -2
+	BITMAP
+	60
+	A0
+	60
+	20
+	C0
+	ENDCHAR
+	ENDFONT
+"#};
+
+fn ascii_art(font: &Font, enc: u32) -> String {
+	font.glyph(enc).unwrap().bitmap().ascii_art()
+}
+
+/// Wrap FNT resources into a minimal NE executable.
+fn fon(resources: &[&[u8]]) -> Vec<u8> {
+	let mut exe = vec![0u8; 0x40];
+	exe[.. 2].copy_from_slice(b"MZ");
+	exe[0x3C] = 0x40;
+	let mut ne = vec![0u8; 0x40];
+	ne[.. 2].copy_from_slice(b"NE");
+	ne[0x24] = 0x40;
+	exe.extend(ne);
+
+	let align_shift = 4u16;
+	let mut table = Vec::new();
+	table.extend(align_shift.to_le_bytes());
+	table.extend(0x8008u16.to_le_bytes());
+	table.extend((resources.len() as u16).to_le_bytes());
+	table.extend([0; 4]);
+	let mut offset = 0x100;
+	for res in resources {
+		table.extend(((offset >> align_shift) as u16).to_le_bytes());
+		table.extend((res.len().div_ceil(16) as u16).to_le_bytes());
+		table.extend([0; 8]);
+		offset += res.len().div_ceil(16) * 16;
+	}
+	table.extend([0; 2]);
+	exe.extend(table);
+
+	for res in resources {
+		exe.resize(exe.len().div_ceil(16) * 16, 0);
+		exe.resize(exe.len().max(0x100), 0);
+		exe.extend(*res);
+	}
+	exe
+}
+
+#[test]
+fn fnt_round_trip() {
+	let font = Font::read(FONT.as_bytes()).unwrap();
+	for version in [FntVersion::V2, FntVersion::V3] {
+		let mut fnt = Vec::new();
+		font.write_fnt(&mut fnt, version).unwrap();
+		let read = Font::read_fnt(fnt.as_slice()).unwrap();
+
+		assert_eq!(
+			read.name(),
+			"-misc-Raster-Bold-R-Normal--7-60-96-96-P-40-microsoft-cp1251"
+		);
+		assert_eq!(read.bounding_box(), BoundingBox {
+			width: 5,
+			height: 8,
+			offset_x: 0,
+			offset_y: -2
+		});
+		for (key, value) in [
+			("PIXEL_SIZE", Value::Integer(7)),
+			("FONT_ASCENT", Value::Integer(6)),
+			("FONT_DESCENT", Value::Integer(2)),
+			("DEFAULT_CHAR", Value::Integer(65)),
+			("CHARSET_ENCODING", Value::String("cp1251".into()))
+		] {
+			assert_eq!(read.property(key), Some(&value), "{key}");
+		}
+
+		// all characters between the first and the last one are present
+		assert_eq!(read.glyphs().into_iter().count(), 103 - 32 + 1);
+		assert_eq!(read.glyph('B').unwrap().dwidth(), Some((5.0, 0.0)));
+		assert_eq!(ascii_art(&read, 66), ascii_art(&read, 65));
+		assert_eq!(read.glyph('g').unwrap().dwidth(), Some((4.0, 0.0)));
+		assert_eq!(ascii_art(&read, 103), indoc! {"
+			........
+			........
+			........
+			..####..
+			##..##..
+			..####..
+			--------
+			....##..
+			####....
+		"});
+
+		let fonts = Font::read_fon(fon(&[&fnt, &fnt]).as_slice()).unwrap();
+		assert_eq!(fonts.len(), 2);
+		assert_eq!(ascii_art(&fonts[1], 65), ascii_art(&read, 65));
+	}
+}
+
+#[test]
+fn write_fnt_unicode() {
+	// the euro sign is at 0x80 in code page 1252, where Unicode has a control character
+	let font = Font::read(
+		FONT.replace("\"microsoft\"", "\"ISO10646\"")
+			.replace("\"cp1251\"", "\"1\"")
+			.replace("ENCODING 103", "ENCODING 8364")
+			.as_bytes()
+	)
+	.unwrap();
+	let mut fnt = Vec::new();
+	font.write_fnt(&mut fnt, FntVersion::V3).unwrap();
+	let read = Font::read_fnt(fnt.as_slice()).unwrap();
+	assert_eq!(
+		read.property("CHARSET_ENCODING"),
+		Some(&Value::String("cp1252".into()))
+	);
+	assert_eq!(read.glyphs().into_iter().count(), 0x80 - 32 + 1);
+	assert_eq!(ascii_art(&read, 0x80), indoc! {"
+		........
+		........
+		........
+		..####..
+		##..##..
+		..####..
+		--------
+		....##..
+		####....
+	"});
+}
+
+#[test]
+fn write_fnt_invalid() {
+	let font = sample_font();
+	let mut fnt = Vec::new();
+	font.write_fnt(&mut fnt, FntVersion::V2).unwrap();
+	let read = Font::read_fnt(fnt.as_slice()).unwrap();
+	assert_eq!(read.glyph('B').unwrap().dwidth(), Some((0.0, 0.0)));
+
+	let font =
+		Font::read(FONT.replace("BBX 3 5 0 -2", "BBX 3 5 2 -2").as_bytes()).unwrap();
+	let err = font.write_fnt(Vec::new(), FntVersion::V2).unwrap_err();
+	assert!(matches!(err, FntError::GlyphOutsideCell(103)));
+
+	let err = Font::read_fon(&b"MZ"[..]);
+	assert!(matches!(err, Err(FntError::UnexpectedEof)));
+
+	// an alignment shift that overflows, and resources beyond the end of the file
+	for align_shift in [64u16, 16] {
+		let mut exe = fon(&[&fnt]);
+		exe[0x80 .. 0x82].copy_from_slice(&align_shift.to_le_bytes());
+		let err = Font::read_fon(exe.as_slice());
+		assert!(matches!(err, Err(FntError::InvalidExecutable)));
+	}
+}
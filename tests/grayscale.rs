@@ -0,0 +1,77 @@
+mod common;
+
+use bdf_reader::{BoundingBox, GrayscaleOptions};
+use common::sample_font;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn render_grayscale() {
+	let font = sample_font();
+	let bitmap = font.glyph('a').unwrap().bitmap();
+	let gray = bitmap.render_grayscale(&GrayscaleOptions::default());
+	assert_eq!(gray.bounding_box(), bitmap.bounding_box());
+	let expected: Vec<u8> = (0 .. bitmap.height())
+		.flat_map(|y| (0 .. bitmap.width()).map(move |x| (x, y)))
+		.map(|(x, y)| match bitmap.get(x, y).unwrap() {
+			true => 255,
+			false => 0
+		})
+		.collect();
+	assert_eq!(gray.data(), expected);
+}
+
+#[test]
+fn render_grayscale_downscaled() {
+	let font = sample_font();
+	let bitmap = font.glyph('i').unwrap().bitmap();
+	let options = GrayscaleOptions {
+		scale: 0.5,
+		..Default::default()
+	};
+	let gray = bitmap.render_grayscale(&options);
+	assert_eq!(gray.bounding_box(), BoundingBox {
+		width: 1,
+		height: 3,
+		offset_x: 0,
+		offset_y: 0
+	});
+	// the dot, the top of the stem, and the bottom of the stem
+	assert_eq!(gray.data(), [64, 64, 128]);
+
+	let options = GrayscaleOptions {
+		gamma: 2.0,
+		..options
+	};
+	assert_eq!(bitmap.render_grayscale(&options).data(), [128, 128, 180]);
+	assert_eq!(gray.get(0, 3), None);
+}
+
+#[test]
+fn render_layout_grayscale() {
+	let font = sample_font();
+	let layout = font.layout("Aai");
+	let options = GrayscaleOptions {
+		scale: 0.75,
+		..Default::default()
+	};
+	let gray = layout.render_grayscale(&options);
+	// the layout covers 18x8 pixels starting at (0, -2)
+	assert_eq!(gray.bounding_box(), BoundingBox {
+		width: 14,
+		height: 7,
+		offset_x: 0,
+		offset_y: -2
+	});
+
+	// the covered area is scaled with the square of the scale
+	let bitmap = layout.render();
+	let ink = bitmap.as_bitmap().ascii_art().matches("##").count() as f64;
+	let coverage: f64 = gray.data().iter().map(|v| *v as f64 / 255.0).sum();
+	assert!((coverage - ink * 0.75 * 0.75).abs() < 0.1, "{coverage}");
+
+	let options = GrayscaleOptions {
+		scale: 0.0,
+		..Default::default()
+	};
+	assert_eq!(layout.render_grayscale(&options).width(), 0);
+}
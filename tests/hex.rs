@@ -0,0 +1,78 @@
+mod common;
+
+use bdf_reader::{BoundingBox, Font, HexError};
+use common::sample_font;
+use pretty_assertions::assert_eq;
+
+const HEX: &str = "\
+0041:0000000018242442427E424242420000
+0301:000C1800000000000000000000000000
+4E00:00000000000000007FFE00000000000000000000000000000000000000000000
+01F600:0000000000000000000000000000000000000000000000000000000000000000
+";
+
+const COMBINING: &str = "0301:-8\n";
+
+#[test]
+fn read_hex_odd_digits() {
+	// the CJK glyph has an odd number of digits
+	let hex = format!("0041:{}\n4E00:{}\n", "0".repeat(32), "0".repeat(63));
+	let err = Font::read_hex(hex.as_bytes());
+	assert!(matches!(err, Err(HexError::SyntaxError(2))));
+}
+
+#[test]
+fn read_hex_signs() {
+	let hex = format!("+041:{}\n", "0".repeat(32));
+	let err = Font::read_hex(hex.as_bytes());
+	assert!(matches!(err, Err(HexError::SyntaxError(1))));
+
+	let hex = format!("0041:+0{}\n", "0".repeat(30));
+	let err = Font::read_hex(hex.as_bytes());
+	assert!(matches!(err, Err(HexError::SyntaxError(1))));
+}
+
+#[test]
+fn read_write_hex() {
+	let mut font = Font::read_hex(HEX.as_bytes()).unwrap();
+	font.read_hex_combining(COMBINING.as_bytes()).unwrap();
+
+	let a = font.glyph('A').unwrap();
+	assert_eq!(a.name(), "uni0041");
+	assert_eq!(a.dwidth(), Some((8.0, 0.0)));
+	assert_eq!(a.bounding_box(), BoundingBox {
+		width: 8,
+		height: 16,
+		offset_x: 0,
+		offset_y: -2
+	});
+	let acute = font.glyph('\u{301}').unwrap();
+	assert_eq!(acute.dwidth(), Some((0.0, 0.0)));
+	assert_eq!(acute.bounding_box().offset_x, -8);
+	assert_eq!(font.glyph('\u{4E00}').unwrap().bounding_box().width, 16);
+	assert_eq!(font.glyph(0x1F600).unwrap().name(), "uni1F600");
+	assert_eq!(font.bounding_box(), BoundingBox {
+		width: 24,
+		height: 16,
+		offset_x: -8,
+		offset_y: -2
+	});
+
+	let mut written = Vec::new();
+	font.write_hex(&mut written).unwrap();
+	assert_eq!(String::from_utf8(written).unwrap(), HEX);
+	let mut written = Vec::new();
+	font.write_hex_combining(&mut written).unwrap();
+	assert_eq!(String::from_utf8(written).unwrap(), COMBINING);
+}
+
+#[test]
+fn write_hex_from_bdf() {
+	let font = sample_font();
+	let err = font.write_hex(Vec::new()).unwrap_err();
+	assert!(matches!(err, HexError::UnsupportedWidth(105, 4)));
+
+	let mut written = Vec::new();
+	font.write_hex_combining(&mut written).unwrap();
+	assert_eq!(String::from_utf8(written).unwrap(), "0301:-8\n0308:-8\n");
+}
@@ -0,0 +1,39 @@
+#![cfg(feature = "image")]
+
+mod common;
+
+use bdf_reader::RenderOptions;
+use common::sample_font;
+use image::{GrayImage, Luma, Rgba, RgbaImage};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn gray_image() {
+	let font = sample_font();
+	let bitmap = font.glyph('a').unwrap().bitmap();
+	let image: GrayImage = bitmap.into();
+	assert_eq!(image.dimensions(), (5, 4));
+	for (x, y, px) in image.enumerate_pixels() {
+		let set = bitmap.get(x as usize, y as usize).unwrap();
+		assert_eq!(*px, Luma([u8::from(set) * 0xFF]));
+	}
+}
+
+#[test]
+fn rgba_image() {
+	let font = sample_font();
+	let bitmap = font.glyph('a').unwrap().bitmap();
+	let image: RgbaImage = bitmap.into();
+	assert_eq!(image.get_pixel(0, 0), &Rgba([0xFF, 0xFF, 0xFF, 0xFF]));
+	assert_eq!(image.get_pixel(1, 0), &Rgba([0, 0, 0, 0xFF]));
+
+	let options = RenderOptions {
+		foreground: [0x20, 0x40, 0x60, 0x80],
+		scale: 3,
+		..Default::default()
+	};
+	let image = bitmap.to_rgba_image(&options);
+	assert_eq!(image.dimensions(), (15, 12));
+	assert_eq!(image.get_pixel(5, 2), &Rgba(options.foreground));
+	assert_eq!(image.get_pixel(2, 2), &Rgba(options.background));
+}
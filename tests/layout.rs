@@ -0,0 +1,74 @@
+mod common;
+
+use common::sample_font;
+use indoc::indoc;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn combining_mark_does_not_advance() {
+	let font = sample_font();
+	let layout = font.layout("A\u{301}");
+	assert_eq!(layout.advance(), 8);
+	assert_eq!(layout.glyphs().len(), 2);
+
+	let expected = indoc! { r#"
+		........##......
+		......####......
+		....##....##....
+		..##........##..
+		..############..
+		..##........##..
+		----------------
+		................
+		................
+	"# };
+	assert_eq!(layout.render().as_bitmap().ascii_art(), expected);
+}
+
+#[test]
+fn spacing_style_mark_is_centered_on_base() {
+	let font = sample_font();
+	let layout = font.layout("A\u{300}i");
+	assert_eq!(layout.advance(), 12);
+	let x: Vec<i32> = layout.glyphs().iter().map(|pg| pg.x).collect();
+	assert_eq!(x, [0, 2, 8]);
+}
+
+#[test]
+fn synthesize_precomposed() {
+	let font = sample_font();
+	assert!(font.glyph('ä').is_none());
+
+	let expected = indoc! { r#"
+		....##....##....
+		................
+		....######......
+		..........##....
+		..##########....
+		....########....
+		----------------
+		................
+		................
+	"# };
+	let layout = font.layout("ä");
+	assert_eq!(layout.advance(), 8);
+	assert_eq!(layout.render().as_bitmap().ascii_art(), expected);
+
+	let glyph = font
+		.synthesize_glyph('ä')
+		.expect("Failed to synthesize 'ä'");
+	assert_eq!(glyph.encoding(), 'ä' as u32);
+	assert_eq!(glyph.dwidth(), Some((8.0, 0.0)));
+	assert_eq!(glyph.bitmap().ascii_art(), expected);
+}
+
+#[test]
+fn zero_width_and_missing_chars() {
+	let font = sample_font();
+	let layout = font.layout("A\u{200B}A");
+	assert_eq!(layout.advance(), 16);
+	assert_eq!(layout.glyphs().len(), 2);
+
+	let layout = font.layout("Z");
+	assert_eq!(layout.glyphs()[0].glyph.encoding(), 0xFFFD);
+}
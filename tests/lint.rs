@@ -0,0 +1,88 @@
+mod common;
+
+use bdf_reader::{Check, Font, Severity};
+use common::sample_font;
+use indoc::indoc;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn lint_sample_font() {
+	let font = sample_font();
+	assert_eq!(font.lint(), []);
+}
+
+#[test]
+fn lint_broken_font() {
+	let bdf = indoc! { r#"
+		STARTFONT 2.1
+		FONT broken
+		SIZE 8 75 75
+		FONTBOUNDINGBOX 4 4 0 0
+		STARTPROPERTIES 5
+		PIXEL_SIZE 9
+		POINT_SIZE 80
+		RESOLUTION_Y 75
+		FONT_ASCENT 4
+		FONT_DESCENT 1
+		ENDPROPERTIES
+		CHARS 2
+		STARTCHAR A
+		ENCODING 65
+		SWIDTH 960 0
+		DWIDTH 4 0
+		BBX 4 4 0 0
+		BITMAP
+		F8
+		9000
+		90
+		F0
+		ENDCHAR
+		STARTCHAR B
+		ENCODING 66
+		DWIDTH 4 0
+		BBX 5 4 0 0
+		BITMAP
+		F0
+		90
+		90
+		F0
+		ENDCHAR
+		ENDFONT
+	"# };
+	let font = Font::read(bdf.as_bytes()).expect("Failed to parse font");
+	let findings: Vec<_> = font
+		.lint()
+		.into_iter()
+		.map(|finding| (finding.check, finding.glyph))
+		.collect();
+
+	let mut expected = vec![(Check::MissingProperty, None); 11];
+	expected.extend([
+		(Check::AscentDescentMismatch, None),
+		(Check::AscentDescentMismatch, None),
+		(Check::PixelSizeMismatch, None),
+		(Check::NonZeroPadding, Some(65)),
+		(Check::ExtraBitmapData, Some(65)),
+		(Check::InconsistentWidth, Some(65)),
+		(Check::GlyphOutsideBoundingBox, Some(66)),
+		(Check::MissingWidth, Some(66))
+	]);
+	assert_eq!(findings, expected);
+	assert_eq!(Check::MissingWidth.severity(), Severity::Error);
+}
+
+#[test]
+fn lint_shifted_bounding_box() {
+	// the ascent and descent add up to the height, but the box is shifted
+	let bdf = std::fs::read_to_string("tests/sample.bdf")
+		.unwrap()
+		.replace("FONT_ASCENT 6", "FONT_ASCENT 7")
+		.replace("FONT_DESCENT 2", "FONT_DESCENT 1");
+	let font = Font::read(bdf.as_bytes()).unwrap();
+	let findings: Vec<_> = font
+		.lint()
+		.into_iter()
+		.map(|finding| finding.check)
+		.collect();
+	assert_eq!(findings, [Check::AscentDescentMismatch]);
+}
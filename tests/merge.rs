@@ -0,0 +1,139 @@
+mod common;
+
+use bdf_reader::{
+	BoundingBox, ConflictPolicy, Font, MergeError, MergeOptions, Rebaseline, Value
+};
+use common::sample_font;
+use indoc::indoc;
+use pretty_assertions::assert_eq;
+
+fn icon_font() -> Font {
+	let bdf = indoc! { r#"
+		STARTFONT 2.1
+		FONT -Icons-Icons-Medium-R-Normal--4-40-75-75-C-40-ISO10646-1
+		SIZE 4 75 75
+		FONTBOUNDINGBOX 4 4 0 0
+		STARTPROPERTIES 3
+		FAMILY_NAME "Icons"
+		FONT_ASCENT 4
+		FONT_DESCENT 0
+		ENDPROPERTIES
+		CHARS 2
+		STARTCHAR A
+		ENCODING 65
+		SWIDTH 1000 0
+		DWIDTH 4 0
+		BBX 4 4 0 0
+		BITMAP
+		60
+		90
+		F0
+		90
+		ENDCHAR
+		STARTCHAR square
+		ENCODING 57344
+		SWIDTH 1000 0
+		DWIDTH 4 0
+		BBX 4 4 0 0
+		BITMAP
+		F0
+		90
+		90
+		F0
+		ENDCHAR
+		ENDFONT
+	"# };
+	Font::read(bdf.as_bytes()).expect("Failed to parse font")
+}
+
+#[test]
+fn merge_keep_first() {
+	let mut font = sample_font();
+	let report = font.merge(icon_font(), &MergeOptions::default()).unwrap();
+	assert_eq!(report.added_glyphs, [0xE000]);
+	assert_eq!(report.kept_glyphs, [65]);
+	assert!(report.replaced_glyphs.is_empty());
+	assert!(report.replaced_properties.is_empty());
+	assert_eq!(report.pixel_size_mismatch, Some((8, 4)));
+
+	assert_eq!(font.glyph('A').unwrap().bounding_box().width, 8);
+	// the SWIDTH matches the DWIDTH at the size of the sample font
+	let icon = font.glyph('\u{E000}').unwrap();
+	assert_eq!(icon.swidth(), Some((480.0, 0.0)));
+	assert!(matches!(
+		font.property("PIXEL_SIZE"),
+		Some(Value::Integer(8))
+	));
+	assert!(matches!(
+		font.property("FAMILY_NAME"),
+		Some(Value::String(name)) if name == "Sample"
+	));
+}
+
+#[test]
+fn merge_overwrite() {
+	let mut font = sample_font();
+	let options = MergeOptions {
+		glyphs: ConflictPolicy::Overwrite,
+		properties: ConflictPolicy::Overwrite,
+		..Default::default()
+	};
+	let report = font.merge(icon_font(), &options).unwrap();
+	assert_eq!(report.replaced_glyphs, [65]);
+	assert_eq!(report.replaced_properties, ["FAMILY_NAME"]);
+
+	assert_eq!(font.glyph('A').unwrap().bounding_box().width, 4);
+	assert!(matches!(
+		font.property("FAMILY_NAME"),
+		Some(Value::String(name)) if name == "Icons"
+	));
+}
+
+#[test]
+fn merge_error() {
+	let mut font = sample_font();
+	let options = MergeOptions {
+		glyphs: ConflictPolicy::Error,
+		..Default::default()
+	};
+	let err = font.merge(icon_font(), &options).unwrap_err();
+	assert!(matches!(err, MergeError::GlyphConflict(65)));
+	assert!(font.glyph('\u{E000}').is_none());
+}
+
+#[test]
+fn merge_scaled_and_rebaselined() {
+	let mut font = sample_font();
+	let options = MergeOptions {
+		scale: 2,
+		rebaseline: Rebaseline::AlignDescent,
+		..Default::default()
+	};
+	let report = font.merge(icon_font(), &options).unwrap();
+	assert_eq!(report.pixel_size_mismatch, None);
+	assert!(font.lint().is_empty());
+
+	let icon = font.glyph('\u{E000}').unwrap();
+	assert_eq!(icon.bounding_box(), BoundingBox {
+		width: 8,
+		height: 8,
+		offset_x: 0,
+		offset_y: -2
+	});
+	assert_eq!(icon.dwidth(), Some((8.0, 0.0)));
+	assert_eq!(icon.swidth(), Some((960.0, 0.0)));
+	assert_eq!(font.bounding_box(), BoundingBox {
+		width: 16,
+		height: 8,
+		offset_x: -8,
+		offset_y: -2
+	});
+	assert!(matches!(
+		font.property("FONT_ASCENT"),
+		Some(Value::Integer(6))
+	));
+	assert!(matches!(
+		font.property("FONT_DESCENT"),
+		Some(Value::Integer(2))
+	));
+}
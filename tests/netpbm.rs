@@ -0,0 +1,80 @@
+mod common;
+
+use bdf_reader::NetpbmEncoding;
+use common::sample_font;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn write_pbm() {
+	let font = sample_font();
+	let bitmap = font.glyph('a').unwrap().bitmap();
+
+	let mut buf = Vec::new();
+	bitmap.write_pbm(&mut buf, NetpbmEncoding::Plain).unwrap();
+	assert_eq!(
+		String::from_utf8(buf).unwrap(),
+		"P1\n5 4\n0 1 1 1 0\n0 0 0 0 1\n1 1 1 1 1\n0 1 1 1 1\n"
+	);
+
+	let mut buf = Vec::new();
+	bitmap.write_pbm(&mut buf, NetpbmEncoding::Raw).unwrap();
+	assert_eq!(buf, b"P4\n5 4\n\x70\x08\xF8\x78");
+}
+
+#[test]
+fn write_pgm() {
+	let font = sample_font();
+	let bitmap = font.glyph('i').unwrap().bitmap();
+
+	let mut buf = Vec::new();
+	bitmap.write_pgm(&mut buf, NetpbmEncoding::Plain).unwrap();
+	assert_eq!(
+		String::from_utf8(buf).unwrap(),
+		"P2\n1 5\n255\n0\n255\n0\n0\n0\n"
+	);
+
+	let mut buf = Vec::new();
+	bitmap.write_pgm(&mut buf, NetpbmEncoding::Raw).unwrap();
+	assert_eq!(buf, b"P5\n1 5\n255\n\x00\xFF\x00\x00\x00");
+}
+
+#[test]
+fn write_rendered_text() {
+	let font = sample_font();
+	let rendered = font.layout("Aai").render();
+	let mut buf = Vec::new();
+	rendered
+		.as_bitmap()
+		.write_pbm(&mut buf, NetpbmEncoding::Plain)
+		.unwrap();
+	let text = String::from_utf8(buf).unwrap();
+	let mut lines = text.lines();
+	assert_eq!(lines.next(), Some("P1"));
+	assert_eq!(lines.next(), Some("18 8"));
+	// 18 samples fit into a single line of at most 70 characters
+	assert_eq!(lines.count(), 8);
+}
+
+#[test]
+fn glyph_grid() {
+	let font = sample_font();
+	let grid = font.glyph_grid(4, 1);
+	let bbox = grid.bounding_box();
+	assert_eq!((bbox.width, bbox.height), (67, 26));
+
+	// the glyph a is in the third cell of the first row
+	let grid = grid.as_bitmap();
+	let a = font.glyph('a').unwrap().bitmap();
+	for y in 0 .. 8 {
+		for x in 34 .. 50 {
+			let expected = (43 .. 48).contains(&x)
+				&& (2 .. 6).contains(&y)
+				&& a.get(x - 43, y - 2).unwrap();
+			assert_eq!(grid.get(x, y).unwrap(), expected, "({x}, {y})");
+		}
+	}
+
+	let mut buf = Vec::new();
+	grid.write_pbm(&mut buf, NetpbmEncoding::Raw).unwrap();
+	assert_eq!(buf.len(), "P4\n67 26\n".len() + 9 * 26);
+}
@@ -0,0 +1,335 @@
+mod common;
+
+use bdf_reader::{Font, SfntError, Value};
+use common::sample_font;
+use pretty_assertions::assert_eq;
+
+fn u16_at(data: &[u8], pos: usize) -> u16 {
+	u16::from_be_bytes([data[pos], data[pos + 1]])
+}
+
+fn u32_at(data: &[u8], pos: usize) -> u32 {
+	u32::from_be_bytes(data[pos .. pos + 4].try_into().unwrap())
+}
+
+/// Get the tables of an SFNT font, checking their checksums.
+fn tables(data: &[u8]) -> Vec<([u8; 4], &[u8])> {
+	let checksum = |data: &[u8]| {
+		data.chunks(4).fold(0u32, |sum, chunk| {
+			let mut word = [0; 4];
+			word[.. chunk.len()].copy_from_slice(chunk);
+			sum.wrapping_add(u32::from_be_bytes(word))
+		})
+	};
+	assert_eq!(checksum(data), 0xB1B0_AFBA);
+
+	let num_tables = u16_at(data, 4) as usize;
+	(0 .. num_tables)
+		.map(|i| {
+			let record = 12 + 16 * i;
+			let tag = data[record .. record + 4].try_into().unwrap();
+			let offset = u32_at(data, record + 8) as usize;
+			let len = u32_at(data, record + 12) as usize;
+			let table = &data[offset .. offset + len];
+			if &tag != b"head" {
+				assert_eq!(checksum(table), u32_at(data, record + 4));
+			}
+			(tag, table)
+		})
+		.collect()
+}
+
+/// Look up a character in a format 4 `cmap` subtable.
+fn cmap_lookup(cmap: &[u8], ch: char) -> u16 {
+	let subtable = &cmap[u32_at(cmap, 8) as usize ..];
+	assert_eq!(u16_at(subtable, 0), 4);
+	let seg_count = u16_at(subtable, 6) as usize / 2;
+	let code = ch as u16;
+	for i in 0 .. seg_count {
+		let end = u16_at(subtable, 14 + 2 * i);
+		let start = u16_at(subtable, 16 + 2 * seg_count + 2 * i);
+		let delta = u16_at(subtable, 16 + 4 * seg_count + 2 * i);
+		if (start ..= end).contains(&code) {
+			return code.wrapping_add(delta);
+		}
+	}
+	0
+}
+
+#[test]
+fn write_otb() {
+	let font = sample_font();
+	let mut otb = Vec::new();
+	Font::write_otb_strikes(&[&font, &font], &mut otb).unwrap();
+
+	let tables = tables(&otb);
+	let tags: Vec<&str> = tables
+		.iter()
+		.map(|(tag, _)| std::str::from_utf8(tag).unwrap())
+		.collect();
+	assert_eq!(tags, vec![
+		"EBDT", "EBLC", "OS/2", "cmap", "head", "hhea", "hmtx", "maxp", "name", "post"
+	]);
+	let table = |tag: &[u8; 4]| tables.iter().find(|(t, _)| t == tag).unwrap().1;
+
+	// .notdef followed by the 9 glyphs of the font
+	assert_eq!(u16_at(table(b"maxp"), 4), 10);
+	assert_eq!(u16_at(table(b"head"), 18), 1024);
+	assert_eq!(cmap_lookup(table(b"cmap"), ' '), 1);
+	assert_eq!(cmap_lookup(table(b"cmap"), 'A'), 2);
+	assert_eq!(cmap_lookup(table(b"cmap"), '\u{FFFD}'), 9);
+	assert_eq!(cmap_lookup(table(b"cmap"), 'B'), 0);
+
+	// advance of A is 8 pixels of 128 units each
+	let hmtx = table(b"hmtx");
+	assert_eq!(u16_at(hmtx, 2 * 4), 1024);
+
+	// both strikes at 8 pixels per em, covering all glyphs
+	let eblc = table(b"EBLC");
+	assert_eq!(u32_at(eblc, 4), 2);
+	let size = &eblc[8 .. 56];
+	assert_eq!(&size[40 .. 48], &[0, 0, 0, 9, 8, 8, 1, 1]);
+
+	// the image of A uses format 1
+	let array = &eblc[u32_at(size, 0) as usize ..];
+	assert_eq!(u16_at(array, 8), 1);
+	assert_eq!(u16_at(array, 10), 1);
+	let image_offset = u32_at(array, 12) as usize + u32_at(array, 16 + 4 * 2) as usize;
+	let ebdt = table(b"EBDT");
+	assert_eq!(&ebdt[image_offset .. image_offset + 13], &[
+		8, 8, 0, 6, 8, 0x00, 0x18, 0x24, 0x42, 0x7E, 0x42, 0x00, 0x00
+	]);
+}
+
+#[test]
+fn write_otb_no_strikes() {
+	let err = Font::write_otb_strikes(&[], Vec::new()).unwrap_err();
+	assert!(matches!(err, SfntError::NoStrikes));
+}
+
+#[test]
+fn otb_round_trip() {
+	let font = sample_font();
+	let mut otb = Vec::new();
+	Font::write_otb_strikes(&[&font, &font], &mut otb).unwrap();
+	let fonts = Font::read_otb(otb.as_slice()).unwrap();
+	assert_eq!(fonts.len(), 2);
+
+	let read = &fonts[0];
+	assert_eq!(
+		read.name(),
+		"-misc-Sample-Medium-R-Normal--8-80-72-72-P-53-iso10646-1"
+	);
+	assert_eq!(read.bounding_box(), font.bounding_box());
+	for (key, value) in [
+		("FAMILY_NAME", Value::String("Sample".into())),
+		("PIXEL_SIZE", Value::Integer(8)),
+		("FONT_ASCENT", Value::Integer(6)),
+		("FONT_DESCENT", Value::Integer(2))
+	] {
+		assert_eq!(read.property(key), Some(&value), "{key}");
+	}
+
+	let glyphs: Vec<_> = font.glyphs().into_iter().collect();
+	let read_glyphs: Vec<_> = read.glyphs().into_iter().collect();
+	assert_eq!(read_glyphs.len(), glyphs.len());
+	for (read, glyph) in read_glyphs.into_iter().zip(glyphs) {
+		assert_eq!(read.encoding(), glyph.encoding());
+		assert_eq!(read.dwidth(), glyph.dwidth());
+		assert_eq!(read.bounding_box(), glyph.bounding_box());
+		assert_eq!(read.bitmap().ascii_art(), glyph.bitmap().ascii_art());
+	}
+}
+
+#[test]
+fn otb_many_cmap_segments() {
+	// every other character, so that each one needs its own format 4 segment
+	let mut bdf = String::from(
+		"STARTFONT 2.1\nFONT -Test-Sparse\nSIZE 8 75 75\nFONTBOUNDINGBOX 1 1 0 0\n\
+		 CHARS 9000\n"
+	);
+	for i in 0 .. 9000 {
+		bdf += &format!(
+			"STARTCHAR c{i}\nENCODING {}\nSWIDTH 125 0\nDWIDTH 1 0\nBBX 1 1 0 0\n\
+			 BITMAP\n80\nENDCHAR\n",
+			2 * i
+		);
+	}
+	bdf += "ENDFONT\n";
+	let font = Font::read(bdf.as_bytes()).unwrap();
+
+	let mut otb = Vec::new();
+	font.write_otb(&mut otb).unwrap();
+	let (_, cmap) = tables(&otb)
+		.into_iter()
+		.find(|(tag, _)| tag == b"cmap")
+		.unwrap();
+	// only a format 12 subtable
+	assert_eq!(u16_at(cmap, 2), 1);
+	assert_eq!(u16_at(cmap, 6), 10);
+
+	let read = &Font::read_otb(otb.as_slice()).unwrap()[0];
+	assert_eq!(read.glyphs().into_iter().count(), 9000);
+	assert!(read.glyph(17998).is_some());
+	assert!(read.glyph(17999).is_none());
+}
+
+#[test]
+fn otb_cmap_last_code() {
+	let mut bdf = String::from(
+		"STARTFONT 2.1\nFONT -Test-Last\nSIZE 8 75 75\nFONTBOUNDINGBOX 1 1 0 0\n\
+		 CHARS 2\n"
+	);
+	for enc in [65, 0xFFFF] {
+		bdf += &format!(
+			"STARTCHAR u{enc:X}\nENCODING {enc}\nSWIDTH 125 0\nDWIDTH 1 0\n\
+			 BBX 1 1 0 0\nBITMAP\n80\nENDCHAR\n"
+		);
+	}
+	bdf += "ENDFONT\n";
+	let font = Font::read(bdf.as_bytes()).unwrap();
+	let mut otb = Vec::new();
+	font.write_otb(&mut otb).unwrap();
+
+	// the format 4 segments are valid and end with the 0xFFFF sentinel
+	let (_, cmap) = tables(&otb)
+		.into_iter()
+		.find(|(tag, _)| tag == b"cmap")
+		.unwrap();
+	let subtable = &cmap[u32_at(cmap, 8) as usize ..];
+	let seg_count = u16_at(subtable, 6) as usize / 2;
+	for i in 0 .. seg_count {
+		let end = u16_at(subtable, 14 + 2 * i);
+		let start = u16_at(subtable, 16 + 2 * seg_count + 2 * i);
+		assert!(start <= end, "segment {i} from {start:#x} to {end:#x}");
+	}
+	assert_eq!(cmap_lookup(cmap, 'A'), 1);
+	assert_eq!(cmap_lookup(cmap, '\u{FFFF}'), 0);
+
+	// the format 12 subtable maps 0xFFFF
+	let read = &Font::read_otb(otb.as_slice()).unwrap()[0];
+	assert!(read.glyph('A').is_some());
+	assert!(read.glyph(0xFFFF).is_some());
+}
+
+#[test]
+fn read_otb_cmap_overflow() {
+	let mut bdf = String::from(
+		"STARTFONT 2.1\nFONT -Test-Astral\nSIZE 8 75 75\nFONTBOUNDINGBOX 1 1 0 0\n\
+		 CHARS 3\n"
+	);
+	for enc in [65, 0x10000, 0x10001] {
+		bdf += &format!(
+			"STARTCHAR u{enc:X}\nENCODING {enc}\nSWIDTH 125 0\nDWIDTH 1 0\n\
+			 BBX 1 1 0 0\nBITMAP\n80\nENDCHAR\n"
+		);
+	}
+	bdf += "ENDFONT\n";
+	let font = Font::read(bdf.as_bytes()).unwrap();
+	let mut otb = Vec::new();
+	font.write_otb(&mut otb).unwrap();
+
+	// let the glyph ids of the astral group overflow in the format 12 subtable
+	let cmap = tables(&otb)
+		.into_iter()
+		.find(|(tag, _)| tag == b"cmap")
+		.map(|(_, cmap)| cmap.as_ptr() as usize - otb.as_ptr() as usize)
+		.unwrap();
+	assert_eq!(u16_at(&otb, cmap + 4 + 8 + 2), 10);
+	let format12 = cmap + u32_at(&otb, cmap + 4 + 8 + 4) as usize;
+	assert_eq!(u32_at(&otb, format12 + 12), 2);
+	otb[format12 + 16 + 12 + 8 .. format12 + 16 + 12 + 12].fill(0xFF);
+
+	let read = &Font::read_otb(otb.as_slice()).unwrap()[0];
+	assert!(read.glyph('A').is_some());
+	assert!(read.glyph(0x10000).is_none());
+
+	// let the astral group cover all of unicode, of which only 16 bit glyph ids map
+	otb[format12 + 16 + 12 + 4 .. format12 + 16 + 12 + 8]
+		.copy_from_slice(&0x10FFFFu32.to_be_bytes());
+	otb[format12 + 16 + 12 + 8 .. format12 + 16 + 12 + 12]
+		.copy_from_slice(&2u32.to_be_bytes());
+	let read = &Font::read_otb(otb.as_slice()).unwrap()[0];
+	assert_eq!(read.glyphs().into_iter().count(), 3);
+	assert!(read.glyph(0x10001).is_some());
+}
+
+#[test]
+fn read_otb_invalid() {
+	let result = Font::read_otb(b"STARTFONT 2.1\n".as_slice());
+	assert!(matches!(result, Err(SfntError::InvalidMagic)));
+
+	// a font without embedded bitmaps
+	let mut otb = Vec::new();
+	Font::write_otb(&sample_font(), &mut otb).unwrap();
+	otb[12 .. 16].copy_from_slice(b"XBDT");
+	let result = Font::read_otb(otb.as_slice());
+	assert!(matches!(result, Err(SfntError::MissingTable(tag)) if tag == "EBDT"));
+}
+
+#[test]
+fn read_otb_nested_composites() {
+	let mut otb = Vec::new();
+	Font::write_otb(&sample_font(), &mut otb).unwrap();
+
+	// glyphs 1 to 8 each paint 200 copies of the next glyph, glyph 9 is a single dot
+	let mut ebdt = 0x0002_0000u32.to_be_bytes().to_vec();
+	let mut offsets = Vec::new();
+	for gid in 1u16 ..= 8 {
+		offsets.push(ebdt.len() as u32 - 4);
+		ebdt.extend_from_slice(&[1, 1, 0, 1, 1, 0, 0, 0]);
+		ebdt.extend_from_slice(&200u16.to_be_bytes());
+		for _ in 0 .. 200 {
+			ebdt.extend_from_slice(&(gid + 1).to_be_bytes());
+			ebdt.extend_from_slice(&[0, 0]);
+		}
+	}
+	offsets.push(ebdt.len() as u32 - 4);
+	let dot = ebdt.len() as u32;
+	ebdt.extend_from_slice(&[1, 1, 0, 1, 1, 0x80]);
+
+	let mut eblc = 0x0002_0000u32.to_be_bytes().to_vec();
+	eblc.extend_from_slice(&1u32.to_be_bytes());
+	let mut size = [0; 48];
+	size[0 .. 4].copy_from_slice(&56u32.to_be_bytes());
+	size[8 .. 12].copy_from_slice(&2u32.to_be_bytes());
+	size[16] = 1;
+	size[42 .. 44].copy_from_slice(&9u16.to_be_bytes());
+	size[44 .. 48].copy_from_slice(&[8, 8, 1, 1]);
+	eblc.extend_from_slice(&size);
+	let composites = 16 + 8 + 4 * offsets.len() as u32;
+	for (first, last, subtable) in [(1u16, 8u16, 16u32), (9, 9, composites)] {
+		eblc.extend_from_slice(&first.to_be_bytes());
+		eblc.extend_from_slice(&last.to_be_bytes());
+		eblc.extend_from_slice(&subtable.to_be_bytes());
+	}
+	eblc.extend_from_slice(&[0, 1, 0, 9]);
+	eblc.extend_from_slice(&4u32.to_be_bytes());
+	for offset in offsets {
+		eblc.extend_from_slice(&offset.to_be_bytes());
+	}
+	eblc.extend_from_slice(&[0, 1, 0, 1]);
+	eblc.extend_from_slice(&dot.to_be_bytes());
+	eblc.extend_from_slice(&0u32.to_be_bytes());
+	eblc.extend_from_slice(&6u32.to_be_bytes());
+
+	// append the crafted tables and point the table records at them
+	for (tag, table) in [(b"EBLC", eblc), (b"EBDT", ebdt)] {
+		let record = (0 .. u16_at(&otb, 4) as usize)
+			.map(|i| 12 + 16 * i)
+			.find(|&record| &otb[record .. record + 4] == tag)
+			.unwrap();
+		let offset = otb.len() as u32;
+		otb[record + 8 .. record + 12].copy_from_slice(&offset.to_be_bytes());
+		otb[record + 12 .. record + 16]
+			.copy_from_slice(&(table.len() as u32).to_be_bytes());
+		otb.extend_from_slice(&table);
+	}
+
+	let read = &Font::read_otb(otb.as_slice()).unwrap()[0];
+	assert_eq!(read.glyphs().into_iter().count(), 9);
+	for glyph in read.glyphs() {
+		assert_eq!(glyph.bounding_box().width, 1);
+		assert!(glyph.bitmap().get(0, 0).unwrap());
+	}
+}
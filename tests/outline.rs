@@ -0,0 +1,95 @@
+mod common;
+
+use bdf_reader::{BitmapBuf, BoundingBox, Contour, OutlineOptions, Winding};
+use common::sample_font;
+use pretty_assertions::assert_eq;
+
+fn outer(points: Vec<(i32, i32)>) -> Contour {
+	Contour {
+		points,
+		hole: false
+	}
+}
+
+/// A 3x3 square with a hole in the middle, below the baseline.
+fn ring() -> BitmapBuf {
+	let mut ring = BitmapBuf::new(BoundingBox {
+		width: 3,
+		height: 3,
+		offset_x: 0,
+		offset_y: -1
+	});
+	for (x, y) in [
+		(0, 0),
+		(1, 0),
+		(2, 0),
+		(0, 1),
+		(2, 1),
+		(0, 2),
+		(1, 2),
+		(2, 2)
+	] {
+		ring.set(x, y, true).unwrap();
+	}
+	ring
+}
+
+#[test]
+fn outline() {
+	let font = sample_font();
+	let bitmap = font.glyph('i').unwrap().bitmap();
+	assert_eq!(bitmap.outline(&OutlineOptions::default()), vec![
+		outer(vec![(1, 0), (1, 3), (2, 3), (2, 0)]),
+		outer(vec![(1, 4), (1, 5), (2, 5), (2, 4)])
+	]);
+}
+
+#[test]
+fn outline_holes() {
+	let ring = ring();
+	assert_eq!(ring.as_bitmap().outline(&OutlineOptions::default()), vec![
+		outer(vec![(0, -1), (0, 2), (3, 2), (3, -1)]),
+		Contour {
+			points: vec![(1, 0), (2, 0), (2, 1), (1, 1)],
+			hole: true
+		}
+	]);
+
+	let options = OutlineOptions {
+		winding: Winding::CounterClockwise,
+		..Default::default()
+	};
+	assert_eq!(ring.as_bitmap().outline(&options), vec![
+		outer(vec![(3, -1), (3, 2), (0, 2), (0, -1)]),
+		Contour {
+			points: vec![(1, 1), (2, 1), (2, 0), (1, 0)],
+			hole: true
+		}
+	]);
+}
+
+#[test]
+fn outline_smooth_diagonals() {
+	let font = sample_font();
+	let options = OutlineOptions {
+		smooth_diagonals: true,
+		..Default::default()
+	};
+	let bitmap = font.glyph('a').unwrap().bitmap();
+	assert_eq!(bitmap.outline(&options), vec![
+		outer(vec![(1, 2), (5, 2), (6, 3), (6, 0), (2, 0)]),
+		outer(vec![(2, 3), (2, 4), (5, 4), (5, 3)])
+	]);
+
+	// single pixels and holes keep their corners
+	let bitmap = font.glyph('i').unwrap().bitmap();
+	assert_eq!(
+		bitmap.outline(&options),
+		bitmap.outline(&OutlineOptions::default())
+	);
+	let ring = ring();
+	assert_eq!(
+		ring.as_bitmap().outline(&options),
+		ring.as_bitmap().outline(&OutlineOptions::default())
+	);
+}
@@ -0,0 +1,360 @@
+mod common;
+
+use bdf_reader::{BoundingBox, Font, PcfError, PcfOptions, Value};
+use common::sample_font;
+use indoc::indoc;
+use pretty_assertions::assert_eq;
+
+const PCF_PROPERTIES: u32 = 1 << 0;
+const PCF_METRICS: u32 = 1 << 2;
+const PCF_BITMAPS: u32 = 1 << 3;
+const PCF_BDF_ENCODINGS: u32 = 1 << 5;
+const PCF_SWIDTHS: u32 = 1 << 6;
+const PCF_GLYPH_NAMES: u32 = 1 << 7;
+const PCF_BDF_ACCELERATORS: u32 = 1 << 8;
+
+const PCF_COMPRESSED_METRICS: u32 = 0x100;
+const PCF_BYTE_MASK: u32 = 1 << 2;
+const PCF_BIT_MASK: u32 = 1 << 3;
+
+/// A minimal PCF encoder for a font with two glyphs, `A` and `B`, each 5 pixels wide
+/// and 3 pixels high.
+struct Pcf {
+	/// The format flags for byte and bit order, glyph padding and scan unit.
+	flags: u32,
+
+	/// Whether to use uncompressed metrics with bearings at the limits of their
+	/// range and no height instead.
+	wide: bool
+}
+
+impl Pcf {
+	fn int(&self, buf: &mut Vec<u8>, value: i32) {
+		match self.flags & PCF_BYTE_MASK != 0 {
+			true => buf.extend(value.to_be_bytes()),
+			false => buf.extend(value.to_le_bytes())
+		}
+	}
+
+	fn short(&self, buf: &mut Vec<u8>, value: i16) {
+		match self.flags & PCF_BYTE_MASK != 0 {
+			true => buf.extend(value.to_be_bytes()),
+			false => buf.extend(value.to_le_bytes())
+		}
+	}
+
+	fn table(&self, format: u32) -> Vec<u8> {
+		(format | self.flags).to_le_bytes().to_vec()
+	}
+
+	fn properties(&self) -> Vec<u8> {
+		let mut buf = self.table(0);
+		let strings = b"FONT\0-Test-Font\0POINT_SIZE\0";
+		self.int(&mut buf, 2);
+		for (name, is_string, value) in [(0, 1, 5), (16, 0, 80)] {
+			self.int(&mut buf, name);
+			buf.push(is_string);
+			self.int(&mut buf, value);
+		}
+		buf.extend([0, 0]);
+		self.int(&mut buf, strings.len() as i32);
+		buf.extend(strings);
+		buf
+	}
+
+	/// The left and right bearing, width, ascent and descent of each glyph.
+	fn glyph_metrics(&self) -> [i16; 5] {
+		match self.wide {
+			true => [i16::MIN, i16::MAX, 6, 0, 0],
+			false => [0, 5, 6, 2, 1]
+		}
+	}
+
+	fn metrics(&self) -> Vec<u8> {
+		if self.wide {
+			let mut buf = self.table(0);
+			self.int(&mut buf, 2);
+			for _ in 0 .. 2 {
+				for v in self.glyph_metrics() {
+					self.short(&mut buf, v);
+				}
+				self.short(&mut buf, 0);
+			}
+			return buf;
+		}
+		let mut buf = self.table(PCF_COMPRESSED_METRICS);
+		self.short(&mut buf, 2);
+		for _ in 0 .. 2 {
+			buf.extend(self.glyph_metrics().map(|v| (v + 0x80) as u8));
+		}
+		buf
+	}
+
+	fn bitmaps(&self) -> Vec<u8> {
+		let pad = 1 << (self.flags & 3);
+		let unit = 1 << ((self.flags >> 4) & 3);
+		let rows: [u8; 6] = [0x70, 0x88, 0xF8, 0xF0, 0x88, 0xF0];
+		let mut data = Vec::new();
+		for row in rows {
+			let mut bytes = vec![0; pad];
+			bytes[0] = row;
+			if self.flags & PCF_BIT_MASK == 0 {
+				bytes[0] = row.reverse_bits();
+			}
+			if (self.flags & PCF_BYTE_MASK != 0) != (self.flags & PCF_BIT_MASK != 0) {
+				for chunk in bytes.chunks_mut(unit) {
+					chunk.reverse();
+				}
+			}
+			data.extend(bytes);
+		}
+
+		let mut buf = self.table(0);
+		self.int(&mut buf, 2);
+		self.int(&mut buf, 0);
+		self.int(&mut buf, 3 * pad as i32);
+		for size in [1, 2, 4, 8] {
+			self.int(&mut buf, 6 * size);
+		}
+		buf.extend(data);
+		buf
+	}
+
+	fn encodings(&self) -> Vec<u8> {
+		let mut buf = self.table(0);
+		for v in [0x41, 0x42, 0, 0, 0x42] {
+			self.short(&mut buf, v);
+		}
+		self.short(&mut buf, 0);
+		self.short(&mut buf, 1);
+		buf
+	}
+
+	fn swidths(&self) -> Vec<u8> {
+		let mut buf = self.table(0);
+		self.int(&mut buf, 2);
+		self.int(&mut buf, 540);
+		self.int(&mut buf, 550);
+		buf
+	}
+
+	fn glyph_names(&self) -> Vec<u8> {
+		let mut buf = self.table(0);
+		self.int(&mut buf, 2);
+		self.int(&mut buf, 0);
+		self.int(&mut buf, 2);
+		self.int(&mut buf, 4);
+		buf.extend(b"A\0B\0");
+		buf
+	}
+
+	fn accelerators(&self) -> Vec<u8> {
+		let mut buf = self.table(0);
+		buf.extend([1, 1, 1, 1, 1, 0, 0, 0]);
+		self.int(&mut buf, 3);
+		self.int(&mut buf, 1);
+		self.int(&mut buf, 0);
+		for _ in 0 .. 2 {
+			for v in self.glyph_metrics() {
+				self.short(&mut buf, v);
+			}
+			self.short(&mut buf, 0);
+		}
+		buf
+	}
+
+	fn build(&self) -> Vec<u8> {
+		let tables = [
+			(PCF_PROPERTIES, self.properties()),
+			(PCF_BDF_ACCELERATORS, self.accelerators()),
+			(PCF_METRICS, self.metrics()),
+			(PCF_BITMAPS, self.bitmaps()),
+			(PCF_BDF_ENCODINGS, self.encodings()),
+			(PCF_SWIDTHS, self.swidths()),
+			(PCF_GLYPH_NAMES, self.glyph_names())
+		];
+		let mut buf = b"\x01fcp".to_vec();
+		buf.extend((tables.len() as i32).to_le_bytes());
+		let mut offset = 8 + 16 * tables.len();
+		for (ty, data) in &tables {
+			buf.extend(ty.to_le_bytes());
+			buf.extend(
+				(u32::from_le_bytes(data[.. 4].try_into().unwrap())).to_le_bytes()
+			);
+			buf.extend((data.len() as i32).to_le_bytes());
+			buf.extend((offset as i32).to_le_bytes());
+			offset += data.len().div_ceil(4) * 4;
+		}
+		for (_, data) in &tables {
+			buf.extend(data);
+			buf.resize(buf.len().div_ceil(4) * 4, 0);
+		}
+		buf
+	}
+}
+
+#[test]
+fn read_pcf_all_formats() {
+	let expected_a = indoc! { r#"
+		..######..
+		##......##
+		----------
+		##########
+	"# };
+	let expected_b = indoc! { r#"
+		########..
+		##......##
+		----------
+		########..
+	"# };
+
+	for byte in [0, PCF_BYTE_MASK] {
+		for bit in [0, PCF_BIT_MASK] {
+			for pad in 0 .. 4 {
+				// the scan unit may not exceed the glyph padding
+				for unit in 0 ..= pad.min(2) {
+					let flags = byte | bit | pad | unit << 4;
+					let pcf = Pcf { flags, wide: false }.build();
+					let font = Font::read_pcf(pcf.as_slice())
+						.unwrap_or_else(|err| panic!("{flags:#x}: {err}"));
+
+					assert_eq!(font.name(), "-Test-Font");
+					assert_eq!(font.size().pt, 8);
+					assert_eq!(font.bounding_box(), BoundingBox {
+						width: 5,
+						height: 3,
+						offset_x: 0,
+						offset_y: -1
+					});
+					assert!(matches!(
+						font.property("FONT_ASCENT"),
+						Some(Value::Integer(3))
+					));
+					assert!(matches!(
+						font.property("DEFAULT_CHAR"),
+						Some(Value::Integer(0x42))
+					));
+
+					let a = font.glyph('A').unwrap();
+					assert_eq!(a.name(), "A");
+					assert_eq!(a.swidth(), Some((540.0, 0.0)));
+					assert_eq!(a.dwidth(), Some((6.0, 0.0)));
+					assert_eq!(a.bitmap().ascii_art(), expected_a, "{flags:#x}");
+					let b = font.glyph('B').unwrap();
+					assert_eq!(b.bitmap().ascii_art(), expected_b, "{flags:#x}");
+				}
+			}
+		}
+	}
+}
+
+#[test]
+fn read_pcf_invalid() {
+	assert!(Font::read_pcf(&b"STARTFONT 2.1\n"[..]).is_err());
+	let mut pcf = Pcf {
+		flags: 0,
+		wide: false
+	}
+	.build();
+	pcf.truncate(200);
+	assert!(Font::read_pcf(pcf.as_slice()).is_err());
+
+	// a 16 byte properties table claiming 2^31-1 properties
+	let mut pcf = b"\x01fcp".to_vec();
+	for value in [1, 1, 0, 16, 24, 0, i32::MAX, 0, 0] {
+		pcf.extend_from_slice(&i32::to_le_bytes(value));
+	}
+	let err = Font::read_pcf(pcf.as_slice());
+	assert!(matches!(err, Err(PcfError::UnexpectedEof(1))));
+}
+
+#[test]
+fn read_pcf_wide_metrics() {
+	let pcf = Pcf {
+		flags: 0,
+		wide: true
+	}
+	.build();
+	let font = Font::read_pcf(pcf.as_slice()).unwrap();
+	let bbox = BoundingBox {
+		width: 0xFFFF,
+		height: 0,
+		offset_x: -0x8000,
+		offset_y: 0
+	};
+	assert_eq!(font.bounding_box(), bbox);
+	assert_eq!(font.glyph('A').unwrap().bounding_box(), bbox);
+}
+
+#[test]
+fn write_pcf_round_trip() {
+	let font = sample_font();
+	for msb_byte_first in [false, true] {
+		for msb_bit_first in [false, true] {
+			for (glyph_padding, scan_unit) in [(1, 1), (2, 2), (4, 1), (4, 4), (8, 2)] {
+				let options = PcfOptions {
+					msb_byte_first,
+					msb_bit_first,
+					glyph_padding,
+					scan_unit
+				};
+				let mut pcf = Vec::new();
+				font.write_pcf(&mut pcf, &options).unwrap();
+				let read = Font::read_pcf(pcf.as_slice())
+					.unwrap_or_else(|err| panic!("{options:?}: {err}"));
+
+				assert_eq!(read.name(), font.name());
+				assert_eq!(read.size(), font.size());
+				assert_eq!(read.bounding_box(), font.bounding_box());
+				let mut keys: Vec<_> = read.properties().map(|(key, _)| key).collect();
+				keys.sort_unstable();
+				let mut expected: Vec<_> =
+					font.properties().map(|(key, _)| key).collect();
+				expected.sort_unstable();
+				assert_eq!(keys, expected);
+
+				let glyphs = font.glyphs().into_iter().zip(read.glyphs());
+				for (expected, glyph) in glyphs {
+					assert_eq!(glyph.name(), expected.name());
+					assert_eq!(glyph.encoding(), expected.encoding());
+					assert_eq!(glyph.swidth(), expected.swidth());
+					assert_eq!(glyph.dwidth(), expected.dwidth());
+					assert_eq!(glyph.bounding_box(), expected.bounding_box());
+					assert_eq!(
+						glyph.bitmap().ascii_art(),
+						expected.bitmap().ascii_art(),
+						"{options:?}"
+					);
+				}
+			}
+		}
+	}
+}
+
+#[test]
+fn write_pcf_invalid_options() {
+	let options = PcfOptions {
+		glyph_padding: 1,
+		scan_unit: 2,
+		..Default::default()
+	};
+	assert!(sample_font().write_pcf(Vec::new(), &options).is_err());
+}
+
+#[test]
+fn write_pcf_too_many_glyphs() {
+	let mut bdf = String::from(
+		"STARTFONT 2.1\nFONT -Test-Full\nSIZE 8 75 75\nFONTBOUNDINGBOX 1 1 0 0\n\
+		 CHARS 65536\n"
+	);
+	for enc in 0 .. 0x10000 {
+		bdf += &format!(
+			"STARTCHAR c{enc}\nENCODING {enc}\nSWIDTH 125 0\nDWIDTH 1 0\nBBX 1 1 0 0\n\
+			 BITMAP\n80\nENDCHAR\n"
+		);
+	}
+	bdf += "ENDFONT\n";
+	let font = Font::read(bdf.as_bytes()).unwrap();
+	let err = font.write_pcf(Vec::new(), &PcfOptions::default());
+	assert!(matches!(err, Err(PcfError::TooManyGlyphs(65536))));
+}
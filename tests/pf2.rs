@@ -0,0 +1,70 @@
+mod common;
+
+use bdf_reader::{Font, Pf2Error, Value};
+use common::sample_font;
+use pretty_assertions::assert_eq;
+
+/// Get the names and data of all sections before the `DATA` section.
+fn sections(pf2: &[u8]) -> Vec<(&str, &[u8])> {
+	let mut sections = Vec::new();
+	let mut pos = 0;
+	while &pf2[pos .. pos + 4] != b"DATA" {
+		let len =
+			u32::from_be_bytes(pf2[pos + 4 .. pos + 8].try_into().unwrap()) as usize;
+		let name = std::str::from_utf8(&pf2[pos .. pos + 4]).unwrap();
+		sections.push((name, &pf2[pos + 8 .. pos + 8 + len]));
+		pos += 8 + len;
+	}
+	sections
+}
+
+#[test]
+fn pf2_round_trip() {
+	let font = sample_font();
+	let mut pf2 = Vec::new();
+	font.write_pf2(&mut pf2).unwrap();
+
+	let sections = sections(&pf2);
+	let names: Vec<&str> = sections.iter().map(|(name, _)| *name).collect();
+	assert_eq!(names, vec![
+		"FILE", "NAME", "FAMI", "WEIG", "SLAN", "PTSZ", "MAXW", "MAXH", "ASCE", "DESC",
+		"CHIX"
+	]);
+	assert_eq!(sections[1].1, b"Sample Regular 8\0");
+	assert_eq!(sections[5].1, [0, 8]);
+	assert_eq!(sections[10].1.len(), 9 * 9);
+
+	let read = Font::read_pf2(pf2.as_slice()).unwrap();
+	assert_eq!(
+		read.name(),
+		"-misc-Sample-Medium-R-Normal--8-80-72-72-P-53-iso10646-1"
+	);
+	assert_eq!(read.bounding_box(), font.bounding_box());
+	for (key, value) in [
+		("FAMILY_NAME", Value::String("Sample".into())),
+		("PIXEL_SIZE", Value::Integer(8)),
+		("FONT_ASCENT", Value::Integer(6)),
+		("FONT_DESCENT", Value::Integer(2))
+	] {
+		assert_eq!(read.property(key), Some(&value), "{key}");
+	}
+
+	let glyphs: Vec<_> = font.glyphs().into_iter().collect();
+	let read_glyphs: Vec<_> = read.glyphs().into_iter().collect();
+	assert_eq!(read_glyphs.len(), glyphs.len());
+	for (read, glyph) in read_glyphs.into_iter().zip(glyphs) {
+		assert_eq!(read.encoding(), glyph.encoding());
+		assert_eq!(read.dwidth(), glyph.dwidth());
+		assert_eq!(read.bounding_box(), glyph.bounding_box());
+		assert_eq!(read.bitmap().ascii_art(), glyph.bitmap().ascii_art());
+	}
+}
+
+#[test]
+fn read_pf2_invalid() {
+	let result = Font::read_pf2(b"STARTFONT 2.1\n".as_slice());
+	assert!(matches!(result, Err(Pf2Error::InvalidMagic)));
+
+	let result = Font::read_pf2(b"FILE\0\0\0\x04PFF2DATA\xff\xff\xff\xff".as_slice());
+	assert!(matches!(result, Err(Pf2Error::MissingSection("CHIX"))));
+}
@@ -0,0 +1,79 @@
+#![cfg(feature = "png")]
+
+mod common;
+
+use bdf_reader::RenderOptions;
+use common::sample_font;
+use pretty_assertions::assert_eq;
+
+fn decode(data: &[u8]) -> (u32, u32, Vec<u8>) {
+	let decoder = png::Decoder::new(data);
+	let mut reader = decoder.read_info().unwrap();
+	let mut buf = vec![0; reader.output_buffer_size()];
+	let info = reader.next_frame(&mut buf).unwrap();
+	assert_eq!(info.color_type, png::ColorType::Rgba);
+	buf.truncate(info.buffer_size());
+	(info.width, info.height, buf)
+}
+
+#[test]
+fn write_png() {
+	let font = sample_font();
+	let bitmap = font.glyph('i').unwrap().bitmap();
+	let options = RenderOptions {
+		foreground: [0xFF, 0, 0, 0xFF],
+		background: [0, 0, 0, 0],
+		scale: 2
+	};
+	let mut buf = Vec::new();
+	bitmap.write_png(&mut buf, &options).unwrap();
+
+	let (width, height, pixels) = decode(&buf);
+	assert_eq!((width, height), (2, 10));
+	let pixels: Vec<[u8; 4]> =
+		pixels.chunks(4).map(|px| px.try_into().unwrap()).collect();
+	for (idx, px) in pixels.iter().enumerate() {
+		let set = bitmap.get(idx % 2 / 2, idx / 2 / 2).unwrap();
+		let expected = match set {
+			true => options.foreground,
+			false => options.background
+		};
+		assert_eq!(*px, expected, "{idx}");
+	}
+}
+
+#[test]
+fn write_layout_png() {
+	let font = sample_font();
+	let layout = font.layout("Aai");
+	let mut buf = Vec::new();
+	layout
+		.write_png(&mut buf, &RenderOptions::default())
+		.unwrap();
+
+	let (width, height, pixels) = decode(&buf);
+	let rendered = layout.render();
+	let bitmap = rendered.as_bitmap();
+	assert_eq!(
+		(width as usize, height as usize),
+		(bitmap.width(), bitmap.height())
+	);
+	let black = pixels.chunks(4).filter(|px| *px == [0, 0, 0, 0xFF]).count();
+	let set = (0 .. bitmap.height())
+		.flat_map(|y| (0 .. bitmap.width()).map(move |x| (x, y)))
+		.filter(|(x, y)| bitmap.get(*x, *y).unwrap())
+		.count();
+	assert_eq!(black, set);
+}
+
+#[test]
+fn write_glyph_grid_png() {
+	let font = sample_font();
+	let grid = font.glyph_grid(3, 0);
+	let mut buf = Vec::new();
+	grid.as_bitmap()
+		.write_png(&mut buf, &RenderOptions::default())
+		.unwrap();
+	let (width, height, _) = decode(&buf);
+	assert_eq!((width, height), (48, 24));
+}
@@ -0,0 +1,185 @@
+mod common;
+
+use bdf_reader::{BitmapBuf, BoundingBox, Font, Glyph, PsfError, PsfOptions, PsfVersion};
+use common::sample_font;
+use indoc::indoc;
+use pretty_assertions::assert_eq;
+
+const CONSOLE_FONT: &str = indoc! {r#"
+	STARTFONT 2.1
+	FONT -Test-Console
+	SIZE 8 72 72
+	FONTBOUNDINGBOX 8 8 0 -2
+	STARTPROPERTIES 2
+	FONT_ASCENT 6
+	FONT_DESCENT 2
+	ENDPROPERTIES
+	CHARS 5
+	STARTCHAR space
+	ENCODING 32
+	SWIDTH 1000 0
+	DWIDTH 8 0
+	BBX 0 0 0 0
+	BITMAP
+	ENDCHAR
+	STARTCHAR period
+	ENCODING 46
+	SWIDTH 1000 0
+	DWIDTH 8 0
+	BBX 2 2 3 0
+	BITMAP
+	C0
+	C0
+	ENDCHAR
+	STARTCHAR A
+	ENCODING 65
+	SWIDTH 1000 0
+	DWIDTH 8 0
+	BBX 8 6 0 0
+	BITMAP
+	18
+	24
+	42
+	7E
+	42
+	42
+	ENDCHAR
+	STARTCHAR Aring
+	ENCODING 197
+	SWIDTH 1000 0
+	DWIDTH 8 0
+	BBX 8 8 0 -2
+	BITMAP
+	18
+	00
+	18
+	24
+	42
+	7E
+	42
+	42
+	ENDCHAR
+	STARTCHAR Alpha
+	ENCODING 913
+	SWIDTH 1000 0
+	DWIDTH 8 0
+	BBX 8 6 0 0
+	BITMAP
+	18
+	24
+	42
+	7E
+	42
+	42
+	ENDCHAR
+	ENDFONT
+"#};
+
+/// Render a glyph into the 8x8 character cell of the console font.
+fn cell(glyph: &Glyph) -> BitmapBuf {
+	let mut buf = BitmapBuf::new(BoundingBox {
+		width: 8,
+		height: 8,
+		offset_x: 0,
+		offset_y: -2
+	});
+	buf.draw(glyph.bitmap(), 0, 0);
+	buf
+}
+
+#[test]
+fn psf_round_trip() {
+	let font = Font::read(CONSOLE_FONT.as_bytes()).unwrap();
+	for version in [PsfVersion::Psf1, PsfVersion::Psf2] {
+		let options = PsfOptions {
+			version,
+			..Default::default()
+		};
+		let mut psf = Vec::new();
+		font.write_psf(&mut psf, &options).unwrap();
+		let read = Font::read_psf(psf.as_slice()).unwrap();
+
+		assert_eq!(read.bounding_box(), font.bounding_box());
+		let encodings: Vec<u32> =
+			read.glyphs().into_iter().map(Glyph::encoding).collect();
+		assert_eq!(encodings, vec![32, 46, 65, 197, 913]);
+		for glyph in read.glyphs() {
+			let expected = font.glyph(glyph.encoding()).unwrap();
+			assert_eq!(cell(glyph), cell(expected), "{version:?} {}", glyph.name());
+			assert_eq!(glyph.dwidth(), Some((8.0, 0.0)));
+		}
+	}
+
+	// A and Alpha share a glyph, and Aring has a sequence with a combining ring
+	let mut psf = Vec::new();
+	font.write_psf(&mut psf, &PsfOptions::default()).unwrap();
+	assert_eq!(psf[16 .. 20], 4u32.to_le_bytes());
+	let table = &psf[32 + 4 * 8 ..];
+	assert_eq!(table, b" \xff.\xffA\xce\x91\xff\xc3\x85\xfeA\xcc\x8a\xff");
+}
+
+#[test]
+fn read_psf1_sequences() {
+	let mut psf = vec![0x36, 0x04, 0x06, 1];
+	psf.extend((0 .. 256).map(|i| i as u8));
+	let table: [&[u16]; 4] = [
+		&[0x41],
+		&[0xC5, 0xFFFE, 0x41, 0x30A],
+		&[0xFFFE, 0x65, 0x301],
+		&[0xFFFE, 0x78, 0x301]
+	];
+	for entry in table {
+		for value in entry.iter().chain(&[0xFFFF]) {
+			psf.extend(value.to_le_bytes());
+		}
+	}
+	for _ in table.len() .. 256 {
+		psf.extend(0xFFFFu16.to_le_bytes());
+	}
+
+	let font = Font::read_psf(psf.as_slice()).unwrap();
+	let glyphs: Vec<(u32, String)> = font
+		.glyphs()
+		.into_iter()
+		.map(|glyph| (glyph.encoding(), glyph.bitmap().ascii_art()))
+		.collect();
+	assert_eq!(glyphs, vec![
+		(0x41, "................\n----------------\n".to_owned()),
+		(0xC5, "..............##\n----------------\n".to_owned()),
+		(0xE9, "............##..\n----------------\n".to_owned())
+	]);
+}
+
+#[test]
+fn write_psf_invalid() {
+	let font = sample_font();
+	let err = font
+		.write_psf(Vec::new(), &PsfOptions::default())
+		.unwrap_err();
+	assert!(matches!(err, PsfError::ProportionalFont(105)));
+
+	let font = Font::read(
+		CONSOLE_FONT
+			.replace("BBX 2 2 3 0", "BBX 2 2 7 0")
+			.as_bytes()
+	)
+	.unwrap();
+	let err = font
+		.write_psf(Vec::new(), &PsfOptions::default())
+		.unwrap_err();
+	assert!(matches!(err, PsfError::GlyphOutsideCell(46)));
+
+	let err = Font::read_psf(&b"\x36\x04\x00\x10"[..]);
+	assert!(matches!(err, Err(PsfError::UnexpectedEof)));
+}
+
+#[test]
+fn read_psf_huge_count() {
+	// a header with a unicode table and empty glyphs, claiming 2^32-1 glyphs
+	let mut psf = b"\x72\xb5\x4a\x86".to_vec();
+	for value in [0u32, 32, 1, u32::MAX, 0, 0, 0] {
+		psf.extend_from_slice(&value.to_le_bytes());
+	}
+	let err = Font::read_psf(psf.as_slice());
+	assert!(matches!(err, Err(PsfError::UnexpectedEof)));
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use bdf_reader::{BoundingBox, DistanceField, Font, Glyph, SdfOptions};
+use common::sample_font;
+use pretty_assertions::assert_eq;
+
+/// Check whether the center of a distance field pixel is inside of the glyph.
+fn is_ink(glyph: &Glyph, field: &DistanceField, x: usize, y: usize, scale: u32) -> bool {
+	let bbox = glyph.bounding_box();
+	let field_bbox = field.bounding_box();
+	let fx = field_bbox.offset_x + x as i32;
+	let fy = field_bbox.offset_y + field_bbox.height as i32 - 1 - y as i32;
+	let (gx, gy) = (fx.div_euclid(scale as i32), fy.div_euclid(scale as i32));
+	let col = gx - bbox.offset_x;
+	let row = bbox.offset_y + bbox.height as i32 - 1 - gy;
+	(0 .. bbox.width as i32).contains(&col)
+		&& (0 .. bbox.height as i32).contains(&row)
+		&& glyph
+			.bitmap()
+			.get(col as usize, row as usize)
+			.unwrap_or(false)
+}
+
+/// A font whose only glyph keeps the padding bits beyond its width of 3 pixels.
+fn padded_font() -> Font {
+	let bdf = "STARTFONT 2.1\nFONT -Test-Padded\nSIZE 8 75 75\nFONTBOUNDINGBOX 3 2 0 0\n\
+	           CHARS 1\nSTARTCHAR block\nENCODING 65\nSWIDTH 500 0\nDWIDTH 4 0\n\
+	           BBX 3 2 0 0\nBITMAP\nFF\nFF\nENDCHAR\nENDFONT\n";
+	Font::read(bdf.as_bytes()).unwrap()
+}
+
+#[test]
+fn distance_field() {
+	let font = sample_font();
+	let glyph = font.glyph('i').unwrap();
+	let field = glyph.distance_field(&SdfOptions::default());
+	assert_eq!(field.bounding_box(), BoundingBox {
+		width: 12,
+		height: 28,
+		offset_x: 0,
+		offset_y: -4
+	});
+	assert_eq!(field.advance(), 16);
+	assert_eq!(field.channels(), 1);
+	assert_eq!(field.data().len(), 12 * 28);
+
+	// inside the stem, 1.5 pixels away from its left edge
+	assert_eq!(field.get(5, 21), Some(&[175][..]));
+	// inside the dot, half a pixel away from its top edge
+	assert_eq!(field.get(5, 4), Some(&[143][..]));
+	// far away from the outline
+	assert_eq!(field.get(0, 27), Some(&[0][..]));
+	// between the dot and the stem
+	assert_eq!(field.get(5, 9), Some(&[80][..]));
+	assert_eq!(field.get(12, 0), None);
+}
+
+#[test]
+fn distance_field_sign() {
+	for (font, multi_channel) in [
+		(sample_font(), false),
+		(sample_font(), true),
+		(padded_font(), false),
+		(padded_font(), true)
+	] {
+		let options = SdfOptions {
+			spread: 3,
+			upsampling: 3,
+			multi_channel
+		};
+		for glyph in font.glyphs() {
+			let field = glyph.distance_field(&options);
+			for y in 0 .. field.height() as usize {
+				for x in 0 .. field.width() as usize {
+					let mut value = field.get(x, y).unwrap().to_vec();
+					value.sort_unstable();
+					assert_eq!(
+						value[value.len() / 2] >= 128,
+						is_ink(glyph, &field, x, y, 3),
+						"{} at {x},{y}",
+						glyph.name()
+					);
+				}
+			}
+		}
+	}
+}
+
+#[test]
+fn distance_field_empty() {
+	let font = sample_font();
+	let options = SdfOptions {
+		multi_channel: true,
+		..Default::default()
+	};
+	let field = font.glyph(' ').unwrap().distance_field(&options);
+	assert_eq!(field.width(), 40);
+	assert_eq!(field.channels(), 3);
+	assert!(field.data().iter().all(|value| *value == 0));
+}
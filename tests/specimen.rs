@@ -0,0 +1,71 @@
+mod common;
+
+use bdf_reader::SpecimenOptions;
+use common::sample_font;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn specimen() {
+	let font = sample_font();
+	let options = SpecimenOptions {
+		columns: 5,
+		sample_text: "Aai".into(),
+		..Default::default()
+	};
+	let sheet = font.specimen(&options);
+	let bbox = sheet.bounding_box();
+	assert_eq!((bbox.width, bbox.height), (104, 52));
+	let sheet = sheet.as_bitmap();
+
+	// the header starts in the top left corner, inside the spacing
+	let header = font.layout("Aai").render();
+	let header = header.as_bitmap();
+	for y in 0 .. header.height() {
+		for x in 0 .. header.width() {
+			assert_eq!(sheet.get(x + 4, y + 4).unwrap(), header.get(x, y).unwrap());
+		}
+	}
+
+	// the glyph A is in the second cell, aligned like in a glyph grid
+	let grid = font.glyph_grid(9, 0);
+	let grid = grid.as_bitmap();
+	for y in 0 .. 8 {
+		for x in 0 .. 16 {
+			assert_eq!(
+				sheet.get(x + 24, y + 16).unwrap(),
+				grid.get(x + 16, y).unwrap()
+			);
+		}
+	}
+
+	// the first cell is labelled 0020
+	let row: String = (4 .. 19)
+		.map(|x| match sheet.get(x, 25).unwrap() {
+			true => '#',
+			false => '.'
+		})
+		.collect();
+	assert_eq!(row, "###.###.###.###");
+	let row: String = (4 .. 19)
+		.map(|x| match sheet.get(x, 27).unwrap() {
+			true => '#',
+			false => '.'
+		})
+		.collect();
+	assert_eq!(row, "#.#.#.#.###.#.#");
+}
+
+#[test]
+fn specimen_range() {
+	let font = sample_font();
+	let options = SpecimenOptions {
+		range: Some(0x61 ..= 0x69),
+		names: true,
+		sample_text: String::new(),
+		spacing: 1,
+		..Default::default()
+	};
+	let bbox = font.specimen(&options).bounding_box();
+	// two cells side by side, each with the font bounding box and two label lines
+	assert_eq!((bbox.width, bbox.height), (2 * 16 + 3, 8 + 12 + 2));
+}
@@ -0,0 +1,209 @@
+#![cfg(feature = "sprite-sheet")]
+
+mod common;
+
+use bdf_reader::{BoundingBox, Font, Glyph, SpriteSheetError, SpriteSheetOptions};
+use common::sample_font;
+use pretty_assertions::assert_eq;
+use std::{collections::BTreeSet, fs, path::PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+	let dir =
+		std::env::temp_dir().join(format!("bdf-reader-{name}-{}", std::process::id()));
+	fs::create_dir_all(&dir).unwrap();
+	dir
+}
+
+/// Get the ink of a glyph in coordinates relative to its origin, independent of its
+/// bounding box.
+fn ink(glyph: &Glyph) -> BTreeSet<(i32, i32)> {
+	let bitmap = glyph.bitmap();
+	let bbox = glyph.bounding_box();
+	let mut ink = BTreeSet::new();
+	for y in 0 .. bitmap.height() {
+		for x in 0 .. bitmap.width() {
+			if bitmap.get(x, y).unwrap() {
+				ink.insert((
+					bbox.offset_x + x as i32,
+					bbox.offset_y + bbox.height as i32 - 1 - y as i32
+				));
+			}
+		}
+	}
+	ink
+}
+
+fn assert_same_glyphs(read: &Font, font: &Font) {
+	let glyphs: Vec<_> = font.glyphs().into_iter().collect();
+	let read_glyphs: Vec<_> = read.glyphs().into_iter().collect();
+	assert_eq!(read_glyphs.len(), glyphs.len());
+	for (read, glyph) in read_glyphs.into_iter().zip(glyphs) {
+		assert_eq!(read.encoding(), glyph.encoding());
+		assert_eq!(read.name(), glyph.name());
+		assert_eq!(read.swidth(), glyph.swidth());
+		assert_eq!(read.dwidth(), glyph.dwidth());
+		assert_eq!(ink(read), ink(glyph), "{}", glyph.name());
+	}
+}
+
+#[test]
+fn sprite_sheet_round_trip() {
+	let font = sample_font();
+	let dir = temp_dir("sprite");
+	let path = dir.join("sample.json");
+	let options = SpriteSheetOptions {
+		columns: 4,
+		padding: 2,
+		spacing: 1,
+		..Default::default()
+	};
+	font.write_sprite_sheet(&path, &options).unwrap();
+	let sidecar = fs::read_to_string(&path).unwrap();
+	let lines: Vec<&str> = sidecar.lines().take(8).collect();
+	assert_eq!(lines, vec![
+		"{",
+		"\t\"image\": \"sample.tga\",",
+		"\t\"columns\": 4,",
+		"\t\"cell_width\": 20,",
+		"\t\"cell_height\": 12,",
+		"\t\"spacing\": 1,",
+		"\t\"origin_x\": 10,",
+		"\t\"origin_y\": 8,"
+	]);
+
+	let read = Font::read_sprite_sheet(&path).unwrap();
+	assert_eq!(read.name(), font.name());
+	assert_eq!(read.size(), font.size());
+	assert_eq!(read.property("FAMILY_NAME"), font.property("FAMILY_NAME"));
+	assert_eq!(read.property("FONT_ASCENT"), font.property("FONT_ASCENT"));
+	assert_same_glyphs(&read, &font);
+
+	// bounding boxes are tight around the ink
+	assert_eq!(
+		read.glyph(' ').unwrap().bounding_box(),
+		BoundingBox::default()
+	);
+	assert_eq!(
+		read.glyph('a').unwrap().bounding_box(),
+		font.glyph('a').unwrap().bounding_box()
+	);
+
+	// draw a pixel above the dot of the i, which is in the fourth cell
+	let mut tga = fs::read(dir.join("sample.tga")).unwrap();
+	let (x, y) = (3 * 21 + 10 + 1, 8 - 7);
+	let idx = 18 + (y * (4 * 21 - 1) + x) * 4;
+	tga[idx .. idx + 4].copy_from_slice(&[0x20, 0x20, 0x20, 0xFF]);
+	fs::write(dir.join("sample.tga"), tga).unwrap();
+	let read = Font::read_sprite_sheet(&path).unwrap();
+	let i = read.glyph('i').unwrap();
+	assert_eq!(i.bounding_box(), BoundingBox {
+		width: 1,
+		height: 7,
+		offset_x: 1,
+		offset_y: 0
+	});
+	assert_eq!(i.bitmap().ascii_art(), "##\n..\n##\n..\n##\n##\n##\n--\n");
+	fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(feature = "png")]
+#[test]
+fn sprite_sheet_png() {
+	let font = sample_font();
+	let dir = temp_dir("sprite-png");
+	let path = dir.join("sample.json");
+	let options = SpriteSheetOptions {
+		image: bdf_reader::SpriteSheetImage::Png,
+		..Default::default()
+	};
+	font.write_sprite_sheet(&path, &options).unwrap();
+	assert!(dir.join("sample.png").exists());
+	assert_same_glyphs(&Font::read_sprite_sheet(&path).unwrap(), &font);
+	fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn read_sprite_sheet_invalid() {
+	let font = sample_font();
+	let dir = temp_dir("sprite-invalid");
+	let path = dir.join("sample.json");
+	font.write_sprite_sheet(&path, &SpriteSheetOptions::default())
+		.unwrap();
+	let sidecar = fs::read_to_string(&path).unwrap();
+
+	fs::write(
+		&path,
+		sidecar.replacen("\"columns\": 9,", "\"columns\": 9", 1)
+	)
+	.unwrap();
+	let result = Font::read_sprite_sheet(&path);
+	assert!(matches!(result, Err(SpriteSheetError::SyntaxError(4))));
+
+	fs::write(
+		&path,
+		sidecar.replacen("\"columns\": 9,", "\"columns\": 3,", 1)
+	)
+	.unwrap();
+	let result = Font::read_sprite_sheet(&path);
+	assert!(matches!(
+		result,
+		Err(SpriteSheetError::CellOutsideImage(105))
+	));
+
+	fs::write(&path, sidecar.replacen("\"image\"", "\"picture\"", 1)).unwrap();
+	let result = Font::read_sprite_sheet(&path);
+	assert!(matches!(result, Err(SpriteSheetError::Missing("image"))));
+
+	for image in ["../sample.tga", "/etc/passwd", ""] {
+		let escape = sidecar.replacen("\"sample.tga\"", &format!("\"{image}\""), 1);
+		fs::write(&path, escape).unwrap();
+		let result = Font::read_sprite_sheet(&path);
+		assert!(matches!(
+			result,
+			Err(SpriteSheetError::InvalidValue("image"))
+		));
+	}
+
+	// cell positions that overflow
+	let huge = "4611686018427387904";
+	let overflow = sidecar
+		.replacen("\"spacing\": 0,", &format!("\"spacing\": {huge},"), 1)
+		.replacen("\"cell\": 1,", &format!("\"cell\": {huge},"), 1);
+	fs::write(&path, overflow).unwrap();
+	let result = Font::read_sprite_sheet(&path);
+	assert!(matches!(
+		result,
+		Err(SpriteSheetError::CellOutsideImage(65))
+	));
+
+	let origin = sidecar.replacen("\"origin_x\": 8,", "\"origin_x\": -2147483648,", 1);
+	fs::write(&path, origin).unwrap();
+	let result = Font::read_sprite_sheet(&path);
+	assert!(matches!(
+		result,
+		Err(SpriteSheetError::InvalidValue("origin_x"))
+	));
+
+	let nested = format!("[{}{}],", "[".repeat(100_000), "]".repeat(100_000));
+	fs::write(
+		&path,
+		sidecar.replacen("\"columns\": 9,", &format!("\"columns\": {nested}"), 1)
+	)
+	.unwrap();
+	let result = Font::read_sprite_sheet(&path);
+	assert!(matches!(result, Err(SpriteSheetError::SyntaxError(3))));
+	fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn write_sprite_sheet_too_large() {
+	let font = sample_font();
+	let dir = temp_dir("sprite-large");
+	let options = SpriteSheetOptions {
+		padding: u32::MAX,
+		..Default::default()
+	};
+	let result = font.write_sprite_sheet(dir.join("sample.json"), &options);
+	assert!(matches!(result, Err(SpriteSheetError::ImageTooLarge)));
+	fs::remove_dir_all(dir).unwrap();
+}
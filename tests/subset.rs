@@ -0,0 +1,50 @@
+mod common;
+
+use bdf_reader::{BoundingBox, CharSet, Font, Value};
+use common::sample_font;
+use pretty_assertions::assert_eq;
+
+fn encodings(font: &Font) -> Vec<u32> {
+	font.glyphs()
+		.into_iter()
+		.map(|glyph| glyph.encoding())
+		.collect()
+}
+
+#[test]
+fn subset_from_text() {
+	let font = sample_font();
+	let subset = font.subset(&CharSet::from_text("iä"));
+	assert_eq!(encodings(&subset), [0x61, 0x69, 0x308, 0xFFFD]);
+	assert!(matches!(
+		subset.property("DEFAULT_CHAR"),
+		Some(Value::Integer(0xFFFD))
+	));
+	assert_eq!(subset.layout("ä").glyphs().len(), 2);
+}
+
+#[test]
+fn subset_ranges_and_names() {
+	let font = sample_font();
+	let mut chars = CharSet::new();
+	chars.insert_range(0x300 ..= 0x36F).insert_name("A");
+	let subset = font.subset(&chars);
+	assert_eq!(encodings(&subset), [0x41, 0x300, 0x301, 0x308, 0xFFFD]);
+}
+
+#[test]
+fn subset_recomputes_bounding_box() {
+	let bdf = std::fs::read_to_string("tests/sample.bdf")
+		.unwrap()
+		.replace("DEFAULT_CHAR 65533", "DEFAULT_CHAR 0");
+	let font = Font::read(bdf.as_bytes()).expect("Failed to parse font");
+	let subset = font.subset(&['i'].into_iter().collect());
+	assert_eq!(encodings(&subset), [0x69]);
+	assert!(subset.property("DEFAULT_CHAR").is_none());
+	assert_eq!(subset.bounding_box(), BoundingBox {
+		width: 1,
+		height: 5,
+		offset_x: 1,
+		offset_y: 0
+	});
+}
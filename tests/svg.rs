@@ -0,0 +1,89 @@
+mod common;
+
+use bdf_reader::{Font, SvgOptions};
+use common::sample_font;
+use indoc::indoc;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn write_svg() {
+	let font = sample_font();
+	let mut buf = Vec::new();
+	font.glyph('A')
+		.unwrap()
+		.bitmap()
+		.write_svg(&mut buf, &SvgOptions::default())
+		.unwrap();
+	assert_eq!(String::from_utf8(buf).unwrap(), indoc! {r##"
+		<svg xmlns="http://www.w3.org/2000/svg" width="64" height="64" viewBox="0 -6 8 8">
+		<path d="M1 0V-3H2V-2H6V-3H7V0H6V-1H2V0ZM2 -3V-4H3V-3ZM3 -4V-5H5V-4ZM5 -3V-4H6V-3Z" fill="#000"/>
+		</svg>
+	"##});
+}
+
+#[test]
+fn write_svg_padding_bits() {
+	// the rows keep the padding bits beyond the width of 3 pixels
+	let bdf = "STARTFONT 2.1\nFONT -Test-Padded\nSIZE 8 75 75\nFONTBOUNDINGBOX 3 2 0 0\n\
+	           CHARS 1\nSTARTCHAR block\nENCODING 65\nSWIDTH 500 0\nDWIDTH 4 0\n\
+	           BBX 3 2 0 0\nBITMAP\nFF\nFF\nENDCHAR\nENDFONT\n";
+	let font = Font::read(bdf.as_bytes()).unwrap();
+	let mut buf = Vec::new();
+	font.glyph('A')
+		.unwrap()
+		.bitmap()
+		.write_svg(&mut buf, &SvgOptions::default())
+		.unwrap();
+	assert_eq!(String::from_utf8(buf).unwrap(), indoc! {r##"
+		<svg xmlns="http://www.w3.org/2000/svg" width="24" height="16" viewBox="0 -2 3 2">
+		<path d="M0 0V-2H3V0Z" fill="#000"/>
+		</svg>
+	"##});
+}
+
+#[test]
+fn write_svg_overlays() {
+	let font = sample_font();
+	let options = SvgOptions {
+		scale: 4,
+		foreground: "currentColor".into(),
+		background: Some("#fff".into()),
+		grid: true,
+		baseline: true,
+		bounding_box: true
+	};
+	let mut buf = Vec::new();
+	font.glyph('i')
+		.unwrap()
+		.bitmap()
+		.write_svg(&mut buf, &options)
+		.unwrap();
+	assert_eq!(String::from_utf8(buf).unwrap(), indoc! {r##"
+		<svg xmlns="http://www.w3.org/2000/svg" width="4" height="20" viewBox="1 -5 1 5">
+		<rect x="1" y="-5" width="1" height="5" fill="#fff"/>
+		<path d="M1 0V-3H2V0ZM1 -4V-5H2V-4Z" fill="currentColor"/>
+		<path d="M1 -5V0M2 -5V0M1 -5H2M1 -4H2M1 -3H2M1 -2H2M1 -1H2M1 0H2" fill="none" stroke="#ccc" stroke-width="1" vector-effect="non-scaling-stroke"/>
+		<path d="M1 0H2" fill="none" stroke="#f00" stroke-width="1" vector-effect="non-scaling-stroke"/>
+		<rect x="1" y="-5" width="1" height="5" fill="none" stroke="#00f" stroke-width="1" vector-effect="non-scaling-stroke"/>
+		</svg>
+	"##});
+}
+
+#[test]
+fn write_layout_svg() {
+	let font = sample_font();
+	let options = SvgOptions {
+		bounding_box: true,
+		..Default::default()
+	};
+	let mut buf = Vec::new();
+	font.layout("ai").write_svg(&mut buf, &options).unwrap();
+	let svg = String::from_utf8(buf).unwrap();
+	let lines: Vec<&str> = svg.lines().collect();
+	assert_eq!(lines.len(), 5);
+	assert!(lines[0].ends_with("viewBox=\"1 -5 9 5\">"));
+	// one path for all glyphs, and one bounding box per glyph
+	assert!(lines[1].starts_with("<path d=\"M1 -1V-2H5V-3H6V0H2V-1Z"));
+	assert!(lines[2].starts_with("<rect x=\"1\" y=\"-4\" width=\"5\" height=\"4\""));
+	assert!(lines[3].starts_with("<rect x=\"9\" y=\"-5\" width=\"1\" height=\"5\""));
+}
@@ -0,0 +1,163 @@
+mod common;
+
+use bdf_reader::{Font, SfntError};
+use common::sample_font;
+use pretty_assertions::assert_eq;
+
+fn u16_at(data: &[u8], pos: usize) -> u16 {
+	u16::from_be_bytes([data[pos], data[pos + 1]])
+}
+
+fn i16_at(data: &[u8], pos: usize) -> i16 {
+	u16_at(data, pos) as i16
+}
+
+fn u32_at(data: &[u8], pos: usize) -> u32 {
+	u32::from_be_bytes(data[pos .. pos + 4].try_into().unwrap())
+}
+
+fn table<'a>(data: &'a [u8], tag: &[u8; 4]) -> &'a [u8] {
+	let num_tables = u16_at(data, 4) as usize;
+	(0 .. num_tables)
+		.map(|i| 12 + 16 * i)
+		.find(|record| &data[*record .. record + 4] == tag)
+		.map(|record| {
+			let offset = u32_at(data, record + 8) as usize;
+			&data[offset .. offset + u32_at(data, record + 12) as usize]
+		})
+		.unwrap()
+}
+
+/// Decode the contours of a simple glyph without instructions.
+fn contours(glyph: &[u8]) -> Vec<Vec<(i32, i32)>> {
+	let num_contours = i16_at(glyph, 0) as usize;
+	let ends: Vec<usize> = (0 .. num_contours)
+		.map(|i| u16_at(glyph, 10 + 2 * i) as usize)
+		.collect();
+	let num_points = ends.last().map_or(0, |end| end + 1);
+	let mut pos = 10 + 2 * num_contours;
+	assert_eq!(u16_at(glyph, pos), 0);
+	pos += 2;
+	let flags = &glyph[pos .. pos + num_points];
+	pos += num_points;
+
+	let mut coordinates = |short: u8, same: u8| {
+		let mut value = 0;
+		let mut values = Vec::new();
+		for flag in flags {
+			assert_eq!(flag & 0x08, 0, "repeated flags are not used");
+			value += match (flag & short != 0, flag & same != 0) {
+				(true, positive) => {
+					let delta = glyph[pos] as i32;
+					pos += 1;
+					match positive {
+						true => delta,
+						false => -delta
+					}
+				},
+				(false, true) => 0,
+				(false, false) => {
+					pos += 2;
+					i16_at(glyph, pos - 2) as i32
+				}
+			};
+			values.push(value);
+		}
+		values
+	};
+	let xs = coordinates(0x02, 0x10);
+	let ys = coordinates(0x04, 0x20);
+	let mut points = xs.into_iter().zip(ys);
+	let mut start = 0;
+	ends.iter()
+		.map(|end| {
+			let contour = points.by_ref().take(end + 1 - start).collect();
+			start = end + 1;
+			contour
+		})
+		.collect()
+}
+
+#[test]
+fn write_ttf() {
+	let font = sample_font();
+	let mut ttf = Vec::new();
+	font.write_ttf(&mut ttf).unwrap();
+
+	// .notdef followed by the 9 glyphs of the font
+	let maxp = table(&ttf, b"maxp");
+	assert_eq!(u32_at(maxp, 0), 0x0001_0000);
+	assert_eq!(u16_at(maxp, 4), 10);
+	assert_eq!(u16_at(table(&ttf, b"head"), 18), 1024);
+	assert_eq!(i16_at(table(&ttf, b"head"), 50), 0);
+
+	let loca = table(&ttf, b"loca");
+	let glyf = table(&ttf, b"glyf");
+	let glyph = |gid: usize| {
+		let start = u16_at(loca, 2 * gid) as usize * 2;
+		let end = u16_at(loca, 2 * gid + 2) as usize * 2;
+		&glyf[start .. end]
+	};
+
+	// the space is empty
+	assert!(glyph(1).is_empty());
+
+	// the replacement character is a square with a square hole, 128 units per pixel
+	let replacement = glyph(9);
+	assert_eq!(
+		(1 .. 5)
+			.map(|i| i16_at(replacement, 2 * i))
+			.collect::<Vec<_>>(),
+		vec![0, -256, 1024, 768]
+	);
+	assert_eq!(contours(replacement), vec![
+		vec![(0, -256), (0, 768), (1024, 768), (1024, -256)],
+		vec![(128, -128), (896, -128), (896, 640), (128, 640)]
+	]);
+
+	// the dot of i is separate from its stem, and the left side bearing matches
+	assert_eq!(contours(glyph(4)), vec![
+		vec![(128, 0), (128, 384), (256, 384), (256, 0)],
+		vec![(128, 512), (128, 640), (256, 640), (256, 512)]
+	]);
+	let hmtx = table(&ttf, b"hmtx");
+	assert_eq!(u16_at(hmtx, 4 * 4), 512);
+	assert_eq!(i16_at(hmtx, 4 * 4 + 2), 128);
+}
+
+#[test]
+fn write_ttf_out_of_range() {
+	// both pixels are within 16 bits of font units, but not the distance between them
+	let bdf = format!(
+		"STARTFONT 2.1\nFONT -Test-Wide\nSIZE 8 75 75\nFONTBOUNDINGBOX 400 1 -200 0\n\
+		 STARTPROPERTIES 1\nPIXEL_SIZE 8\nENDPROPERTIES\nCHARS 1\nSTARTCHAR wide\n\
+		 ENCODING 65\nSWIDTH 1000 0\nDWIDTH 8 0\nBBX 400 1 -200 0\nBITMAP\n80{}01\n\
+		 ENDCHAR\nENDFONT\n",
+		"00".repeat(48)
+	);
+	let font = Font::read(bdf.as_bytes()).unwrap();
+	let err = font.write_ttf(Vec::new()).unwrap_err();
+	assert!(matches!(err, SfntError::MetricsOutOfRange(65)));
+}
+
+#[test]
+fn write_ttf_padding_bits() {
+	// the rows keep the padding bits beyond the width of 3 pixels
+	let bdf = "STARTFONT 2.1\nFONT -Test-Padded\nSIZE 8 75 75\nFONTBOUNDINGBOX 3 2 0 0\n\
+	           STARTPROPERTIES 1\nPIXEL_SIZE 8\nENDPROPERTIES\nCHARS 1\nSTARTCHAR block\n\
+	           ENCODING 65\nSWIDTH 500 0\nDWIDTH 4 0\nBBX 3 2 0 0\nBITMAP\nFF\nFF\n\
+	           ENDCHAR\nENDFONT\n";
+	let font = Font::read(bdf.as_bytes()).unwrap();
+	let mut ttf = Vec::new();
+	font.write_ttf(&mut ttf).unwrap();
+
+	let loca = table(&ttf, b"loca");
+	let glyf = table(&ttf, b"glyf");
+	let glyph = &glyf[u16_at(loca, 2) as usize * 2 .. u16_at(loca, 4) as usize * 2];
+	assert_eq!(contours(glyph), vec![vec![
+		(0, 0),
+		(0, 256),
+		(384, 256),
+		(384, 0)
+	]]);
+}